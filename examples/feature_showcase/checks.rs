@@ -103,6 +103,13 @@ pub async fn cooldowns(ctx: Context<'_>) -> Result<(), Error> {
     Ok(())
 }
 
+/// Rate limited to five uses per minute per guild member, with bursts of up to eight uses
+#[poise::command(prefix_command, slash_command, member_cooldown = "5/60s burst 8")]
+pub async fn rate_limited(ctx: Context<'_>) -> Result<(), Error> {
+    ctx.say("You successfully called the command").await?;
+    Ok(())
+}
+
 #[poise::command(prefix_command, slash_command)]
 pub async fn minmax(
     ctx: Context<'_>,
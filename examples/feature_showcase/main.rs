@@ -43,6 +43,7 @@ async fn main() {
                 checks::delete(),
                 checks::ferrisparty(),
                 checks::cooldowns(),
+                checks::rate_limited(),
                 checks::minmax(),
                 checks::get_guild_name(),
                 checks::only_in_dms(),
@@ -57,12 +58,15 @@ async fn main() {
                 localization::welcome(),
                 modal::modal(),
                 paginate::paginate(),
+                paginate::paginate_lazy(),
                 panic_handler::div(),
                 parameter_attributes::addmultiple(),
                 parameter_attributes::voiceinfo(),
                 parameter_attributes::say(),
                 parameter_attributes::punish(),
                 parameter_attributes::stringlen(),
+                parameter_attributes::nickname(),
+                parameter_attributes::warn(),
                 // raw_identifiers::r#move(), // Currently doesn't work (issue #170)
                 response_with_reply::reply(),
                 subcommands::parent(),
@@ -13,3 +13,21 @@ pub async fn paginate(ctx: Context<'_>) -> Result<(), Error> {
 
     Ok(())
 }
+
+/// Flip through a thousand pages which are only built when they're shown
+#[poise::command(slash_command, prefix_command)]
+pub async fn paginate_lazy(ctx: Context<'_>) -> Result<(), Error> {
+    poise::builtins::Paginator::lazy(1000, |page| {
+        Box::pin(async move {
+            poise::CreateReply::default().embed(
+                poise::serenity_prelude::CreateEmbed::default()
+                    .title(format!("Page {}", page + 1))
+                    .description(format!("Square of {} is {}", page, page * page)),
+            )
+        })
+    })
+    .run(ctx)
+    .await?;
+
+    Ok(())
+}
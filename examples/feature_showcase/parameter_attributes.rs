@@ -98,3 +98,46 @@ pub async fn stringlen(
     ctx.say(format!("you wrote: {}", s)).await?;
     Ok(())
 }
+
+fn validate_not_reserved(name: &str) -> Result<(), String> {
+    match name {
+        "admin" | "root" => Err(format!("`{}` is reserved", name)),
+        _ => Ok(()),
+    }
+}
+
+/// Demonstrates `#[regex]` and `#[validate]`, which are checked in prefix commands too
+#[poise::command(prefix_command, slash_command)]
+pub async fn nickname(
+    ctx: Context<'_>,
+    #[description = "Lowercase letters, digits and underscores"]
+    #[regex = "[a-z0-9_]+"]
+    #[validate = "validate_not_reserved"]
+    name: String,
+) -> Result<(), Error> {
+    ctx.say(format!("Your nickname is now {}", name)).await?;
+    Ok(())
+}
+
+/// Parameters shared by moderation commands
+#[derive(poise::ParameterGroup)]
+pub struct Punishment {
+    #[description = "User to punish"]
+    user: serenity::User,
+    #[description = "Reason shown in the audit log"]
+    #[max_length = 100]
+    #[rest]
+    reason: Option<String>,
+}
+
+/// Demonstrates `#[flatten]`, which turns the fields of a parameter group into parameters
+#[poise::command(prefix_command, slash_command)]
+pub async fn warn(ctx: Context<'_>, #[flatten] punishment: Punishment) -> Result<(), Error> {
+    let reason = punishment.reason.as_deref().unwrap_or("no reason");
+    ctx.say(format!(
+        "{} has been warned: {}",
+        punishment.user.name, reason
+    ))
+    .await?;
+    Ok(())
+}
@@ -1,11 +1,7 @@
-mod translation;
-
 use poise::serenity_prelude as serenity;
-use translation::tr;
+use poise::tr;
 
-pub struct Data {
-    translations: translation::Translations,
-}
+pub struct Data;
 
 type Error = Box<dyn std::error::Error + Send + Sync>;
 type Context<'a> = poise::Context<'a, Data, Error>;
@@ -58,9 +54,8 @@ async fn register(ctx: Context<'_>) -> Result<(), Error> {
 async fn main() {
     env_logger::init();
 
-    let mut commands = vec![welcome(), info(), register()];
-    let translations = translation::read_ftl().expect("failed to read translation files");
-    translation::apply_translations(&translations, &mut commands);
+    let translations = poise::localization::Translations::load("translations", "en-US")
+        .expect("failed to read translation files");
 
     serenity::Client::builder(
         std::env::var("TOKEN").unwrap(),
@@ -68,10 +63,11 @@ async fn main() {
     )
     .framework(poise::Framework::new(
         poise::FrameworkOptions {
-            commands,
+            commands: vec![welcome(), info(), register()],
+            translations: Some(translations),
             ..Default::default()
         },
-        move |_, _, _| Box::pin(async move { Ok(Data { translations }) }),
+        move |_, _, _| Box::pin(async move { Ok(Data) }),
     ))
     .await
     .unwrap()
@@ -1,10 +1,17 @@
-mod prefix;
-mod slash;
+pub mod prefix;
+pub mod slash;
 
 use crate::util::wrap_option;
 use proc_macro::TokenStream;
 use syn::spanned::Spanned as _;
 
+/// Maximum number of options of a slash command, enforced by Discord
+const MAX_SLASH_OPTIONS: usize = 25;
+
+/// Number of slash command options registered for a `Vec<serenity::Attachment>` parameter without
+/// `#[max_count]`. Copy of `DEFAULT_MAX_COUNT` in poise's `attachment.rs`
+const DEFAULT_ATTACHMENT_OPTION_COUNT: usize = 3;
+
 /// Representation of the command attribute arguments (`#[command(...)]`)
 #[derive(Default, Debug, darling::FromMeta)]
 #[darling(default)]
@@ -47,42 +54,212 @@ pub struct CommandArgs {
     category: Option<String>,
     custom_data: Option<syn::Expr>,
 
-    // In seconds
-    global_cooldown: Option<u64>,
-    user_cooldown: Option<u64>,
-    guild_cooldown: Option<u64>,
-    channel_cooldown: Option<u64>,
-    member_cooldown: Option<u64>,
+    // In seconds, or a rate limit string like "5/60s"
+    global_cooldown: Option<CooldownSpec>,
+    user_cooldown: Option<CooldownSpec>,
+    guild_cooldown: Option<CooldownSpec>,
+    channel_cooldown: Option<CooldownSpec>,
+    member_cooldown: Option<CooldownSpec>,
+}
+
+/// Value of a `*_cooldown` attribute: either an integer number of seconds (`user_cooldown = 5`),
+/// or a rate limit string (`user_cooldown = "5/60s"`, `user_cooldown = "5/1m burst 8"`)
+#[derive(Debug, PartialEq)]
+struct CooldownSpec {
+    uses: u32,
+    window_millis: u64,
+    burst: u32,
+}
+
+impl darling::FromMeta for CooldownSpec {
+    fn from_value(value: &syn::Lit) -> darling::Result<Self> {
+        match value {
+            syn::Lit::Int(lit) => Ok(Self {
+                uses: 1,
+                window_millis: lit.base10_parse::<u64>()? * 1000,
+                burst: 1,
+            }),
+            syn::Lit::Str(lit) => parse_cooldown_spec(&lit.value())
+                .ok_or_else(|| {
+                    syn::Error::new(
+                        lit.span(),
+                        "expected cooldown like \"30s\", \"5/60s\" or \"5/60s burst 8\"",
+                    )
+                })
+                .map_err(darling::Error::from),
+            _ => Err(darling::Error::unexpected_lit_type(value)),
+        }
+    }
+}
+
+/// Parses a duration like `60s`, `5m` or `500ms`. Without unit, seconds are assumed
+fn parse_cooldown_duration_millis(s: &str) -> Option<u64> {
+    let s = s.trim();
+    let unit_start = s.find(|c: char| !c.is_ascii_digit()).unwrap_or(s.len());
+    let (number, unit) = s.split_at(unit_start);
+    let factor = match unit.trim() {
+        "ms" => 1,
+        "" | "s" => 1000,
+        "m" => 60 * 1000,
+        "h" => 60 * 60 * 1000,
+        "d" => 24 * 60 * 60 * 1000,
+        _ => return None,
+    };
+    number.parse::<u64>().ok()?.checked_mul(factor)
+}
+
+/// Parses a `*_cooldown` rate limit string, see [`CooldownSpec`]
+fn parse_cooldown_spec(s: &str) -> Option<CooldownSpec> {
+    let (rate, burst) = match s.split_once("burst") {
+        Some((rate, burst)) => (rate, Some(burst.trim().parse::<u32>().ok()?)),
+        None => (s, None),
+    };
+    let (uses, window) = match rate.split_once('/') {
+        Some((uses, window)) => (uses.trim().parse::<u32>().ok()?, window),
+        None => (1, rate),
+    };
+    if uses == 0 || burst == Some(0) {
+        return None;
+    }
+
+    Some(CooldownSpec {
+        uses,
+        window_millis: parse_cooldown_duration_millis(window)?,
+        burst: burst.unwrap_or(uses),
+    })
+}
+
+/// Converts an optional cooldown spec into an `Option<poise::CooldownRate>` expression
+fn cooldown_rate_tokens(spec: &Option<CooldownSpec>) -> proc_macro2::TokenStream {
+    match spec {
+        Some(CooldownSpec {
+            uses,
+            window_millis,
+            burst,
+        }) => quote::quote! { Some(::poise::CooldownRate {
+            uses: #uses,
+            window: std::time::Duration::from_millis(#window_millis),
+            burst: #burst,
+        }) },
+        None => quote::quote! { None },
+    }
 }
 
 /// Representation of the function parameter attribute arguments
 #[derive(Default, Debug, darling::FromMeta)]
 #[darling(default)]
-struct ParamArgs {
+pub struct ParamArgs {
     // When changing these, document it in parent file!
-    description: Option<String>,
-    rename: Option<String>,
+    pub description: Option<String>,
+    pub rename: Option<String>,
     #[darling(multiple)]
-    name_localized: Vec<crate::util::Tuple2<String>>,
+    pub name_localized: Vec<crate::util::Tuple2<String>>,
     #[darling(multiple)]
-    description_localized: Vec<crate::util::Tuple2<String>>,
-    autocomplete: Option<syn::Path>,
-    channel_types: Option<crate::util::List<syn::Ident>>,
-    min: Option<syn::Lit>,
-    max: Option<syn::Lit>,
-    min_length: Option<syn::Lit>,
-    max_length: Option<syn::Lit>,
-    lazy: bool,
-    flag: bool,
-    rest: bool,
+    pub description_localized: Vec<crate::util::Tuple2<String>>,
+    pub autocomplete: Option<syn::Path>,
+    pub channel_types: Option<crate::util::List<syn::Ident>>,
+    pub min: Option<syn::Lit>,
+    pub max: Option<syn::Lit>,
+    pub min_length: Option<syn::Lit>,
+    pub max_length: Option<syn::Lit>,
+    pub lazy: bool,
+    pub flag: bool,
+    pub rest: bool,
+    pub named: bool,
+    pub long: Option<String>,
+    pub short: Option<char>,
+    pub content_types: Option<crate::util::List<String>>,
+    pub max_size: Option<u64>,
+    pub max_count: Option<usize>,
+    pub regex: Option<String>,
+    pub validate: Option<syn::Path>,
+    pub flatten: bool,
 }
 
 /// Part of the Invocation struct. Represents a single parameter of a Discord command.
-struct CommandParameter {
-    name: syn::Ident,
-    type_: syn::Type,
-    args: ParamArgs,
-    span: proc_macro2::Span,
+pub struct CommandParameter {
+    pub name: syn::Ident,
+    pub type_: syn::Type,
+    pub args: ParamArgs,
+    pub span: proc_macro2::Span,
+}
+
+impl CommandParameter {
+    /// If this parameter is a named option in prefix commands (`#[named]`, `#[long = "..."]` or
+    /// `#[short = '.']`), returns its long name
+    pub fn long_name(&self) -> Option<String> {
+        if !self.args.named && self.args.long.is_none() && self.args.short.is_none() {
+            return None;
+        }
+        Some(match (&self.args.long, &self.args.rename) {
+            (Some(long), _) => long.clone(),
+            (None, Some(rename)) => rename.clone(),
+            (None, None) => self.name.to_string(),
+        })
+    }
+
+    /// The parameter type without `Option` and `Vec` around it
+    fn inner_type(&self) -> &syn::Type {
+        let inner_type =
+            crate::util::extract_type_parameter("Option", &self.type_).unwrap_or(&self.type_);
+        crate::util::extract_type_parameter("Vec", inner_type).unwrap_or(inner_type)
+    }
+
+    /// Whether the parameter is a primitive number, possibly wrapped in `Option` or `Vec`
+    fn is_number(&self) -> bool {
+        const NUMBER_TYPES: &[&str] = &[
+            "i8", "i16", "i32", "i64", "isize", "u8", "u16", "u32", "u64", "usize", "f32", "f64",
+        ];
+        match self.inner_type() {
+            syn::Type::Path(path) => matches!(
+                path.path.get_ident(),
+                Some(ident) if NUMBER_TYPES.iter().any(|number| ident == number)
+            ),
+            _ => false,
+        }
+    }
+
+    /// Whether this parameter takes attachments and hence has attachment constraints: either its
+    /// type is an attachment type or any attachment constraint attribute is set
+    pub fn is_attachment(&self) -> bool {
+        let is_attachment_type = match self.inner_type() {
+            syn::Type::Path(path) => matches!(
+                path.path.segments.last(),
+                Some(segment) if segment.ident == "Attachment"
+            ),
+            _ => false,
+        };
+
+        is_attachment_type
+            || self.args.content_types.is_some()
+            || self.args.max_size.is_some()
+            || self.args.max_count.is_some()
+    }
+
+    /// Number of slash command options this parameter is registered as, see
+    /// `poise::CommandParameter::create_as_slash_command_options`. `None` for `#[flatten]`
+    /// parameters, whose fields aren't known here
+    pub fn slash_option_count(&self) -> Option<usize> {
+        if self.args.flatten {
+            return None;
+        }
+        let inner_type =
+            crate::util::extract_type_parameter("Option", &self.type_).unwrap_or(&self.type_);
+        let variadic = crate::util::extract_type_parameter("Vec", inner_type).is_some();
+        Some(match variadic && self.is_attachment() {
+            true => self
+                .args
+                .max_count
+                .unwrap_or(DEFAULT_ATTACHMENT_OPTION_COUNT)
+                .max(1),
+            false => 1,
+        })
+    }
+
+    /// Whether this parameter is a bool which is false unless given: `#[flag]` or a named bool
+    pub fn is_flag(&self) -> bool {
+        self.args.flag || (self.long_name().is_some() && self.type_ == syn::parse_quote! { bool })
+    }
 }
 
 /// Passed to prefix and slash command spec generators; contains info to be included in command spec
@@ -180,7 +357,13 @@ pub fn command(
             .drain(..)
             .map(|attr| attr.parse_meta().map(syn::NestedMeta::Meta))
             .collect::<Result<Vec<_>, _>>()?;
+        let attr_count = attrs.len();
         let attrs = <ParamArgs as darling::FromMeta>::from_list(&attrs)?;
+        if attrs.flatten && attr_count > 1 {
+            let err_msg = "#[flatten] can't be combined with other parameter attributes, put them \
+                on the fields of the parameter group instead";
+            return Err(syn::Error::new(command_param.span(), err_msg).into());
+        }
 
         parameters.push(CommandParameter {
             name: name.clone(),
@@ -190,6 +373,23 @@ pub fn command(
         });
     }
 
+    // Discord rejects the whole command if it has too many options
+    if args.slash_command {
+        let option_count = parameters
+            .iter()
+            .filter_map(|p| p.slash_option_count())
+            .sum::<usize>();
+        if option_count > MAX_SLASH_OPTIONS {
+            let err_msg = format!(
+                "slash commands can have at most {} options, but the parameters of this command \
+                are registered as {} options. `Vec<Attachment>` parameters are registered as \
+                #[max_count] options ({} by default)",
+                MAX_SLASH_OPTIONS, option_count, DEFAULT_ATTACHMENT_OPTION_COUNT,
+            );
+            return Err(syn::Error::new(proc_macro2::Span::call_site(), err_msg).into());
+        }
+    }
+
     // Extract the command descriptions from the function doc comments
     let (description, help_text) = extract_help_from_doc_comments(&function.attrs);
 
@@ -226,18 +426,169 @@ pub fn command(
     Ok(TokenStream::from(generate_command(inv)?))
 }
 
-fn generate_command(mut inv: Invocation) -> Result<proc_macro2::TokenStream, darling::Error> {
-    let ctx_type = match inv.function.sig.inputs.first() {
-        Some(syn::FnArg::Typed(syn::PatType { ty, .. })) => &**ty,
-        _ => {
-            return Err(
-                syn::Error::new(inv.function.sig.span(), "expected a Context parameter").into(),
-            )
+impl Invocation {
+    /// Type of the Context parameter of the command function
+    fn ctx_type(&self) -> Result<&syn::Type, syn::Error> {
+        match self.function.sig.inputs.first() {
+            Some(syn::FnArg::Typed(syn::PatType { ty, .. })) => Ok(&**ty),
+            _ => Err(syn::Error::new(
+                self.function.sig.span(),
+                "expected a Context parameter",
+            )),
+        }
+    }
+
+    /// The `poise::ParameterGroup` trait with the generics of this command, which the types of
+    /// `#[flatten]` parameters implement
+    fn parameter_group_trait(&self) -> Result<proc_macro2::TokenStream, syn::Error> {
+        // Needed because we're not allowed to have lifetimes in the hacky use case below
+        let ctx_type = syn::fold::fold_type(
+            &mut crate::util::AllLifetimesToStatic,
+            self.ctx_type()?.clone(),
+        );
+        Ok(quote::quote! { ::poise::ParameterGroup<
+            <#ctx_type as ::poise::_GetGenerics>::U,
+            <#ctx_type as ::poise::_GetGenerics>::E,
+        > })
+    }
+
+    /// Position of the i-th function parameter in `poise::Command::parameters`. Parameter groups
+    /// before it take up as many positions as they have fields, so this may not be a constant
+    fn parameter_index(&self, i: usize) -> Result<proc_macro2::TokenStream, syn::Error> {
+        let (groups, plain): (Vec<_>, Vec<_>) =
+            self.parameters[..i].iter().partition(|p| p.args.flatten);
+        let plain_count = plain.len();
+        if groups.is_empty() {
+            return Ok(quote::quote! { #plain_count });
+        }
+
+        let group_trait = self.parameter_group_trait()?;
+        let group_types = groups.iter().map(|p| &p.type_);
+        Ok(quote::quote! {
+            #plain_count #( + <#group_types as #group_trait>::PARAMETER_COUNT )*
+        })
+    }
+}
+
+/// Generates the statements which check a parsed parameter value against the attachment
+/// constraints and validation attributes (`#[min]`, `#[max]`, `#[min_length]`, `#[max_length]`,
+/// `#[regex]` and `#[validate]`) of the parameter
+///
+/// `index` is the position of the parameter in `poise::Command::parameters`, `value` a reference
+/// to the parsed value and `ctx` a `poise::Context`
+pub fn generate_value_checks(
+    p: &CommandParameter,
+    index: &proc_macro2::TokenStream,
+    value: &proc_macro2::TokenStream,
+    ctx: &proc_macro2::TokenStream,
+) -> Result<Vec<proc_macro2::TokenStream>, syn::Error> {
+    // Bounds of number parameters are parsed here, so that invalid bounds are compile errors.
+    // Other bounds are passed as strings and parsed by the parameter type, so that types like
+    // poise::Duration can have bounds like `#[max = "28d"]`
+    let is_number = p.is_number();
+    let bound = |bound: &Option<syn::Lit>| {
+        let bound = match bound {
+            Some(bound) => bound,
+            None => return Ok(None),
+        };
+        let (text, is_number_literal) = match bound {
+            syn::Lit::Str(bound) => (bound.value(), false),
+            syn::Lit::Int(bound) => (bound.base10_digits().to_owned(), true),
+            syn::Lit::Float(bound) => (bound.base10_digits().to_owned(), true),
+            _ => {
+                return Err(syn::Error::new(
+                    bound.span(),
+                    "expected a number or string literal",
+                ))
+            }
+        };
+        if !is_number && !is_number_literal {
+            return Ok(Some(quote::quote! { #text }));
+        }
+        match text.parse::<f64>() {
+            Ok(number) if number.is_finite() => {
+                let number = proc_macro2::Literal::f64_suffixed(number);
+                Ok(Some(quote::quote! { #number }))
+            }
+            _ => Err(syn::Error::new(
+                bound.span(),
+                format!("invalid number bound `{}`", text),
+            )),
         }
     };
+
+    let mut checks = Vec::new();
+    if p.is_attachment() {
+        checks.push(quote::quote! {
+            ::poise::check_attachment_parameter(#ctx, #index, #value)?;
+        });
+    }
+
+    let (min, max) = (bound(&p.args.min)?, bound(&p.args.max)?);
+    if min.is_some() || max.is_some() {
+        let (min, max) = (wrap_option(min), wrap_option(max));
+        checks.push(quote::quote! {
+            ::poise::check_argument_bounds(#ctx, #index, #value, #min, #max)?;
+        });
+    }
+    if p.args.min_length.is_some() || p.args.max_length.is_some() {
+        let min_length = wrap_option(p.args.min_length.as_ref());
+        let max_length = wrap_option(p.args.max_length.as_ref());
+        checks.push(quote::quote! {
+            ::poise::check_argument_length(#ctx, #index, #value, #min_length, #max_length)?;
+        });
+    }
+    if let Some(regex) = &p.args.regex {
+        // Parsed like the regex crate does at runtime, which wraps the pattern the same way
+        if let Err(e) = regex_syntax::Parser::new().parse(&format!("^(?:{})$", regex)) {
+            return Err(syn::Error::new(
+                p.span,
+                format!("invalid #[regex] pattern `{}`: {}", regex, e),
+            ));
+        }
+        checks.push(quote::quote! {
+            ::poise::check_argument_regex(#ctx, #index, #value, #regex)?;
+        });
+    }
+    if let Some(validate) = &p.args.validate {
+        checks.push(quote::quote! {
+            ::poise::check_argument_validator(#ctx, #index, #validate(#value))?;
+        });
+    }
+    Ok(checks)
+}
+
+/// Generates the statements which check the parsed parameter values of the command, see
+/// [`generate_value_checks`]. Parameter groups check their own fields. Used by both prefix and
+/// slash command actions
+fn generate_parameter_checks(
+    inv: &Invocation,
+) -> Result<Vec<proc_macro2::TokenStream>, syn::Error> {
+    let mut checks = Vec::new();
+    for (i, p) in inv.parameters.iter().enumerate() {
+        let name = &p.name;
+        let index = inv.parameter_index(i)?;
+        if p.args.flatten {
+            let type_ = &p.type_;
+            let group_trait = inv.parameter_group_trait()?;
+            checks.push(quote::quote! {
+                <#type_ as #group_trait>::validate(&#name, ctx.into(), #index)?;
+            });
+        } else {
+            let value = quote::quote! { &#name };
+            let ctx = quote::quote! { ctx.into() };
+            checks.extend(generate_value_checks(p, &index, &value, &ctx)?);
+        }
+    }
+    Ok(checks)
+}
+
+fn generate_command(mut inv: Invocation) -> Result<proc_macro2::TokenStream, darling::Error> {
     // Needed because we're not allowed to have lifetimes in the hacky use case below
-    let ctx_type_with_static =
-        syn::fold::fold_type(&mut crate::util::AllLifetimesToStatic, ctx_type.clone());
+    let ctx_type_with_static = syn::fold::fold_type(
+        &mut crate::util::AllLifetimesToStatic,
+        inv.ctx_type()?.clone(),
+    );
 
     let prefix_action = wrap_option(match inv.args.prefix_command {
         true => Some(prefix::generate_prefix_action(&inv)?),
@@ -267,11 +618,11 @@ fn generate_command(mut inv: Invocation) -> Result<proc_macro2::TokenStream, dar
     let hide_in_help = &inv.args.hide_in_help;
     let category = wrap_option(inv.args.category.as_ref());
 
-    let global_cooldown = wrap_option(inv.args.global_cooldown);
-    let user_cooldown = wrap_option(inv.args.user_cooldown);
-    let guild_cooldown = wrap_option(inv.args.guild_cooldown);
-    let channel_cooldown = wrap_option(inv.args.channel_cooldown);
-    let member_cooldown = wrap_option(inv.args.member_cooldown);
+    let global_cooldown = cooldown_rate_tokens(&inv.args.global_cooldown);
+    let user_cooldown = cooldown_rate_tokens(&inv.args.user_cooldown);
+    let guild_cooldown = cooldown_rate_tokens(&inv.args.guild_cooldown);
+    let channel_cooldown = cooldown_rate_tokens(&inv.args.channel_cooldown);
+    let member_cooldown = cooldown_rate_tokens(&inv.args.member_cooldown);
 
     let default_member_permissions = &inv.default_member_permissions;
     let required_permissions = &inv.required_permissions;
@@ -343,11 +694,11 @@ fn generate_command(mut inv: Invocation) -> Result<proc_macro2::TokenStream, dar
                 help_text: #help_text,
                 hide_in_help: #hide_in_help,
                 cooldowns: std::sync::Mutex::new(::poise::Cooldowns::new(::poise::CooldownConfig {
-                    global: #global_cooldown.map(std::time::Duration::from_secs),
-                    user: #user_cooldown.map(std::time::Duration::from_secs),
-                    guild: #guild_cooldown.map(std::time::Duration::from_secs),
-                    channel: #channel_cooldown.map(std::time::Duration::from_secs),
-                    member: #member_cooldown.map(std::time::Duration::from_secs),
+                    global: #global_cooldown,
+                    user: #user_cooldown,
+                    guild: #guild_cooldown,
+                    channel: #channel_cooldown,
+                    member: #member_cooldown,
                 })),
                 reuse_response: #reuse_response,
                 default_member_permissions: #default_member_permissions,
@@ -359,7 +710,7 @@ fn generate_command(mut inv: Invocation) -> Result<proc_macro2::TokenStream, dar
                 nsfw_only: #nsfw_only,
                 checks: vec![ #( |ctx| Box::pin(#checks(ctx)) ),* ],
                 on_error: #on_error,
-                parameters: vec![ #( #parameters ),* ],
+                parameters: #parameters,
                 custom_data: #custom_data,
 
                 aliases: &[ #( #aliases, )* ],
@@ -375,3 +726,114 @@ fn generate_command(mut inv: Invocation) -> Result<proc_macro2::TokenStream, dar
         }
     })
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_parse_cooldown_spec() {
+        let spec = |uses, window_millis, burst| {
+            Some(CooldownSpec {
+                uses,
+                window_millis,
+                burst,
+            })
+        };
+        assert_eq!(parse_cooldown_spec("30"), spec(1, 30_000, 1));
+        assert_eq!(parse_cooldown_spec("500ms"), spec(1, 500, 1));
+        assert_eq!(parse_cooldown_spec("5/60s"), spec(5, 60_000, 5));
+        assert_eq!(parse_cooldown_spec(" 5 / 2m burst 8 "), spec(5, 120_000, 8));
+        assert_eq!(parse_cooldown_spec("0/60s"), None);
+        assert_eq!(parse_cooldown_spec("5/60s burst 0"), None);
+        assert_eq!(parse_cooldown_spec("5/60 years"), None);
+        assert_eq!(parse_cooldown_spec("five/60s"), None);
+    }
+
+    #[test]
+    fn test_slash_option_count() {
+        let param = |type_: syn::Type, max_count| CommandParameter {
+            name: syn::parse_quote! { files },
+            type_,
+            args: ParamArgs {
+                max_count,
+                ..Default::default()
+            },
+            span: proc_macro2::Span::call_site(),
+        };
+        let count = |type_, max_count| param(type_, max_count).slash_option_count();
+
+        assert_eq!(count(syn::parse_quote! { String }, None), Some(1));
+        assert_eq!(count(syn::parse_quote! { Vec<String> }, None), Some(1));
+        assert_eq!(
+            count(syn::parse_quote! { Vec<serenity::Attachment> }, None),
+            Some(DEFAULT_ATTACHMENT_OPTION_COUNT)
+        );
+        assert_eq!(
+            count(
+                syn::parse_quote! { Option<Vec<serenity::Attachment>> },
+                Some(5)
+            ),
+            Some(5)
+        );
+
+        let mut group = param(syn::parse_quote! { Filters }, None);
+        group.args.flatten = true;
+        assert_eq!(group.slash_option_count(), None);
+    }
+
+    #[test]
+    fn test_value_checks() {
+        let checks = |type_: syn::Type, args: ParamArgs| {
+            let param = CommandParameter {
+                name: syn::parse_quote! { value },
+                type_,
+                args,
+                span: proc_macro2::Span::call_site(),
+            };
+            let (index, value, ctx) = (quote::quote!(0), quote::quote!(&value), quote::quote!(ctx));
+            generate_value_checks(&param, &index, &value, &ctx)
+                .map(|checks| quote::quote!(#( #checks )*).to_string())
+        };
+        let bounds = |min: syn::Lit, max: syn::Lit| ParamArgs {
+            min: Some(min),
+            max: Some(max),
+            ..Default::default()
+        };
+
+        // Number bounds are parsed at compile time, other bounds are passed on as strings
+        let number = checks(
+            syn::parse_quote! { Option<u32> },
+            bounds(syn::parse_quote!(1), syn::parse_quote!("1e3")),
+        )
+        .unwrap();
+        assert!(
+            number.contains("Some (1f64) , Some (1000f64)"),
+            "{}",
+            number
+        );
+        let duration = checks(
+            syn::parse_quote! { poise::Duration },
+            bounds(syn::parse_quote!("1m"), syn::parse_quote!("28d")),
+        )
+        .unwrap();
+        assert!(
+            duration.contains(r#"Some ("1m") , Some ("28d")"#),
+            "{}",
+            duration
+        );
+        assert!(checks(
+            syn::parse_quote! { u32 },
+            bounds(syn::parse_quote!("ten"), syn::parse_quote!(100)),
+        )
+        .is_err());
+
+        let regex = |regex: &str| ParamArgs {
+            regex: Some(regex.into()),
+            ..Default::default()
+        };
+        assert!(checks(syn::parse_quote! { String }, regex("[a-z0-9_]+")).is_ok());
+        assert!(checks(syn::parse_quote! { String }, regex("[a-z")).is_err());
+        assert!(checks(syn::parse_quote! { String }, regex("(")).is_err());
+    }
+}
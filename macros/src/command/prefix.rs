@@ -1,7 +1,21 @@
 use super::Invocation;
+use crate::util::{extract_type_parameter, wrap_option};
 use syn::spanned::Spanned as _;
 
-fn quote_parameter(p: &super::CommandParameter) -> Result<proc_macro2::TokenStream, syn::Error> {
+/// Generates the `poise::parse_prefix_args!` spec of a positional parameter. `group_trait` is the
+/// `poise::ParameterGroup` trait which the type of a `#[flatten]` parameter implements
+pub fn quote_parameter(
+    p: &super::CommandParameter,
+    group_trait: Option<&proc_macro2::TokenStream>,
+) -> Result<proc_macro2::TokenStream, syn::Error> {
+    if p.args.flatten {
+        let type_ = &p.type_;
+        return match group_trait {
+            Some(group_trait) => Ok(quote::quote! { #[flatten] (#type_ as #group_trait) }),
+            None => Err(syn::Error::new(p.span, "#[flatten] can't be used here")),
+        };
+    }
+
     enum Modifier {
         None,
         Lazy,
@@ -36,33 +50,153 @@ fn quote_parameter(p: &super::CommandParameter) -> Result<proc_macro2::TokenStre
     })
 }
 
+/// Generates the [`poise::NamedOption`] spec of a named parameter and the statement that parses it
+/// from the extracted `named_args`
+fn quote_named_parameter(
+    p: &super::CommandParameter,
+    long: &str,
+    option_index: usize,
+    parameter_index: proc_macro2::TokenStream,
+) -> Result<(proc_macro2::TokenStream, proc_macro2::TokenStream), syn::Error> {
+    if p.args.lazy || p.args.rest || p.args.flag || p.args.flatten {
+        return Err(syn::Error::new(
+            p.span,
+            "named parameters can't be used together with #[lazy], #[rest], #[flag] or #[flatten]",
+        ));
+    }
+
+    let name = &p.name;
+    // Values are parsed like positional arguments, so all prefix argument types are supported
+    let parse = |type_: &syn::Type, finish: proc_macro2::TokenStream| {
+        quote::quote! {
+            let #name = ::poise::parse_named_argument!(
+                #type_, &named_args, #option_index, ctx_discord, ctx.msg, tokenizer
+            )
+            .await
+            .map_err(|error| error.to_framework_error(ctx))?;
+            let #name = #finish;
+        }
+    };
+    let (takes_value, statement) = if p.type_ == syn::parse_quote! { bool } {
+        let statement = quote::quote! { let #name = named_args.is_present(#option_index); };
+        (false, statement)
+    } else if let Some(type_) = extract_type_parameter("Option", &p.type_) {
+        // If the option was passed multiple times, the last value wins
+        (
+            true,
+            parse(type_, quote::quote! { #name.into_iter().last() }),
+        )
+    } else if let Some(type_) = extract_type_parameter("Vec", &p.type_) {
+        (true, parse(type_, quote::quote! { #name }))
+    } else {
+        let finish = quote::quote! {
+            named_args
+                .required(#option_index, #name.into_iter().last())
+                .map_err(|error| error.to_framework_error(ctx))?
+        };
+        (true, parse(&p.type_, finish))
+    };
+
+    let short = wrap_option(p.args.short);
+    let option = quote::quote! {
+        ::poise::NamedOption {
+            long: #long,
+            short: #short,
+            takes_value: #takes_value,
+            parameter_index: #parameter_index,
+        }
+    };
+    Ok((option, statement))
+}
+
 pub fn generate_prefix_action(inv: &Invocation) -> Result<proc_macro2::TokenStream, syn::Error> {
+    let mut named_options = Vec::new();
+    let mut named_statements = Vec::new();
+    let mut positional_indices = Vec::new();
+    for (i, p) in inv.parameters.iter().enumerate() {
+        match p.long_name() {
+            Some(long) => {
+                let index = inv.parameter_index(i)?;
+                let (option, statement) =
+                    quote_named_parameter(p, &long, named_options.len(), index)?;
+                named_options.push(option);
+                named_statements.push(statement);
+            }
+            None => positional_indices.push(i),
+        }
+    }
+    let has_groups = inv.parameters.iter().any(|p| p.args.flatten);
+    let group_trait = match has_groups {
+        true => Some(inv.parameter_group_trait()?),
+        false => None,
+    };
+
     let param_names = inv.parameters.iter().map(|p| &p.name).collect::<Vec<_>>();
-    let param_specs = inv
-        .parameters
+    let positional_names = positional_indices
+        .iter()
+        .map(|&i| &inv.parameters[i].name)
+        .collect::<Vec<_>>();
+    let positional_specs = positional_indices
+        .iter()
+        .map(|&i| quote_parameter(&inv.parameters[i], group_trait.as_ref()))
+        .collect::<Result<Vec<_>, syn::Error>>()?;
+    let positional_parameter_indices = positional_indices
         .iter()
-        .map(quote_parameter)
+        .map(|&i| inv.parameter_index(i))
         .collect::<Result<Vec<_>, syn::Error>>()?;
     let wildcard_arg = match inv.args.discard_spare_arguments {
         true => Some(quote::quote! { #[rest] (Option<String>), }),
         false => None,
     };
 
+    // Named options are extracted first, so that the positional parser only sees the remaining
+    // arguments
+    let (extract_named_args, positional_args) = if named_options.is_empty() {
+        (quote::quote! {}, quote::quote! { ctx.args })
+    } else {
+        (
+            quote::quote! {
+                let named_args = ::poise::NamedArgs::extract(ctx.args, tokenizer, &[ #( #named_options, )* ])
+                    .map_err(|error| error.to_framework_error(ctx))?;
+                #( #named_statements )*
+            },
+            quote::quote! { &*named_args.positional },
+        )
+    };
+    // The positional parser only knows the positions of the parameters in its own spec list
+    let map_positional_error = if named_options.is_empty() && !has_groups {
+        quote::quote! { error.to_framework_error(ctx) }
+    } else {
+        let error = quote::quote! {
+            error.with_parameter_indices(&[ #( #positional_parameter_indices ),* ])
+        };
+        match named_options.is_empty() {
+            true => quote::quote! { #error.to_framework_error(ctx) },
+            // The positional parser only saw the arguments without the named options
+            false => quote::quote! { named_args.restore_span(#error).to_framework_error(ctx) },
+        }
+    };
+
+    let parameter_checks = super::generate_parameter_checks(inv)?;
+
     Ok(quote::quote! {
         |ctx| Box::pin(async move {
             let ctx_discord = &ctx.discord;
-            let ( #( #param_names, )* .. ) = ::poise::parse_prefix_args!(
-                ctx_discord, ctx.msg, ctx.args, 0 =>
-                #( #param_specs, )*
+            let tokenizer = &ctx.framework.options.prefix_options.tokenizer;
+            #extract_named_args
+            let ( #( #positional_names, )* .. ) = ::poise::parse_prefix_args!(
+                ctx_discord, ctx.msg, #positional_args, 0, tokenizer =>
+                #( #positional_specs, )*
                 #wildcard_arg
-            ).await.map_err(|(error, input)| poise::FrameworkError::ArgumentParse {
-                error,
-                input,
-                ctx: ctx.into(),
-            })?;
+            ).await.map_err(|error| #map_positional_error)?;
+
+            #( #parameter_checks )*
 
             if !ctx.framework.options.manual_cooldowns {
-                ctx.command.cooldowns.lock().unwrap().start_cooldown(ctx.into());
+                let cooldowns = ::poise::Cooldowns::effective_for(ctx.into(), ctx.command).await;
+                if let Some(cooldowns) = cooldowns {
+                    cooldowns.start_cooldown(ctx.into());
+                }
             }
 
             inner(ctx.into(), #( #param_names, )* )
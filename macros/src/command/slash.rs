@@ -1,131 +1,204 @@
 use super::Invocation;
-use crate::util::extract_type_parameter;
+use crate::util::{extract_type_parameter, wrap_option};
 use syn::spanned::Spanned as _;
 
-pub fn generate_parameters(inv: &Invocation) -> Result<Vec<proc_macro2::TokenStream>, syn::Error> {
-    let mut parameter_structs = Vec::new();
-    for param in &inv.parameters {
-        // no #[description] check here even if slash_command set, so users can programatically
-        // supply descriptions later (e.g. via translation framework like fluent)
-        let description = match &param.args.description {
-            Some(x) => quote::quote! { Some(#x.to_string()) },
-            None => quote::quote! { None },
-        };
-
-        let (mut required, type_) = match extract_type_parameter("Option", &param.type_)
-            .or_else(|| extract_type_parameter("Vec", &param.type_))
-        {
-            Some(t) => (false, t),
-            None => (true, &param.type_),
-        };
-
-        // Don't require user to input a value for flags - use false as default value (see below)
-        if param.args.flag {
-            required = false;
-        }
+/// Generates the `Vec<poise::CommandParameter>` expression of the command. The fields of parameter
+/// groups are spliced in
+pub fn generate_parameters(inv: &Invocation) -> Result<proc_macro2::TokenStream, syn::Error> {
+    if !inv.parameters.iter().any(|p| p.args.flatten) {
+        let parameter_structs = inv
+            .parameters
+            .iter()
+            .map(|p| generate_parameter_struct(p, inv.args.slash_command))
+            .collect::<Result<Vec<_>, _>>()?;
+        return Ok(quote::quote! { vec![ #( #parameter_structs ),* ] });
+    }
 
-        let param_name = match &param.args.rename {
-            Some(rename) => rename.clone(),
-            None => param.name.to_string(),
-        };
-        let name_locales = param.args.name_localized.iter().map(|x| &x.0);
-        let name_localized_values = param.args.name_localized.iter().map(|x| &x.1);
-        let description_locales = param.args.description_localized.iter().map(|x| &x.0);
-        let description_localized_values = param.args.description_localized.iter().map(|x| &x.1);
-
-        let autocomplete_callback = match &param.args.autocomplete {
-            Some(autocomplete_fn) => {
-                quote::quote! { Some(|
-                    ctx: poise::ApplicationContext<'_, _, _>,
-                    partial: &str,
-                | Box::pin(async move {
-                    use ::poise::futures_util::{Stream, StreamExt};
-
-                    let choices_stream = ::poise::into_stream!(
-                        #autocomplete_fn(ctx.into(), partial).await
-                    );
-                    let choices_json = choices_stream
-                        .take(25)
-                        // T or AutocompleteChoice<T> -> AutocompleteChoice<T>
-                        .map(|value| poise::AutocompleteChoice::from(value))
-                        // poise::AutocompleteChoice<T> -> serenity::AutocompleteChoice
-                        .map(|choice| choice.to_serenity())
-                        .collect()
-                        .await;
-
-                    Ok(poise::serenity_prelude::CreateAutocompleteResponse::default().set_choices(choices_json))
-                })) }
+    let group_trait = inv.parameter_group_trait()?;
+    let parameter_lists = inv
+        .parameters
+        .iter()
+        .map(|p| match p.args.flatten {
+            true => {
+                let type_ = &p.type_;
+                Ok(quote::quote! { <#type_ as #group_trait>::parameters() })
             }
-            None => quote::quote! { None },
-        };
-
-        // We can just cast to f64 here because Discord only uses f64 precision anyways
-        // TODO: move this to poise::CommandParameter::{min, max} fields
-        let min_value_setter = match &param.args.min {
-            Some(x) => quote::quote! { .min_number_value(#x as f64) },
-            None => quote::quote! {},
-        };
-        let max_value_setter = match &param.args.max {
-            Some(x) => quote::quote! { .max_number_value(#x as f64) },
-            None => quote::quote! {},
-        };
-        // TODO: move this to poise::CommandParameter::{min_length, max_length} fields
-        let min_length_setter = match &param.args.min_length {
-            Some(x) => quote::quote! { o.min_length(#x); },
-            None => quote::quote! {},
-        };
-        let max_length_setter = match &param.args.max_length {
-            Some(x) => quote::quote! { o.max_length(#x); },
-            None => quote::quote! {},
-        };
-        let type_setter = match inv.args.slash_command {
-            true => quote::quote! { Some(|o| {
-                poise::create_slash_argument!(#type_, o)
-                #min_value_setter #max_value_setter
-                #min_length_setter #max_length_setter
-            }) },
-            false => quote::quote! { None },
-        };
-        // TODO: theoretically a problem that we don't store choices for non slash commands
-        let choices = match inv.args.slash_command {
-            true => quote::quote! { poise::slash_argument_choices!(#type_) },
-            false => quote::quote! { vec![] },
-        };
-
-        let channel_types = match &param.args.channel_types {
-            Some(crate::util::List(channel_types)) => quote::quote! { Some(
-                vec![ #( poise::serenity_prelude::ChannelType::#channel_types ),* ]
-            ) },
-            None => quote::quote! { None },
-        };
-
-        parameter_structs.push((
-            quote::quote! {
-                ::poise::CommandParameter {
-                    name: #param_name.to_string(),
-                    name_localizations: vec![
-                        #( (#name_locales.to_string(), #name_localized_values.to_string()) ),*
-                    ].into_iter().collect(),
-                    description: #description,
-                    description_localizations: vec![
-                        #( (#description_locales.to_string(), #description_localized_values.to_string()) ),*
-                    ].into_iter().collect(),
-                    required: #required,
-                    channel_types: #channel_types,
-                    type_setter: #type_setter,
-                    choices: #choices,
-                    autocomplete_callback: #autocomplete_callback,
-                }
-            },
-            required,
-        ));
+            false => {
+                let parameter_struct = generate_parameter_struct(p, inv.args.slash_command)?;
+                Ok(quote::quote! { vec![ #parameter_struct ] })
+            }
+        })
+        .collect::<Result<Vec<_>, syn::Error>>()?;
+    Ok(quote::quote! {
+        vec![ #( #parameter_lists ),* ].into_iter().flatten().collect()
+    })
+}
+
+/// Generates the `poise::CommandParameter` struct of a single parameter. If `slash_command` is
+/// false, the slash command specific fields are left empty
+pub fn generate_parameter_struct(
+    param: &super::CommandParameter,
+    slash_command: bool,
+) -> Result<proc_macro2::TokenStream, syn::Error> {
+    // no #[description] check here even if slash_command set, so users can programatically
+    // supply descriptions later (e.g. via translation framework like fluent)
+    let description = match &param.args.description {
+        Some(x) => quote::quote! { Some(#x.to_string()) },
+        None => quote::quote! { None },
+    };
+
+    let (mut required, type_) = match extract_type_parameter("Option", &param.type_) {
+        Some(t) => (false, t),
+        None => (true, &param.type_),
+    };
+    // Also covers Option<Vec<T>>
+    let (variadic, type_) = match extract_type_parameter("Vec", type_) {
+        Some(t) => (true, t),
+        None => (false, type_),
+    };
+    if variadic {
+        required = false;
+    }
+
+    let long = param.long_name();
+    let flag = param.is_flag();
+
+    // Don't require user to input a value for flags - use false as default value (see below)
+    if flag {
+        required = false;
+    }
+
+    // Only the last path segment, i.e. `User` instead of `serenity::User`
+    let type_name = match type_ {
+        syn::Type::Path(path) => path.path.segments.last().map(|s| s.ident.to_string()),
+        _ => None,
     }
-    // Sort the parameters so that optional parameters come last - Discord requires this order
-    parameter_structs.sort_by_key(|(_, required)| !required);
-    Ok(parameter_structs
-        .into_iter()
-        .map(|(builder, _)| builder)
-        .collect::<Vec<_>>())
+    .unwrap_or_else(|| quote::quote!(#type_).to_string().replace(' ', ""));
+
+    let param_name = match &param.args.rename {
+        Some(rename) => rename.clone(),
+        None => param.name.to_string(),
+    };
+    let name_locales = param.args.name_localized.iter().map(|x| &x.0);
+    let name_localized_values = param.args.name_localized.iter().map(|x| &x.1);
+    let description_locales = param.args.description_localized.iter().map(|x| &x.0);
+    let description_localized_values = param.args.description_localized.iter().map(|x| &x.1);
+
+    let autocomplete_callback = match &param.args.autocomplete {
+        Some(autocomplete_fn) => {
+            quote::quote! { Some(|
+                ctx: poise::ApplicationContext<'_, _, _>,
+                partial: &str,
+            | Box::pin(async move {
+                use ::poise::futures_util::{Stream, StreamExt};
+
+                let choices_stream = ::poise::into_stream!(
+                    #autocomplete_fn(ctx.into(), partial).await
+                );
+                let choices_json = choices_stream
+                    .take(25)
+                    // T or AutocompleteChoice<T> -> AutocompleteChoice<T>
+                    .map(|value| poise::AutocompleteChoice::from(value))
+                    // poise::AutocompleteChoice<T> -> serenity::AutocompleteChoice
+                    .map(|choice| choice.to_serenity())
+                    .collect()
+                    .await;
+
+                Ok(poise::serenity_prelude::CreateAutocompleteResponse::default().set_choices(choices_json))
+            })) }
+        }
+        None => quote::quote! { None },
+    };
+
+    // We can just cast to f64 here because Discord only uses f64 precision anyways
+    // TODO: move this to poise::CommandParameter::{min, max} fields
+    // String bounds like `#[max = "28d"]` can't be enforced by Discord and are checked by
+    // poise instead, see generate_parameter_checks
+    let min_value_setter = match &param.args.min {
+        Some(syn::Lit::Str(_)) | None => quote::quote! {},
+        Some(x) => quote::quote! { .min_number_value(#x as f64) },
+    };
+    let max_value_setter = match &param.args.max {
+        Some(syn::Lit::Str(_)) | None => quote::quote! {},
+        Some(x) => quote::quote! { .max_number_value(#x as f64) },
+    };
+    // TODO: move this to poise::CommandParameter::{min_length, max_length} fields
+    let min_length_setter = match &param.args.min_length {
+        Some(x) => quote::quote! { o.min_length(#x); },
+        None => quote::quote! {},
+    };
+    let max_length_setter = match &param.args.max_length {
+        Some(x) => quote::quote! { o.max_length(#x); },
+        None => quote::quote! {},
+    };
+    let type_setter = match slash_command {
+        true => quote::quote! { Some(|o| {
+            poise::create_slash_argument!(#type_, o)
+            #min_value_setter #max_value_setter
+            #min_length_setter #max_length_setter
+        }) },
+        false => quote::quote! { None },
+    };
+    // TODO: theoretically a problem that we don't store choices for non slash commands
+    let choices = match slash_command {
+        true => quote::quote! { poise::slash_argument_choices!(#type_) },
+        false => quote::quote! { vec![] },
+    };
+
+    let channel_types = match &param.args.channel_types {
+        Some(crate::util::List(channel_types)) => quote::quote! { Some(
+            vec![ #( poise::serenity_prelude::ChannelType::#channel_types ),* ]
+        ) },
+        None => quote::quote! { None },
+    };
+
+    let attachment_constraints = match param.is_attachment() {
+        true => {
+            let content_types = param.args.content_types.iter().flat_map(|x| &x.0);
+            let max_size = wrap_option(param.args.max_size);
+            let max_count = wrap_option(param.args.max_count);
+            quote::quote! { Some(::poise::AttachmentConstraints {
+                content_types: vec![ #( #content_types.to_string() ),* ],
+                max_size: #max_size,
+                max_count: #max_count,
+                __non_exhaustive: (),
+            }) }
+        }
+        false => quote::quote! { None },
+    };
+    let rest = param.args.rest;
+    let long = match long {
+        Some(long) => quote::quote! { Some(#long.to_string()) },
+        None => quote::quote! { None },
+    };
+    let short = wrap_option(param.args.short);
+    Ok(quote::quote! {
+        ::poise::CommandParameter {
+            name: #param_name.to_string(),
+            name_localizations: vec![
+                #( (#name_locales.to_string(), #name_localized_values.to_string()) ),*
+            ].into_iter().collect(),
+            description: #description,
+            description_localizations: vec![
+                #( (#description_locales.to_string(), #description_localized_values.to_string()) ),*
+            ].into_iter().collect(),
+            required: #required,
+            details: ::poise::CommandParameterDetails {
+                rest: #rest,
+                flag: #flag,
+                long: #long,
+                short: #short,
+                variadic: #variadic,
+                type_name: #type_name,
+                attachment_constraints: #attachment_constraints,
+                __non_exhaustive: (),
+            },
+            channel_types: #channel_types,
+            type_setter: #type_setter,
+            choices: #choices,
+            autocomplete_callback: #autocomplete_callback,
+        }
+    })
 }
 
 pub fn generate_slash_action(inv: &Invocation) -> Result<proc_macro2::TokenStream, syn::Error> {
@@ -154,11 +227,17 @@ pub fn generate_slash_action(inv: &Invocation) -> Result<proc_macro2::TokenStrea
     let param_types = inv
         .parameters
         .iter()
-        .map(|p| match p.args.flag {
-            true => syn::parse_quote! { FLAG },
-            false => p.type_.clone(),
+        .map(|p| {
+            if p.args.flatten {
+                let type_ = &p.type_;
+                let group_trait = inv.parameter_group_trait()?;
+                return Ok(quote::quote! { #[flatten] #type_ as #group_trait });
+            }
+            Ok(slash_args_type(p))
         })
-        .collect::<Vec<_>>();
+        .collect::<Result<Vec<_>, syn::Error>>()?;
+
+    let parameter_checks = super::generate_parameter_checks(inv)?;
 
     Ok(quote::quote! {
         |ctx| Box::pin(async move {
@@ -172,8 +251,13 @@ pub fn generate_slash_action(inv: &Invocation) -> Result<proc_macro2::TokenStrea
                 #( (#param_names: #param_types), )*
             ).await.map_err(|error| error.to_framework_error(ctx))?;
 
+            #( #parameter_checks )*
+
             if !ctx.framework.options.manual_cooldowns {
-                ctx.command.cooldowns.lock().unwrap().start_cooldown(ctx.into());
+                let cooldowns = ::poise::Cooldowns::effective_for(ctx.into(), ctx.command).await;
+                if let Some(cooldowns) = cooldowns {
+                    cooldowns.start_cooldown(ctx.into());
+                }
             }
 
             inner(ctx.into(), #( #param_identifiers, )*)
@@ -186,11 +270,27 @@ pub fn generate_slash_action(inv: &Invocation) -> Result<proc_macro2::TokenStrea
     })
 }
 
+/// The type of a parameter as passed to `poise::parse_slash_args!`
+pub fn slash_args_type(p: &super::CommandParameter) -> proc_macro2::TokenStream {
+    if p.is_flag() {
+        return quote::quote! { FLAG };
+    }
+    // Option<Vec<T>> can't be matched by the macro_rules parser because of the `>>` token
+    match extract_type_parameter("Option", &p.type_).and_then(|t| extract_type_parameter("Vec", t))
+    {
+        Some(t) => quote::quote! { OPTIONAL_VEC<#t> },
+        None => {
+            let type_ = &p.type_;
+            quote::quote! { #type_ }
+        }
+    }
+}
+
 pub fn generate_context_menu_action(
     inv: &Invocation,
 ) -> Result<proc_macro2::TokenStream, syn::Error> {
     let param_type = match &*inv.parameters {
-        [single_param] => &single_param.type_,
+        [single_param] if !single_param.args.flatten => &single_param.type_,
         _ => {
             return Err(syn::Error::new(
                 inv.function.sig.inputs.span(),
@@ -203,7 +303,10 @@ pub fn generate_context_menu_action(
         <#param_type as ::poise::ContextMenuParameter<_, _>>::to_action(|ctx, value| {
             Box::pin(async move {
                 if !ctx.framework.options.manual_cooldowns {
-                    ctx.command.cooldowns.lock().unwrap().start_cooldown(ctx.into());
+                    let cooldowns = ::poise::Cooldowns::effective_for(ctx.into(), ctx.command).await;
+                    if let Some(cooldowns) = cooldowns {
+                        cooldowns.start_cooldown(ctx.into());
+                    }
                 }
 
                 inner(ctx.into(), value)
@@ -5,6 +5,7 @@ Procedural macros used in poise, like [`macro@command`]
 mod choice_parameter;
 mod command;
 mod modal;
+mod parameter_group;
 mod util;
 
 use proc_macro::TokenStream;
@@ -74,6 +75,11 @@ for example for command-specific help (i.e. `~help command_name`). Escape newlin
 - `channel_cooldown`: Minimum duration in seconds between invocations, per channel
 - `member_cooldown`: Minimum duration in seconds between invocations, per guild member
 
+Instead of a number of seconds, each cooldown can also be a rate limit string of the form
+`"<uses>/<window>"`, for example `user_cooldown = "5/60s"` for five uses per minute per user.
+The window supports the units `ms`, `s`, `m`, `h` and `d`. By default, all uses of a window can be
+made back-to-back; append `burst <n>` to allow a different number, e.g. `"5/60s burst 8"`.
+
 ## Other
 
 - `on_error`: Error handling function
@@ -101,19 +107,41 @@ are multiple attributes you can use on parameters:
 - `#[autocomplete = "callback()"]`: Sets the autocomplete callback (slash-only)
 - `#[rename = "new_name"]`: Changes the user-facing name of the parameter (slash-only)
 
-## Input filter (slash only)
+## Input filter
 
+Checked by poise after parsing, in both prefix and slash commands; violations are reported as `FrameworkError::ArgumentValidation`. Discord additionally enforces `min`, `max`, `min_length` and `max_length` for slash command number and string options.
 - `#[channel_types("", "")]`: For channel parameters, restricts allowed channel types (slash-only)
-- `#[min = 0]`: Minimum value for this number parameter (slash-only)
-- `#[max = 0]`: Maximum value for this number parameter (slash-only)
-- `#[min_length = 0]`: Minimum length for this string parameter (slash-only)
-- `#[max_length = 1]`: Maximum length for this string parameter (slash-only)
+- `#[min = 0]`: Minimum value for this number parameter
+- `#[max = 0]`: Maximum value for this number parameter. Invalid number bounds are compile errors
+- `#[min = "1m"]`, `#[max = "28d"]`: Bounds for `poise::Duration` and `poise::Timestamp` parameters
+- `#[min_length = 0]`: Minimum length for this string parameter
+- `#[max_length = 1]`: Maximum length for this string parameter
+- `#[regex = "[a-z0-9_]+"]`: Regular expression which must match the entire value of this string parameter. Invalid patterns are compile errors
+- `#[validate = "path"]`: Function which receives a reference to the parsed value and returns `Result<(), E>`, where the error `E` (for example a `String`) is shown to the user
+
+## Attachment constraints
+
+Checked before the command runs; violations are reported as `FrameworkError::AttachmentConstraint`.
+- `#[content_types("image/png", "application/pdf")]`: Allowed content types of the attachments passed to this attachment parameter. The subtype `*` allows all subtypes, e.g. all images
+- `#[max_size = 8000000]`: Maximum size in bytes of every attachment passed to this attachment parameter
+- `#[max_count = 5]`: Maximum number of attachments for this `Vec<serenity::Attachment>` parameter. In slash commands, the parameter is registered as this many attachment options (default 3). Since Discord allows at most 25 options per slash command, exceeding that is a compile error
 
 ## Parser settings (prefix only)
 - `#[rest]`: Use the entire rest of the message for this parameter (prefix-only)
 - `#[lazy]`: Can be used on Option and Vec parameters and is equivalent to regular expressions' laziness (prefix-only)
 - `#[flag]`: Can be used on a bool parameter to set the bool to true if the user typed the parameter name literally (prefix-only)
     - For example with `async fn my_command(ctx: Context<'_>, #[flag] my_flag: bool)`, `~my_command` would set my_flag to false, and `~my_command my_flag` would set my_flag to true
+- `#[named]`: Turns the parameter into a named option which can be passed anywhere in the arguments, like `--days 7` or `--days=7` (prefix-only, still a regular option in slash commands)
+    - bool parameters become flags like `--silent`, Option parameters are optional, Vec parameters can be passed multiple times and other parameters are required
+    - Values are parsed like positional arguments of the same type, so quoted values like `--reason "spam bot"` work
+    - Can't be combined with `#[rest]`, `#[lazy]`, `#[flag]` or `#[flatten]`
+- `#[long = "..."]`: Long name of a named option, used like `--name` (defaults to the parameter name, implies `#[named]`)
+- `#[short = '.']`: Short name of a named option, used like `-n 7`, `-n7` or, for flags, grouped like `-sv` (implies `#[named]`)
+
+## Parameter groups
+
+- `#[flatten]`: Replaces this parameter with the fields of its type, which must derive `poise::ParameterGroup`. The fields become separate slash command options and are parsed one after the other in prefix commands
+    - Can't be combined with other parameter attributes; put them on the fields instead
 
 # Help text
 
@@ -261,6 +289,73 @@ pub fn slash_choice_parameter(input: TokenStream) -> TokenStream {
     choice_parameter(input)
 }
 
+/**
+Use this derive macro on a struct to bundle parameters which are shared between commands. Use the
+struct as a command parameter with `#[flatten]`, and its fields become parameters of the command.
+
+```rust
+# type Error = Box<dyn std::error::Error + Send + Sync>;
+# type Context<'a> = poise::Context<'a, (), Error>;
+# use poise::serenity_prelude as serenity;
+#[derive(poise::ParameterGroup)]
+struct Punishment {
+    #[description = "User to punish"]
+    user: serenity::User,
+    #[description = "Reason shown in the audit log"]
+    #[max_length = 512]
+    reason: Option<String>,
+}
+
+#[poise::command(prefix_command, slash_command)]
+async fn ban(
+    ctx: Context<'_>,
+    #[flatten] punishment: Punishment,
+    #[description = "Number of days of messages to delete"] days: Option<u8>,
+) -> Result<(), Error> {
+    Ok(())
+}
+```
+
+The fields accept the same attributes as command parameters, except `#[named]`, `#[long]`,
+`#[short]` and `#[flatten]`. The field types must work in both prefix and slash commands.
+
+By default, the group can be used in commands with any `U` and `E`. Autocomplete callbacks take a
+concrete context type, so groups with `#[autocomplete]` fields must name the context type of their
+commands with a struct attribute like `#[context = "Context<'_>"]`.
+*/
+#[proc_macro_derive(
+    ParameterGroup,
+    attributes(
+        context,
+        description,
+        rename,
+        name_localized,
+        description_localized,
+        autocomplete,
+        channel_types,
+        min,
+        max,
+        min_length,
+        max_length,
+        lazy,
+        flag,
+        rest,
+        content_types,
+        max_size,
+        max_count,
+        regex,
+        validate
+    )
+)]
+pub fn parameter_group(input: TokenStream) -> TokenStream {
+    let struct_ = syn::parse_macro_input!(input as syn::DeriveInput);
+
+    match parameter_group::parameter_group(struct_) {
+        Ok(x) => x,
+        Err(e) => e.write_errors().into(),
+    }
+}
+
 /// See `Modal` trait documentation
 #[proc_macro_derive(
     Modal,
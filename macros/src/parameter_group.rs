@@ -0,0 +1,195 @@
+//! Implements the #[derive(ParameterGroup)] derive macro
+
+use crate::command::{CommandParameter, ParamArgs};
+use proc_macro::TokenStream;
+use syn::spanned::Spanned as _;
+
+/// Field attributes which are parsed into [`ParamArgs`]. Other attributes, like doc comments, are
+/// ignored
+const FIELD_ATTRIBUTES: &[&str] = &[
+    "description",
+    "rename",
+    "name_localized",
+    "description_localized",
+    "autocomplete",
+    "channel_types",
+    "min",
+    "max",
+    "min_length",
+    "max_length",
+    "lazy",
+    "flag",
+    "rest",
+    "content_types",
+    "max_size",
+    "max_count",
+    "regex",
+    "validate",
+];
+
+/// Representation of the struct attributes
+#[derive(Debug, Default, darling::FromMeta)]
+#[darling(allow_unknown_fields, default)]
+struct StructAttributes {
+    /// Context type of the commands which use this group. Makes the implementation non-generic,
+    /// which is needed for autocomplete callbacks
+    context: Option<syn::Type>,
+}
+
+pub fn parameter_group(input: syn::DeriveInput) -> Result<TokenStream, darling::Error> {
+    let fields = match input.data {
+        syn::Data::Struct(syn::DataStruct {
+            fields: syn::Fields::Named(fields),
+            ..
+        }) => fields.named,
+        _ => {
+            return Err(syn::Error::new(
+                input.ident.span(),
+                "Only structs with named fields can be used as parameter groups",
+            )
+            .into())
+        }
+    };
+    if fields.is_empty() {
+        return Err(syn::Error::new(
+            input.ident.span(),
+            "parameter groups must have at least one field",
+        )
+        .into());
+    }
+    if !input.generics.params.is_empty() {
+        return Err(
+            syn::Error::new(input.generics.span(), "parameter groups can't be generic").into(),
+        );
+    }
+
+    let struct_attrs = input
+        .attrs
+        .iter()
+        .map(|attr| attr.parse_meta().map(syn::NestedMeta::Meta))
+        .collect::<Result<Vec<_>, _>>()?;
+    let struct_attrs = <StructAttributes as darling::FromMeta>::from_list(&struct_attrs)?;
+
+    let mut parameters = Vec::new();
+    for field in fields {
+        let field_attrs = field
+            .attrs
+            .iter()
+            .filter(|attr| {
+                FIELD_ATTRIBUTES
+                    .iter()
+                    .any(|&name| attr.path.is_ident(name))
+            })
+            .map(|attr| attr.parse_meta().map(syn::NestedMeta::Meta))
+            .collect::<Result<Vec<_>, _>>()?;
+        let args = <ParamArgs as darling::FromMeta>::from_list(&field_attrs)?;
+
+        parameters.push(CommandParameter {
+            span: field.span(),
+            name: field.ident.unwrap(),
+            type_: field.ty,
+            args,
+        });
+    }
+
+    let (impl_generics, u, e) = match &struct_attrs.context {
+        Some(ctx_type) => {
+            // The context type, e.g. `poise::Context<'_, Data, Error>`, is only used to look up
+            // U and E. Its lifetimes don't matter, and `'_` isn't allowed in the impl header
+            let ctx_type =
+                syn::fold::fold_type(&mut crate::util::AllLifetimesToStatic, ctx_type.clone());
+            (
+                quote::quote! {},
+                quote::quote! { <#ctx_type as ::poise::_GetGenerics>::U },
+                quote::quote! { <#ctx_type as ::poise::_GetGenerics>::E },
+            )
+        }
+        None => (
+            quote::quote! { <U, E> },
+            quote::quote! { U },
+            quote::quote! { E },
+        ),
+    };
+
+    let parameter_count = parameters.len();
+    let parameter_structs = parameters
+        .iter()
+        .map(|p| crate::command::slash::generate_parameter_struct(p, true))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let field_names = parameters.iter().map(|p| &p.name).collect::<Vec<_>>();
+    let prefix_specs = parameters
+        .iter()
+        .map(|p| crate::command::prefix::quote_parameter(p, None))
+        .collect::<Result<Vec<_>, _>>()?;
+    let slash_names = parameters.iter().map(|p| match &p.args.rename {
+        Some(rename) => syn::Ident::new(rename, p.name.span()),
+        None => p.name.clone(),
+    });
+    let slash_types = parameters
+        .iter()
+        .map(crate::command::slash::slash_args_type);
+
+    let mut checks = Vec::new();
+    for (i, p) in parameters.iter().enumerate() {
+        let name = &p.name;
+        let index = quote::quote! { parameter_offset + #i };
+        let value = quote::quote! { #name };
+        let ctx = quote::quote! { ctx };
+        checks.extend(crate::command::generate_value_checks(
+            p, &index, &value, &ctx,
+        )?);
+    }
+
+    let struct_ident = input.ident;
+    Ok(quote::quote! {
+        #[poise::async_trait]
+        impl #impl_generics ::poise::ParameterGroup<#u, #e> for #struct_ident {
+            const PARAMETER_COUNT: usize = #parameter_count;
+
+            #[allow(clippy::str_to_string)]
+            fn parameters() -> Vec<::poise::CommandParameter<#u, #e>> {
+                vec![ #( #parameter_structs ),* ]
+            }
+
+            async fn pop_from<'a>(
+                args: &'a str,
+                attachment_index: usize,
+                tokenizer: &::poise::Tokenizer,
+                ctx: &::poise::serenity_prelude::Context,
+                msg: &::poise::serenity_prelude::Message,
+            ) -> Result<(&'a str, usize, Self), ::poise::PrefixArgumentError> {
+                let ( #( #field_names, )* args, attachment_index ) = ::poise::parse_prefix_args!(
+                    ctx, msg, args, attachment_index, tokenizer =>
+                    #( #prefix_specs, )*
+                    #[remaining] ()
+                ).await?;
+                Ok((args, attachment_index, Self { #( #field_names ),* }))
+            }
+
+            async fn extract(
+                ctx: &impl ::poise::serenity_prelude::CacheHttp,
+                interaction: ::poise::CommandOrAutocompleteInteraction<'_>,
+                args: &[::poise::serenity_prelude::ResolvedOption<'_>],
+            ) -> Result<Self, ::poise::SlashArgError> {
+                let ( #( #field_names, )* ) = ::poise::parse_slash_args!(
+                    ctx, interaction, args =>
+                    #( (#slash_names: #slash_types), )*
+                ).await?;
+                Ok(Self { #( #field_names ),* })
+            }
+
+            #[allow(unused_variables)]
+            fn validate<'a>(
+                &self,
+                ctx: ::poise::Context<'a, #u, #e>,
+                parameter_offset: usize,
+            ) -> Result<(), ::poise::FrameworkError<'a, #u, #e>> {
+                let Self { #( #field_names ),* } = self;
+                #( #checks )*
+                Ok(())
+            }
+        }
+    }
+    .into())
+}
@@ -0,0 +1,177 @@
+//! Parsing code for [`Color`], a human-friendly color parameter type
+
+use super::*;
+
+/// Error thrown when the user enters a string that is not recognized as a [`Color`]
+#[derive(Debug)]
+pub struct InvalidColor;
+impl std::fmt::Display for InvalidColor {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("Expected a color like `#ff0000`, `rgb(255, 0, 0)` or `red`")
+    }
+}
+impl std::error::Error for InvalidColor {}
+
+/// A command parameter type for colors, for example for embeds or roles
+///
+/// Accepts hex codes with or without `#` or `0x` prefix (`#ff0000`, `ff0000`, `0xff0000`, `#f00`),
+/// `rgb(255, 0, 0)` and a few common color names like `red`, `orange` or `blurple`.
+///
+/// ```rust
+/// let color: poise::Color = "rgb(88, 101, 242)".parse().unwrap();
+/// assert_eq!(color, "blurple".parse().unwrap());
+/// assert_eq!(color.to_string(), "#5865f2");
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Color(pub serenity::Colour);
+
+/// Maximum number of words a color like `rgb( 255, 0, 0 )` can span
+const MAX_WORDS: usize = 5;
+
+/// Colors which can be referred to by name
+const NAMED_COLORS: &[(&str, u32)] = &[
+    ("black", 0x000000),
+    ("white", 0xffffff),
+    ("gray", 0x808080),
+    ("grey", 0x808080),
+    ("red", 0xff0000),
+    ("orange", 0xffa500),
+    ("yellow", 0xffff00),
+    ("green", 0x008000),
+    ("lime", 0x00ff00),
+    ("cyan", 0x00ffff),
+    ("blue", 0x0000ff),
+    ("purple", 0x800080),
+    ("magenta", 0xff00ff),
+    ("pink", 0xffc0cb),
+    ("brown", 0xa52a2a),
+    ("blurple", 0x5865f2),
+];
+
+/// Parses a hex code, `rgb(...)` or color name into a 24-bit RGB value
+fn parse_color(input: &str) -> Option<u32> {
+    let input = input.trim();
+
+    if let Some(components) = input.strip_prefix("rgb(").and_then(|x| x.strip_suffix(')')) {
+        let mut value = 0;
+        let mut count = 0;
+        for component in components.split(',') {
+            value = (value << 8) | u32::from(component.trim().parse::<u8>().ok()?);
+            count += 1;
+        }
+        return if count == 3 { Some(value) } else { None };
+    }
+
+    let hex = input
+        .strip_prefix('#')
+        .or_else(|| input.strip_prefix("0x"))
+        .unwrap_or(input);
+    if hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        match hex.len() {
+            6 => return u32::from_str_radix(hex, 16).ok(),
+            // Shorthand like `#f00`, where each digit is doubled
+            3 => {
+                return hex.chars().try_fold(0, |value, digit| {
+                    let digit = digit.to_digit(16)?;
+                    Some((value << 8) | (digit * 0x11))
+                })
+            }
+            _ => {}
+        }
+    }
+
+    NAMED_COLORS
+        .iter()
+        .find(|(name, _)| name.eq_ignore_ascii_case(input))
+        .map(|&(_, value)| value)
+}
+
+impl std::str::FromStr for Color {
+    type Err = InvalidColor;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse_color(s)
+            .map(|value| Self(serenity::Colour::new(value)))
+            .ok_or(InvalidColor)
+    }
+}
+
+impl std::fmt::Display for Color {
+    /// Formats the color as lowercase hex code, like `#ff0000`
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "#{:06x}", self.0 .0)
+    }
+}
+
+impl From<serenity::Colour> for Color {
+    fn from(color: serenity::Colour) -> Self {
+        Self(color)
+    }
+}
+
+impl From<Color> for serenity::Colour {
+    fn from(color: Color) -> Self {
+        color.0
+    }
+}
+
+#[async_trait::async_trait]
+impl<'a> crate::PopArgument<'a> for Color {
+    async fn pop_from(
+        args: &'a str,
+        attachment_index: usize,
+        ctx: &serenity::Context,
+        msg: &serenity::Message,
+    ) -> Result<(&'a str, usize, Self), (Box<dyn std::error::Error + Send + Sync>, Option<String>)>
+    {
+        let tokenizer = crate::Tokenizer::default();
+        Self::pop_from_tokenized(args, attachment_index, &tokenizer, ctx, msg).await
+    }
+
+    /// Parses a single word, or multiple words for `rgb(...)` with spaces inside
+    async fn pop_from_tokenized(
+        args: &'a str,
+        attachment_index: usize,
+        tokenizer: &crate::Tokenizer,
+        _: &serenity::Context,
+        _: &serenity::Message,
+    ) -> Result<(&'a str, usize, Self), (Box<dyn std::error::Error + Send + Sync>, Option<String>)>
+    {
+        let (args, color) = pop_words(args, MAX_WORDS, tokenizer, str::parse::<Self>)?;
+        Ok((args, attachment_index, color))
+    }
+}
+
+#[async_trait::async_trait]
+impl crate::SlashArgument for Color {
+    async fn extract(
+        _: &impl serenity::CacheHttp,
+        _: crate::CommandOrAutocompleteInteraction<'_>,
+        value: &serenity::ResolvedValue<'_>,
+    ) -> Result<Self, crate::SlashArgError> {
+        extract_slash_string(value, str::parse::<Self>)
+    }
+
+    fn create(builder: serenity::CreateCommandOption) -> serenity::CreateCommandOption {
+        builder.kind(serenity::CommandOptionType::String)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_parse_color() {
+        assert_eq!(parse_color("#ff0000"), Some(0xff0000));
+        assert_eq!(parse_color("00FF00"), Some(0x00ff00));
+        assert_eq!(parse_color("0x0000ff"), Some(0x0000ff));
+        assert_eq!(parse_color("#f80"), Some(0xff8800));
+        assert_eq!(parse_color("rgb(255, 128,0)"), Some(0xff8000));
+        assert_eq!(parse_color("Blurple"), Some(0x5865f2));
+        assert_eq!(parse_color("rgb(256, 0, 0)"), None);
+        assert_eq!(parse_color("rgb(1, 2)"), None);
+        assert_eq!(parse_color("#ff00"), None);
+        assert_eq!(parse_color("reddish"), None);
+    }
+}
@@ -0,0 +1,200 @@
+//! Parsing code for [`Duration`], a human-friendly duration parameter type
+
+use super::*;
+
+/// Error thrown when the user enters a string that is not recognized as a [`Duration`]
+#[derive(Debug)]
+pub struct InvalidDuration;
+impl std::fmt::Display for InvalidDuration {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("Expected a duration like `10m`, `2h30m` or `1d`")
+    }
+}
+impl std::error::Error for InvalidDuration {}
+
+/// A command parameter type for durations like `10m`, `2h30m`, `1.5d` or `2 hours 30 minutes`
+///
+/// Recognized units are milliseconds (`ms`), seconds (`s`, `sec`, `second`), minutes (`m`, `min`,
+/// `minute`), hours (`h`, `hr`, `hour`), days (`d`, `day`), weeks (`w`, `wk`, `week`), months
+/// (`mo`, `month`, 30 days) and years (`y`, `yr`, `year`, 365 days), each also in plural. Every
+/// number needs a unit.
+///
+/// Supports `#[min = "..."]` and `#[max = "..."]` bounds in the [`crate::command`] macro, like
+/// `#[max = "28d"]`.
+///
+/// ```rust
+/// # use std::time::Duration;
+/// let duration: poise::Duration = "2h 30m".parse().unwrap();
+/// assert_eq!(duration.0, Duration::from_secs(2 * 60 * 60 + 30 * 60));
+/// assert_eq!(duration.to_string(), "2h 30m");
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Duration(pub std::time::Duration);
+
+/// Maximum number of words a duration like `1 day 2 hours 3 minutes 4 seconds` can span
+const MAX_WORDS: usize = 8;
+
+/// Returns the number of seconds in the given (lowercase) unit
+fn unit_seconds(unit: &str) -> Option<f64> {
+    Some(match unit {
+        "ms" | "msec" | "msecs" | "millisecond" | "milliseconds" => 0.001,
+        "s" | "sec" | "secs" | "second" | "seconds" => 1.0,
+        "m" | "min" | "mins" | "minute" | "minutes" => 60.0,
+        "h" | "hr" | "hrs" | "hour" | "hours" => 60.0 * 60.0,
+        "d" | "day" | "days" => 24.0 * 60.0 * 60.0,
+        "w" | "wk" | "wks" | "week" | "weeks" => 7.0 * 24.0 * 60.0 * 60.0,
+        "mo" | "month" | "months" => 30.0 * 24.0 * 60.0 * 60.0,
+        "y" | "yr" | "yrs" | "year" | "years" => 365.0 * 24.0 * 60.0 * 60.0,
+        _ => return None,
+    })
+}
+
+/// Parses a sequence of numbers with units, optionally separated by whitespace, commas or `and`
+fn parse_duration(input: &str) -> Option<std::time::Duration> {
+    let mut rest = input.trim();
+    if rest.is_empty() {
+        return None;
+    }
+
+    let mut seconds = 0.0;
+    while !rest.is_empty() {
+        let number_len = rest
+            .find(|c: char| !c.is_ascii_digit() && c != '.')
+            .unwrap_or(rest.len());
+        let number = rest[..number_len].parse::<f64>().ok()?;
+        rest = rest[number_len..].trim_start();
+
+        let unit_len = rest
+            .find(|c: char| !c.is_alphabetic())
+            .unwrap_or(rest.len());
+        seconds += number * unit_seconds(&rest[..unit_len].to_lowercase())?;
+        rest = rest[unit_len..].trim_start_matches(|c: char| c.is_whitespace() || c == ',');
+
+        if let Some(after_and) = rest.strip_prefix("and") {
+            if after_and.starts_with(char::is_whitespace) {
+                rest = after_and.trim_start();
+            }
+        }
+    }
+
+    // Duration::from_secs_f64 panics on overflow
+    if seconds >= u64::MAX as f64 {
+        return None;
+    }
+    Some(std::time::Duration::from_secs_f64(seconds))
+}
+
+impl std::str::FromStr for Duration {
+    type Err = InvalidDuration;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse_duration(s).map(Self).ok_or(InvalidDuration)
+    }
+}
+
+impl std::fmt::Display for Duration {
+    /// Formats the duration in the short form with days, hours, minutes, seconds and
+    /// milliseconds, like `1d 2h 30m`
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let seconds = self.0.as_secs();
+        let components = [
+            (seconds / (24 * 60 * 60), "d"),
+            (seconds / (60 * 60) % 24, "h"),
+            (seconds / 60 % 60, "m"),
+            (seconds % 60, "s"),
+            (u64::from(self.0.subsec_millis()), "ms"),
+        ];
+
+        let mut is_first = true;
+        for &(value, unit) in components.iter().filter(|(value, _)| *value != 0) {
+            if !is_first {
+                f.write_str(" ")?;
+            }
+            write!(f, "{}{}", value, unit)?;
+            is_first = false;
+        }
+        if is_first {
+            f.write_str("0s")?;
+        }
+        Ok(())
+    }
+}
+
+impl From<std::time::Duration> for Duration {
+    fn from(duration: std::time::Duration) -> Self {
+        Self(duration)
+    }
+}
+
+impl From<Duration> for std::time::Duration {
+    fn from(duration: Duration) -> Self {
+        duration.0
+    }
+}
+
+#[async_trait::async_trait]
+impl<'a> crate::PopArgument<'a> for Duration {
+    async fn pop_from(
+        args: &'a str,
+        attachment_index: usize,
+        ctx: &serenity::Context,
+        msg: &serenity::Message,
+    ) -> Result<(&'a str, usize, Self), (Box<dyn std::error::Error + Send + Sync>, Option<String>)>
+    {
+        let tokenizer = crate::Tokenizer::default();
+        Self::pop_from_tokenized(args, attachment_index, &tokenizer, ctx, msg).await
+    }
+
+    /// Parses the longest run of words which form a duration, so both `2h30m` and `2 hours 30
+    /// minutes` are consumed entirely
+    async fn pop_from_tokenized(
+        args: &'a str,
+        attachment_index: usize,
+        tokenizer: &crate::Tokenizer,
+        _: &serenity::Context,
+        _: &serenity::Message,
+    ) -> Result<(&'a str, usize, Self), (Box<dyn std::error::Error + Send + Sync>, Option<String>)>
+    {
+        let (args, duration) = pop_words(args, MAX_WORDS, tokenizer, str::parse::<Self>)?;
+        Ok((args, attachment_index, duration))
+    }
+}
+
+#[async_trait::async_trait]
+impl crate::SlashArgument for Duration {
+    async fn extract(
+        _: &impl serenity::CacheHttp,
+        _: crate::CommandOrAutocompleteInteraction<'_>,
+        value: &serenity::ResolvedValue<'_>,
+    ) -> Result<Self, crate::SlashArgError> {
+        extract_slash_string(value, str::parse::<Self>)
+    }
+
+    fn create(builder: serenity::CreateCommandOption) -> serenity::CreateCommandOption {
+        builder.kind(serenity::CommandOptionType::String)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_parse_duration() {
+        let secs = |s: &str| parse_duration(s).map(|d| d.as_secs_f64());
+        assert_eq!(secs("10m"), Some(600.0));
+        assert_eq!(secs("2h30m"), Some(9000.0));
+        assert_eq!(secs("2 hours, 30 Minutes and 5s"), Some(9005.0));
+        assert_eq!(secs("1.5d"), Some(129600.0));
+        assert_eq!(secs("1w 500ms"), Some(604800.5));
+        assert_eq!(secs("10"), None);
+        assert_eq!(secs("m"), None);
+        assert_eq!(secs("10 lightyears"), None);
+        assert_eq!(secs(""), None);
+
+        let display = |s: &str| s.parse::<Duration>().unwrap().to_string();
+        assert_eq!(display("90m"), "1h 30m");
+        assert_eq!(display("1d 1ms"), "1d 1ms");
+        assert_eq!(display("0s"), "0s");
+    }
+}
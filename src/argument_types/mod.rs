@@ -0,0 +1,115 @@
+//! Built-in parameter types for common Discord bot inputs like durations, timestamps and colors,
+//! usable in both prefix and slash commands
+
+mod color;
+pub use color::*;
+
+mod duration;
+pub use duration::*;
+
+mod timestamp;
+pub use timestamp::*;
+
+use crate::serenity_prelude as serenity;
+
+/// Pops the longest run of up to `max_words` words from the front of `args` which `parse`
+/// accepts, joined by single spaces. Used by the argument types whose input may contain spaces,
+/// like `2h 30m`. Words are split with the given [`crate::Tokenizer`], so quoted input like
+/// `"2h 30m"` is a single word
+///
+/// If no run of words can be parsed, the error for the first word is returned.
+fn pop_words<'a, T, E>(
+    args: &'a str,
+    max_words: usize,
+    tokenizer: &crate::Tokenizer,
+    parse: impl Fn(&str) -> Result<T, E>,
+) -> Result<(&'a str, T), (Box<dyn std::error::Error + Send + Sync>, Option<String>)>
+where
+    E: std::error::Error + Send + Sync + 'static,
+{
+    // The first `max_words` words, each with the arguments remaining after it
+    let mut words = Vec::new();
+    let mut rest = args;
+    while words.len() < max_words {
+        match tokenizer.pop_word(rest) {
+            Ok((remaining, word)) => {
+                rest = remaining;
+                words.push((word, remaining));
+            }
+            Err(_) => break,
+        }
+    }
+    if words.is_empty() {
+        return Err((crate::TooFewArguments.into(), None));
+    }
+
+    for count in (2..=words.len()).rev() {
+        let input = words[..count]
+            .iter()
+            .map(|(word, _)| word.as_str())
+            .collect::<Vec<_>>()
+            .join(" ");
+        if let Ok(value) = parse(&input) {
+            return Ok((words[count - 1].1.trim_start(), value));
+        }
+    }
+    let (first_word, rest) = &words[0];
+    match parse(first_word) {
+        Ok(value) => Ok((rest.trim_start(), value)),
+        Err(e) => Err((e.into(), Some(first_word.clone()))),
+    }
+}
+
+/// Extracts the string of a slash command option and parses it with `parse`
+fn extract_slash_string<T, E>(
+    value: &serenity::ResolvedValue<'_>,
+    parse: impl Fn(&str) -> Result<T, E>,
+) -> Result<T, crate::SlashArgError>
+where
+    E: std::error::Error + Send + Sync + 'static,
+{
+    let string = match *value {
+        serenity::ResolvedValue::String(s) => s,
+        _ => {
+            return Err(crate::SlashArgError::CommandStructureMismatch(
+                "expected string",
+            ))
+        }
+    };
+    parse(string).map_err(|e| crate::SlashArgError::Parse {
+        error: e.into(),
+        input: string.into(),
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_pop_words() {
+        let parse = |s: &str| match s {
+            "a" | "a b" | "a b c" => Ok(s.len()),
+            _ => Err(crate::TooFewArguments),
+        };
+        let tokenizer = crate::Tokenizer::default();
+        assert_eq!(
+            pop_words(" a b c d", 5, &tokenizer, parse).unwrap(),
+            ("d", 5)
+        );
+        assert_eq!(pop_words("a b c", 2, &tokenizer, parse).unwrap(), ("c", 3));
+        assert_eq!(pop_words("a x", 5, &tokenizer, parse).unwrap(), ("x", 1));
+        assert_eq!(
+            pop_words(r#""a b" x"#, 5, &tokenizer, parse).unwrap(),
+            ("x", 3)
+        );
+        assert_eq!(
+            pop_words("x a", 5, &tokenizer, parse)
+                .unwrap_err()
+                .1
+                .as_deref(),
+            Some("x")
+        );
+        assert!(pop_words("  ", 5, &tokenizer, parse).is_err());
+    }
+}
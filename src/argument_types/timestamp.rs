@@ -0,0 +1,163 @@
+//! Parsing code for [`Timestamp`], a human-friendly point in time parameter type
+
+use super::*;
+
+/// Error thrown when the user enters a string that is not recognized as a [`Timestamp`]
+#[derive(Debug)]
+pub struct InvalidTimestamp;
+impl std::fmt::Display for InvalidTimestamp {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("Expected a timestamp like `<t:1700000000>`, `2024-01-31`, `in 2h` or `1d ago`")
+    }
+}
+impl std::error::Error for InvalidTimestamp {}
+
+/// A command parameter type for points in time
+///
+/// Accepts Discord timestamp markdown (`<t:1700000000:R>`), Unix timestamps in seconds, RFC 3339
+/// dates (`2024-01-31T12:00:00Z` or just `2024-01-31`), `now`, and times relative to now using
+/// [`crate::Duration`] syntax (`in 2h 30m`, `1d ago`).
+///
+/// Supports `#[min = "..."]` and `#[max = "..."]` bounds in the [`crate::command`] macro. Relative
+/// bounds are evaluated when the command is invoked, so `#[min = "now"]` only allows times in the
+/// future.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Timestamp(pub serenity::Timestamp);
+
+/// Maximum number of words a timestamp like `in 1 day 2 hours 3 minutes 4 seconds` can span
+const MAX_WORDS: usize = 9;
+
+/// Parses the formats of [`Timestamp`] which can be expressed as Unix timestamps without a date
+/// parser, relative to the given current Unix timestamp
+fn parse_unix_timestamp(input: &str, now: i64) -> Option<i64> {
+    let input = input.trim();
+    if input.eq_ignore_ascii_case("now") {
+        return Some(now);
+    }
+
+    if let Some(markdown) = input.strip_prefix("<t:").and_then(|x| x.strip_suffix('>')) {
+        // Strip the optional style, like `:R`
+        let seconds = markdown.split(':').next().unwrap_or(markdown);
+        return seconds.parse().ok();
+    }
+    if let Ok(seconds) = input.parse() {
+        return Some(seconds);
+    }
+
+    let relative_seconds = |duration: &str| -> Option<i64> {
+        let duration = duration.parse::<crate::Duration>().ok()?;
+        std::convert::TryFrom::try_from(duration.0.as_secs()).ok()
+    };
+    if let Some(duration) = input.strip_prefix("in ") {
+        return now.checked_add(relative_seconds(duration)?);
+    }
+    if let Some(duration) = input.strip_suffix(" ago") {
+        return now.checked_sub(relative_seconds(duration)?);
+    }
+
+    None
+}
+
+impl std::str::FromStr for Timestamp {
+    type Err = InvalidTimestamp;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let now = serenity::Timestamp::now().unix_timestamp();
+        let timestamp = match parse_unix_timestamp(s, now) {
+            Some(seconds) => serenity::Timestamp::from_unix_timestamp(seconds).ok(),
+            None => serenity::Timestamp::parse(s)
+                .or_else(|_| serenity::Timestamp::parse(&format!("{}T00:00:00Z", s)))
+                .ok(),
+        };
+        timestamp.map(Self).ok_or(InvalidTimestamp)
+    }
+}
+
+impl std::fmt::Display for Timestamp {
+    /// Formats the timestamp as Discord timestamp markdown, which Discord displays in the
+    /// reader's time zone
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "<t:{}:f>", self.0.unix_timestamp())
+    }
+}
+
+impl From<serenity::Timestamp> for Timestamp {
+    fn from(timestamp: serenity::Timestamp) -> Self {
+        Self(timestamp)
+    }
+}
+
+impl From<Timestamp> for serenity::Timestamp {
+    fn from(timestamp: Timestamp) -> Self {
+        timestamp.0
+    }
+}
+
+#[async_trait::async_trait]
+impl<'a> crate::PopArgument<'a> for Timestamp {
+    async fn pop_from(
+        args: &'a str,
+        attachment_index: usize,
+        ctx: &serenity::Context,
+        msg: &serenity::Message,
+    ) -> Result<(&'a str, usize, Self), (Box<dyn std::error::Error + Send + Sync>, Option<String>)>
+    {
+        let tokenizer = crate::Tokenizer::default();
+        Self::pop_from_tokenized(args, attachment_index, &tokenizer, ctx, msg).await
+    }
+
+    /// Parses the longest run of words which form a timestamp, so relative times like
+    /// `in 2 hours` are consumed entirely
+    async fn pop_from_tokenized(
+        args: &'a str,
+        attachment_index: usize,
+        tokenizer: &crate::Tokenizer,
+        _: &serenity::Context,
+        _: &serenity::Message,
+    ) -> Result<(&'a str, usize, Self), (Box<dyn std::error::Error + Send + Sync>, Option<String>)>
+    {
+        let (args, timestamp) = pop_words(args, MAX_WORDS, tokenizer, str::parse::<Self>)?;
+        Ok((args, attachment_index, timestamp))
+    }
+}
+
+#[async_trait::async_trait]
+impl crate::SlashArgument for Timestamp {
+    async fn extract(
+        _: &impl serenity::CacheHttp,
+        _: crate::CommandOrAutocompleteInteraction<'_>,
+        value: &serenity::ResolvedValue<'_>,
+    ) -> Result<Self, crate::SlashArgError> {
+        extract_slash_string(value, str::parse::<Self>)
+    }
+
+    fn create(builder: serenity::CreateCommandOption) -> serenity::CreateCommandOption {
+        builder.kind(serenity::CommandOptionType::String)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_parse_unix_timestamp() {
+        let now = 1_700_000_000;
+        assert_eq!(parse_unix_timestamp("now", now), Some(now));
+        assert_eq!(
+            parse_unix_timestamp("<t:1618953630>", now),
+            Some(1618953630)
+        );
+        assert_eq!(
+            parse_unix_timestamp("<t:1618953630:R>", now),
+            Some(1618953630)
+        );
+        assert_eq!(parse_unix_timestamp("1618953630", now), Some(1618953630));
+        assert_eq!(parse_unix_timestamp("in 1h 30m", now), Some(now + 5400));
+        assert_eq!(parse_unix_timestamp("2 days ago", now), Some(now - 172800));
+        assert_eq!(parse_unix_timestamp("<t:abc>", now), None);
+        assert_eq!(parse_unix_timestamp("in", now), None);
+        assert_eq!(parse_unix_timestamp("2024-01-31", now), None);
+    }
+}
@@ -0,0 +1,228 @@
+//! Restrictions on attachment parameters, checked before the command runs
+
+use crate::serenity_prelude as serenity;
+
+/// Number of slash command options registered for a `Vec<serenity::Attachment>` parameter without
+/// [`AttachmentConstraints::max_count`]. Kept small because a slash command can have at most 25
+/// options in total. The [`crate::command`] macro has a copy of this value
+const DEFAULT_MAX_COUNT: usize = 3;
+
+/// Restrictions on the attachments passed to an attachment parameter, see
+/// [`crate::CommandParameterDetails::attachment_constraints`]
+///
+/// Set with the `#[content_types(...)]`, `#[max_size = ...]` and `#[max_count = ...]` parameter
+/// attributes of the [`crate::command`] macro. Violations are reported as
+/// [`crate::FrameworkError::AttachmentConstraint`].
+#[derive(Clone, Default, Debug)]
+pub struct AttachmentConstraints {
+    /// Allowed MIME types, like `image/png`. A trailing `/*` allows all subtypes, like `image/*`.
+    /// If empty, any content type is allowed
+    pub content_types: Vec<String>,
+    /// Maximum size of every single attachment in bytes
+    pub max_size: Option<u64>,
+    /// Maximum number of attachments for `Vec` parameters
+    ///
+    /// In slash commands, this is the number of attachment options registered for the parameter
+    /// (3 by default).
+    pub max_count: Option<usize>,
+    #[doc(hidden)]
+    pub __non_exhaustive: (),
+}
+
+impl AttachmentConstraints {
+    /// Checks the given attachments against these constraints
+    pub fn check(
+        &self,
+        attachments: &[&serenity::Attachment],
+    ) -> Result<(), AttachmentConstraintViolation> {
+        if let Some(max_count) = self.max_count {
+            if attachments.len() > max_count {
+                return Err(AttachmentConstraintViolation::TooMany {
+                    count: attachments.len(),
+                    max_count,
+                });
+            }
+        }
+
+        for attachment in attachments {
+            let content_type = attachment.content_type.as_deref();
+            if !self.is_content_type_allowed(content_type) {
+                return Err(AttachmentConstraintViolation::ContentType {
+                    filename: attachment.filename.clone(),
+                    content_type: content_type.map(|x| x.to_owned()),
+                });
+            }
+            if let Some(max_size) = self.max_size {
+                if u64::from(attachment.size) > max_size {
+                    return Err(AttachmentConstraintViolation::TooLarge {
+                        filename: attachment.filename.clone(),
+                        size: attachment.size.into(),
+                        max_size,
+                    });
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Whether [`Self::content_types`] allows the given content type. Parameters like
+    /// `; charset=utf-8` are ignored
+    pub fn is_content_type_allowed(&self, content_type: Option<&str>) -> bool {
+        if self.content_types.is_empty() {
+            return true;
+        }
+        let content_type = match content_type {
+            Some(x) => x.split(';').next().unwrap_or(x).trim(),
+            None => return false,
+        };
+
+        self.content_types
+            .iter()
+            .any(|allowed| match allowed.strip_suffix("/*") {
+                Some(type_) => content_type
+                    .split_once('/')
+                    .map_or(false, |(x, _)| x.eq_ignore_ascii_case(type_)),
+                None => allowed.eq_ignore_ascii_case(content_type),
+            })
+    }
+
+    /// Number of slash command options that a `Vec` parameter with these constraints is
+    /// registered as
+    pub(crate) fn slash_option_count(&self) -> usize {
+        self.max_count.unwrap_or(DEFAULT_MAX_COUNT).max(1)
+    }
+}
+
+/// Which [`AttachmentConstraints`] were violated, see
+/// [`crate::FrameworkError::AttachmentConstraint`]
+#[derive(Clone, Debug)]
+pub enum AttachmentConstraintViolation {
+    /// An attachment's content type isn't in [`AttachmentConstraints::content_types`]
+    ContentType {
+        /// File name of the attachment
+        filename: String,
+        /// Content type of the attachment, if Discord detected one
+        content_type: Option<String>,
+    },
+    /// An attachment is larger than [`AttachmentConstraints::max_size`]
+    TooLarge {
+        /// File name of the attachment
+        filename: String,
+        /// Size of the attachment in bytes
+        size: u64,
+        /// Maximum size in bytes
+        max_size: u64,
+    },
+    /// More attachments than [`AttachmentConstraints::max_count`] were passed
+    TooMany {
+        /// Number of passed attachments
+        count: usize,
+        /// Maximum number of attachments
+        max_count: usize,
+    },
+}
+
+impl std::fmt::Display for AttachmentConstraintViolation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::ContentType {
+                filename,
+                content_type: Some(content_type),
+            } => write!(
+                f,
+                "`{}` has unsupported file type {}",
+                filename, content_type
+            ),
+            Self::ContentType {
+                filename,
+                content_type: None,
+            } => write!(f, "`{}` has an unknown file type", filename),
+            Self::TooLarge {
+                filename,
+                size,
+                max_size,
+            } => write!(
+                f,
+                "`{}` is too large ({} bytes, must be max {} bytes)",
+                filename, size, max_size
+            ),
+            Self::TooMany { count, max_count } => write!(
+                f,
+                "Too many attachments ({}, must be max {})",
+                count, max_count
+            ),
+        }
+    }
+}
+
+impl std::error::Error for AttachmentConstraintViolation {}
+
+/// Implemented for the parameter types which can have [`AttachmentConstraints`]
+#[doc(hidden)]
+pub trait AttachmentParameter {
+    /// Returns the attachments that were passed to the parameter
+    fn attachments(&self) -> Vec<&serenity::Attachment>;
+}
+
+impl AttachmentParameter for serenity::Attachment {
+    fn attachments(&self) -> Vec<&serenity::Attachment> {
+        vec![self]
+    }
+}
+
+impl<T: AttachmentParameter> AttachmentParameter for Option<T> {
+    fn attachments(&self) -> Vec<&serenity::Attachment> {
+        self.as_ref().map_or_else(Vec::new, |x| x.attachments())
+    }
+}
+
+impl AttachmentParameter for Vec<serenity::Attachment> {
+    fn attachments(&self) -> Vec<&serenity::Attachment> {
+        self.iter().collect()
+    }
+}
+
+/// Checks the value of an attachment parameter against the
+/// [`crate::CommandParameterDetails::attachment_constraints`] of the parameter at the given index
+///
+/// Mainly used in the desugared [`crate::command`] macro
+#[doc(hidden)]
+pub fn check_attachment_parameter<'a, U, E>(
+    ctx: crate::Context<'a, U, E>,
+    parameter_index: usize,
+    value: &impl AttachmentParameter,
+) -> Result<(), crate::FrameworkError<'a, U, E>> {
+    let parameter = &ctx.command().parameters[parameter_index];
+    let constraints = match &parameter.details.attachment_constraints {
+        Some(x) => x,
+        None => return Ok(()),
+    };
+
+    constraints
+        .check(&value.attachments())
+        .map_err(|violation| crate::FrameworkError::AttachmentConstraint {
+            violation,
+            parameter: &parameter.name,
+            ctx,
+        })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_content_types() {
+        let constraints = AttachmentConstraints {
+            content_types: vec!["image/*".into(), "application/pdf".into()],
+            ..Default::default()
+        };
+        assert!(constraints.is_content_type_allowed(Some("image/png")));
+        assert!(constraints.is_content_type_allowed(Some("application/PDF; charset=utf-8")));
+        assert!(!constraints.is_content_type_allowed(Some("text/plain")));
+        assert!(!constraints.is_content_type_allowed(Some("imagex/png")));
+        assert!(!constraints.is_content_type_allowed(None));
+        assert!(AttachmentConstraints::default().is_content_type_allowed(None));
+    }
+}
@@ -0,0 +1,173 @@
+//! Contains a reusable confirmation prompt with confirm and cancel buttons
+
+use crate::serenity_prelude as serenity;
+
+/// Outcome of a [`confirm()`] prompt
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ConfirmationResult {
+    /// The confirm button was pressed
+    Confirmed,
+    /// The cancel button was pressed
+    Cancelled,
+    /// No button was pressed before the timeout
+    TimedOut,
+}
+
+/// Optional configuration for the confirmation prompt from [`confirm()`]
+pub struct ConfirmOptions<'a> {
+    /// Label of the confirm button. If `None`, [`crate::MessageCatalog::confirm_label`] is used
+    pub confirm_label: Option<&'a str>,
+    /// Style of the confirm button
+    pub confirm_style: serenity::ButtonStyle,
+    /// Label of the cancel button. If `None`, [`crate::MessageCatalog::confirm_cancel_label`] is
+    /// used
+    pub cancel_label: Option<&'a str>,
+    /// Style of the cancel button
+    pub cancel_style: serenity::ButtonStyle,
+    /// How long to wait for a button press
+    pub timeout: std::time::Duration,
+    /// Members with this role may answer the prompt in addition to the command invoker
+    pub allowed_role: Option<serenity::RoleId>,
+    /// Whether to make the prompt ephemeral if possible (only has an effect in application
+    /// commands)
+    pub ephemeral: bool,
+}
+
+impl Default for ConfirmOptions<'_> {
+    fn default() -> Self {
+        Self {
+            confirm_label: None,
+            confirm_style: serenity::ButtonStyle::Danger,
+            cancel_label: None,
+            cancel_style: serenity::ButtonStyle::Secondary,
+            timeout: std::time::Duration::from_secs(60),
+            allowed_role: None,
+            ephemeral: false,
+        }
+    }
+}
+
+/// Sends a prompt with a confirm and a cancel button and waits for one of them to be pressed
+///
+/// Only the command invoker (and, if configured, members with [`ConfirmOptions::allowed_role`])
+/// can answer; presses from anyone else are rejected with an ephemeral notice. Once answered or
+/// timed out, the buttons are removed from the prompt.
+///
+/// Works in both prefix and application commands. In application commands, if the initial
+/// response was already sent, the prompt is sent as a followup.
+///
+/// ```rust,no_run
+/// # async fn _test(ctx: poise::Context<'_, (), serenity::Error>) -> Result<(), serenity::Error> {
+/// use poise::builtins::{confirm, ConfirmOptions, ConfirmationResult};
+///
+/// let options = ConfirmOptions {
+///     confirm_label: Some("Delete everything"),
+///     ephemeral: true,
+///     ..Default::default()
+/// };
+/// match confirm(ctx, "Are you sure?", options).await? {
+///     ConfirmationResult::Confirmed => ctx.say("Deleted everything").await?,
+///     ConfirmationResult::Cancelled => ctx.say("Aborted").await?,
+///     ConfirmationResult::TimedOut => ctx.say("You didn't answer in time").await?,
+/// };
+/// # Ok(()) }
+/// ```
+pub async fn confirm<U, E>(
+    ctx: crate::Context<'_, U, E>,
+    prompt: impl Into<String>,
+    options: ConfirmOptions<'_>,
+) -> Result<ConfirmationResult, serenity::Error> {
+    // Define some unique identifiers for the buttons
+    let confirm_button_id = format!("{}confirm", ctx.id());
+    let cancel_button_id = format!("{}cancel", ctx.id());
+
+    let messages = &ctx.framework().options().messages;
+    let confirm_label = match options.confirm_label {
+        Some(label) => label.to_owned(),
+        None => messages.confirm_label(ctx.locale()),
+    };
+    let cancel_label = match options.cancel_label {
+        Some(label) => label.to_owned(),
+        None => messages.confirm_cancel_label(ctx.locale()),
+    };
+
+    let prompt = prompt.into();
+    let reply = ctx
+        .send(
+            crate::CreateReply::default()
+                .content(prompt.clone())
+                .ephemeral(options.ephemeral)
+                .components(vec![serenity::CreateActionRow::Buttons(vec![
+                    serenity::CreateButton::new(&confirm_button_id)
+                        .label(confirm_label)
+                        .style(options.confirm_style),
+                    serenity::CreateButton::new(&cancel_button_id)
+                        .label(cancel_label)
+                        .style(options.cancel_style),
+                ])]),
+        )
+        .await?;
+
+    // Rejected button presses must not extend the time the prompt stays open
+    let deadline = std::time::Instant::now() + options.timeout;
+    loop {
+        let (filter_confirm_id, filter_cancel_id) =
+            (confirm_button_id.clone(), cancel_button_id.clone());
+        let press = serenity::ComponentInteractionCollector::new(&ctx.serenity_context().shard)
+            .filter(move |press| {
+                let id = &press.data.custom_id;
+                *id == filter_confirm_id || *id == filter_cancel_id
+            })
+            .timeout(deadline.saturating_duration_since(std::time::Instant::now()))
+            .await;
+        let press = match press {
+            Some(x) => x,
+            None => {
+                // remove buttons after timeout
+                reply
+                    .edit(
+                        ctx,
+                        crate::CreateReply::default()
+                            .content(prompt)
+                            .components(vec![]),
+                    )
+                    .await?;
+                return Ok(ConfirmationResult::TimedOut);
+            }
+        };
+
+        let has_allowed_role = match (options.allowed_role, &press.member) {
+            (Some(role), Some(member)) => member.roles.contains(&role),
+            _ => false,
+        };
+        if press.user.id != ctx.author().id && !has_allowed_role {
+            press
+                .create_response(
+                    ctx.serenity_context(),
+                    serenity::CreateInteractionResponse::Message(
+                        serenity::CreateInteractionResponseMessage::new()
+                            .content(messages.confirm_not_allowed(ctx.locale()))
+                            .ephemeral(true),
+                    ),
+                )
+                .await?;
+            continue;
+        }
+
+        // remove buttons after button press
+        press
+            .create_response(
+                ctx.serenity_context(),
+                serenity::CreateInteractionResponse::UpdateMessage(
+                    serenity::CreateInteractionResponseMessage::new().components(vec![]),
+                ),
+            )
+            .await?;
+
+        return Ok(if press.data.custom_id == confirm_button_id {
+            ConfirmationResult::Confirmed
+        } else {
+            ConfirmationResult::Cancelled
+        });
+    }
+}
@@ -13,6 +13,13 @@ pub struct HelpConfiguration<'a> {
     pub ephemeral: bool,
     /// Whether to list context menu commands as well
     pub show_context_menu_commands: bool,
+    /// Whether to render the help as embeds instead of a code block
+    ///
+    /// In this mode, the command overview shows one page per category (navigable with a
+    /// [`super::Paginator`] if there's more than one), and the help for a single command lists its
+    /// parameters and subcommands. Names and descriptions are localized using
+    /// [`crate::Context::locale`]
+    pub embed: bool,
 }
 
 impl Default for HelpConfiguration<'_> {
@@ -21,44 +28,384 @@ impl Default for HelpConfiguration<'_> {
             extra_text_at_bottom: "",
             ephemeral: true,
             show_context_menu_commands: false,
+            embed: false,
         }
     }
 }
 
-/// Code for printing help of a specific command (e.g. `~help my_command`)
+/// Returns the localized variant of a name or description, if one exists for the given locale
+fn localized<'a>(
+    default: &'a str,
+    localizations: &'a std::collections::HashMap<String, String>,
+    locale: Option<&str>,
+) -> &'a str {
+    locale
+        .and_then(|locale| localizations.get(locale))
+        .map_or(default, |x| x.as_str())
+}
+
+/// Maximum number of characters in an embed description
+const EMBED_DESCRIPTION_LIMIT: usize = 4096;
+
+/// Maximum number of characters in an embed field value
+const EMBED_FIELD_VALUE_LIMIT: usize = 1024;
+
+/// Cuts off the text with an ellipsis if it has more than `limit` characters
+fn truncate(text: &str, limit: usize) -> std::borrow::Cow<'_, str> {
+    match text.char_indices().nth(limit) {
+        None => std::borrow::Cow::Borrowed(text),
+        Some(_) => {
+            let mut truncated = text.chars().take(limit - 1).collect::<String>();
+            truncated.push('…');
+            std::borrow::Cow::Owned(truncated)
+        }
+    }
+}
+
+/// Splits the text into chunks of at most `limit` characters. Splits happen at line breaks, unless
+/// a single line is too long by itself
+fn split_into_chunks(text: &str, limit: usize) -> Vec<String> {
+    let mut chunks = Vec::new();
+    let mut chunk = String::new();
+    let mut chunk_len = 0;
+    for mut line in text.split_inclusive('\n') {
+        loop {
+            let line_len = line.chars().count();
+            if chunk_len + line_len <= limit {
+                chunk += line;
+                chunk_len += line_len;
+                break;
+            }
+            if chunk_len > 0 {
+                chunks.push(std::mem::take(&mut chunk));
+                chunk_len = 0;
+                continue;
+            }
+            let (split, _) = line
+                .char_indices()
+                .nth(limit)
+                .expect("line is longer than limit");
+            chunks.push(line[..split].to_owned());
+            line = &line[split..];
+        }
+    }
+    if !chunk.is_empty() {
+        chunks.push(chunk);
+    }
+    chunks
+}
+
+/// Returns all top-level commands, including changes made at runtime via
+/// [`crate::CommandRegistry`]
+fn active_commands<'a, U, E>(
+    ctx: crate::Context<'a, U, E>,
+) -> impl Iterator<Item = &'a crate::Command<U, E>> + 'a {
+    let options = ctx.framework().options();
+    options.command_registry.commands(&options.commands)
+}
+
+/// Resolves a possibly qualified command name like `config set` into the chain of commands from
+/// the top-level command down to the requested subcommand
+fn find_command<'a, U, E>(
+    commands: &[&'a crate::Command<U, E>],
+    query: &str,
+    locale: Option<&str>,
+) -> Option<Vec<&'a crate::Command<U, E>>> {
+    let matches = |command: &crate::Command<U, E>, word: &str| {
+        command.name.eq_ignore_ascii_case(word)
+            || command.aliases.iter().any(|a| a.eq_ignore_ascii_case(word))
+            || localized(&command.name, &command.name_localizations, locale)
+                .eq_ignore_ascii_case(word)
+    };
+
+    // Context menu names may contain spaces, so they're matched against the whole query
+    if let Some(&command) = commands.iter().find(|command| {
+        command
+            .context_menu_name
+            .map_or(false, |name| name.eq_ignore_ascii_case(query))
+    }) {
+        return Some(vec![command]);
+    }
+
+    let mut words = query.split_whitespace();
+    let first = words.next()?;
+    let mut chain = vec![*commands.iter().find(|c| matches(**c, first))?];
+    for word in words {
+        let parent = *chain.last()?;
+        chain.push(parent.subcommands.iter().find(|c| matches(*c, word))?);
+    }
+    Some(chain)
+}
+
+/// Returns the prefix with which the given top-level command is invoked: `/` for slash commands,
+/// the configured prefix for prefix commands, or `None` for context menu-only commands
+async fn command_prefix<U, E>(
+    ctx: crate::Context<'_, U, E>,
+    command: &crate::Command<U, E>,
+) -> Option<String> {
+    if command.slash_action.is_some() {
+        return Some(String::from("/"));
+    }
+    command.prefix_action?;
+
+    let options = &ctx.framework().options().prefix_options;
+    Some(match &options.prefix {
+        Some(fixed_prefix) => fixed_prefix.clone(),
+        None => match options.dynamic_prefix {
+            Some(dynamic_prefix_callback) => {
+                match dynamic_prefix_callback(crate::PartialContext::from(ctx)).await {
+                    Ok(Some(dynamic_prefix)) => dynamic_prefix,
+                    // `String::new()` defaults to "" which is what we want
+                    Err(_) | Ok(None) => String::new(),
+                }
+            }
+            None => String::new(),
+        },
+    })
+}
+
+/// Renders the help embed for a single (sub)command
+fn single_command_embed<U, E>(
+    chain: &[&crate::Command<U, E>],
+    prefix: &str,
+    help_text: &str,
+    locale: Option<&str>,
+    messages: &dyn crate::MessageCatalog,
+    config: &HelpConfiguration<'_>,
+) -> serenity::CreateEmbed {
+    let command = chain[chain.len() - 1];
+    let qualified_name = chain
+        .iter()
+        .map(|c| localized(&c.name, &c.name_localizations, locale))
+        .collect::<Vec<_>>()
+        .join(" ");
+
+    let mut embed = serenity::CreateEmbed::new()
+        .title(format!("{}{}", prefix, qualified_name))
+        .description(truncate(help_text, EMBED_DESCRIPTION_LIMIT))
+        .field(
+            messages.help_usage(locale),
+            format!("```\n{}\n```", command.usage(prefix)),
+            false,
+        );
+
+    for param in &command.parameters {
+        let name = localized(&param.name, &param.name_localizations, locale);
+        let requirement = messages.help_requirement(locale, param.required);
+        let mut value = match &param.description {
+            Some(description) => {
+                localized(description, &param.description_localizations, locale).to_owned()
+            }
+            None => messages.help_no_description(locale),
+        };
+        if !param.choices.is_empty() {
+            let choices = param
+                .choices
+                .iter()
+                .map(|c| format!("`{}`", localized(&c.name, &c.localizations, locale)))
+                .collect::<Vec<_>>()
+                .join(", ");
+            let _ = write!(value, "\n{}", messages.help_choices(locale, &choices));
+        }
+        embed = embed.field(
+            format!("{} ({}, {})", name, param.details.type_name, requirement),
+            truncate(&value, EMBED_FIELD_VALUE_LIMIT),
+            false,
+        );
+    }
+
+    let mut subcommands = String::new();
+    for subcommand in &command.subcommands {
+        if subcommand.hide_in_help {
+            continue;
+        }
+        let _ = write!(
+            subcommands,
+            "`{}{} {}`",
+            prefix,
+            qualified_name,
+            localized(&subcommand.name, &subcommand.name_localizations, locale)
+        );
+        if let Some(description) = &subcommand.description {
+            let description = localized(description, &subcommand.description_localizations, locale);
+            let _ = write!(subcommands, " — {}", description);
+        }
+        subcommands += "\n";
+    }
+    if !subcommands.is_empty() {
+        embed = embed.field(
+            messages.help_subcommands(locale),
+            truncate(&subcommands, EMBED_FIELD_VALUE_LIMIT),
+            false,
+        );
+    }
+
+    if !config.extra_text_at_bottom.is_empty() {
+        embed = embed.footer(serenity::CreateEmbedFooter::new(
+            config.extra_text_at_bottom,
+        ));
+    }
+    embed
+}
+
+/// Code for printing help of a specific command (e.g. `~help my_command` or `~help config set`)
 async fn help_single_command<U, E>(
     ctx: crate::Context<'_, U, E>,
     command_name: &str,
     config: HelpConfiguration<'_>,
 ) -> Result<(), serenity::Error> {
-    let command = ctx.framework().options().commands.iter().find(|command| {
-        if command.name.eq_ignore_ascii_case(command_name) {
-            return true;
+    let locale = ctx.locale();
+    let messages = &*ctx.framework().options().messages;
+    let commands = active_commands(ctx).collect::<Vec<_>>();
+    let chain = match find_command(&commands, command_name, locale) {
+        Some(chain) => chain,
+        None => {
+            let reply = messages.help_unknown_command(locale, command_name);
+            say_ephemeral(ctx, &reply, config.ephemeral).await?;
+            return Ok(());
         }
-        if let Some(context_menu_name) = command.context_menu_name {
-            if context_menu_name.eq_ignore_ascii_case(command_name) {
-                return true;
+    };
+    let command = chain[chain.len() - 1];
+
+    let help_text = match command.help_text {
+        Some(f) => f(),
+        None => match &command.description {
+            Some(description) => {
+                localized(description, &command.description_localizations, locale).to_owned()
             }
+            None => messages.help_no_help_available(locale),
+        },
+    };
+
+    let prefix = command_prefix(ctx, chain[0]).await.unwrap_or_default();
+    if config.embed {
+        let embed = single_command_embed(&chain, &prefix, &help_text, locale, messages, &config);
+        ctx.send(
+            crate::CreateReply::default()
+                .embed(embed)
+                .ephemeral(config.ephemeral),
+        )
+        .await?;
+        return Ok(());
+    }
+
+    let reply = format!("{}\n\n```\n{}\n```", help_text, command.usage(&prefix));
+    say_ephemeral(ctx, &reply, config.ephemeral).await?;
+    Ok(())
+}
+
+/// Appends overview lines for the given command and all of its subcommands
+fn push_overview_lines<U, E>(
+    lines: &mut String,
+    command: &crate::Command<U, E>,
+    path: &str,
+    locale: Option<&str>,
+) {
+    if command.hide_in_help {
+        return;
+    }
+
+    let path = format!(
+        "{}{}",
+        path,
+        localized(&command.name, &command.name_localizations, locale)
+    );
+    let _ = write!(lines, "`{}`", path);
+    if let Some(description) = &command.description {
+        let description = localized(description, &command.description_localizations, locale);
+        let _ = write!(lines, " — {}", description);
+    }
+    *lines += "\n";
+
+    for subcommand in &command.subcommands {
+        push_overview_lines(lines, subcommand, &format!("{} ", path), locale);
+    }
+}
+
+/// Code for printing an overview of all commands as embeds, one page per category
+async fn help_all_commands_embed<U, E>(
+    ctx: crate::Context<'_, U, E>,
+    config: HelpConfiguration<'_>,
+) -> Result<(), serenity::Error> {
+    let locale = ctx.locale();
+    let messages = &ctx.framework().options().messages;
+
+    let mut categories = crate::util::OrderedMap::<Option<&str>, String>::new();
+    for command in active_commands(ctx) {
+        // Context menu only commands are listed separately below
+        let prefix = match command_prefix(ctx, command).await {
+            Some(prefix) => prefix,
+            None => continue,
+        };
+        let lines = categories.get_or_insert_with(command.category, String::new);
+        push_overview_lines(lines, command, &prefix, locale);
+    }
+
+    let mut pages = Vec::new();
+    for (category_name, lines) in categories {
+        if lines.is_empty() {
+            continue;
         }
+        let title = match category_name {
+            Some(category_name) => category_name.to_owned(),
+            None => messages.help_default_category(locale),
+        };
+        // Categories with many commands are spread across multiple pages
+        for chunk in split_into_chunks(&lines, EMBED_DESCRIPTION_LIMIT) {
+            pages.push((title.clone(), chunk));
+        }
+    }
 
-        false
-    });
+    if config.show_context_menu_commands {
+        let mut lines = String::new();
+        for command in active_commands(ctx) {
+            let user = match command.context_menu_action {
+                Some(crate::ContextMenuCommandAction::User(_)) => true,
+                Some(crate::ContextMenuCommandAction::Message(_)) => false,
+                None => continue,
+            };
+            let name = format!("`{}`", command.context_menu_name.unwrap_or(&command.name));
+            let _ = writeln!(
+                lines,
+                "{}",
+                messages.help_context_menu_command(locale, &name, user)
+            );
+        }
+        let title = messages.help_context_menu_commands(locale);
+        for chunk in split_into_chunks(&lines, EMBED_DESCRIPTION_LIMIT) {
+            pages.push((title.clone(), chunk));
+        }
+    }
 
-    let reply = if let Some(command) = command {
-        match command.help_text {
-            Some(f) => f(),
-            None => command
-                .description
-                .as_deref()
-                .unwrap_or("No help available")
-                .to_owned(),
+    let embeds = pages.into_iter().map(|(title, lines)| {
+        let embed = serenity::CreateEmbed::new().title(title).description(lines);
+        if config.extra_text_at_bottom.is_empty() {
+            embed
+        } else {
+            embed.footer(serenity::CreateEmbedFooter::new(
+                config.extra_text_at_bottom,
+            ))
         }
-    } else {
-        format!("No such command `{}`", command_name)
-    };
+    });
+    let mut embeds = embeds.collect::<Vec<_>>();
 
-    say_ephemeral(ctx, &reply, config.ephemeral).await?;
-    Ok(())
+    if embeds.len() <= 1 {
+        let embed = embeds.pop().unwrap_or_else(|| {
+            serenity::CreateEmbed::new().description(messages.help_no_commands(locale))
+        });
+        ctx.send(
+            crate::CreateReply::default()
+                .embed(embed)
+                .ephemeral(config.ephemeral),
+        )
+        .await?;
+        return Ok(());
+    }
+
+    super::Paginator::from_embeds(embeds)
+        .ephemeral(config.ephemeral)
+        .run(ctx)
+        .await
 }
 
 /// Code for printing an overview of all commands (e.g. `~help`)
@@ -67,43 +414,32 @@ async fn help_all_commands<U, E>(
     config: HelpConfiguration<'_>,
 ) -> Result<(), serenity::Error> {
     let mut categories = crate::util::OrderedMap::<Option<&str>, Vec<&crate::Command<U, E>>>::new();
-    for cmd in &ctx.framework().options().commands {
+    for cmd in active_commands(ctx) {
         categories
             .get_or_insert_with(cmd.category, Vec::new)
             .push(cmd);
     }
 
+    let locale = ctx.locale();
+    let messages = &ctx.framework().options().messages;
+
     let mut menu = String::from("```\n");
     for (category_name, commands) in categories {
-        menu += category_name.unwrap_or("Commands");
+        match category_name {
+            Some(category_name) => menu += category_name,
+            None => menu += &messages.help_default_category(locale),
+        }
         menu += ":\n";
         for command in commands {
             if command.hide_in_help {
                 continue;
             }
 
-            let prefix = if command.slash_action.is_some() {
-                String::from("/")
-            } else if command.prefix_action.is_some() {
-                let options = &ctx.framework().options().prefix_options;
-
-                match &options.prefix {
-                    Some(fixed_prefix) => fixed_prefix.clone(),
-                    None => match options.dynamic_prefix {
-                        Some(dynamic_prefix_callback) => {
-                            match dynamic_prefix_callback(crate::PartialContext::from(ctx)).await {
-                                Ok(Some(dynamic_prefix)) => dynamic_prefix,
-                                // `String::new()` defaults to "" which is what we want
-                                Err(_) | Ok(None) => String::new(),
-                            }
-                        }
-                        None => String::new(),
-                    },
-                }
-            } else {
+            let prefix = match command_prefix(ctx, command).await {
+                Some(prefix) => prefix,
                 // This is not a prefix or slash command, i.e. probably a context menu only command
                 // which we will only show later
-                continue;
+                None => continue,
             };
 
             let total_command_name_length = prefix.chars().count() + command.name.chars().count();
@@ -120,16 +456,20 @@ async fn help_all_commands<U, E>(
     }
 
     if config.show_context_menu_commands {
-        menu += "\nContext menu commands:\n";
+        let _ = write!(menu, "\n{}:\n", messages.help_context_menu_commands(locale));
 
-        for command in &ctx.framework().options().commands {
-            let kind = match command.context_menu_action {
-                Some(crate::ContextMenuCommandAction::User(_)) => "user",
-                Some(crate::ContextMenuCommandAction::Message(_)) => "message",
+        for command in active_commands(ctx) {
+            let user = match command.context_menu_action {
+                Some(crate::ContextMenuCommandAction::User(_)) => true,
+                Some(crate::ContextMenuCommandAction::Message(_)) => false,
                 None => continue,
             };
             let name = command.context_menu_name.unwrap_or(&command.name);
-            let _ = writeln!(menu, "  {} (on {})", name, kind);
+            let _ = writeln!(
+                menu,
+                "  {}",
+                messages.help_context_menu_command(locale, name, user)
+            );
         }
     }
 
@@ -194,6 +534,10 @@ async fn help_all_commands<U, E>(
 /// Type ?help command for more info on a command.
 /// You can edit your message to the bot and the bot will edit its response.
 /// ```
+///
+/// Subcommands can be looked up by their qualified name, e.g. `?help config set`. Set
+/// [`HelpConfiguration::embed`] for an embed-based help with one page per category, which also
+/// lists parameters and subcommands of individual commands.
 pub async fn help<U, E>(
     ctx: crate::Context<'_, U, E>,
     command: Option<&str>,
@@ -201,6 +545,49 @@ pub async fn help<U, E>(
 ) -> Result<(), serenity::Error> {
     match command {
         Some(command) => help_single_command(ctx, command, config).await,
+        None if config.embed => help_all_commands_embed(ctx, config).await,
         None => help_all_commands(ctx, config).await,
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_truncate() {
+        assert_eq!(truncate("short", 10), "short");
+        assert_eq!(truncate("exactly10!", 10), "exactly10!");
+        assert_eq!(truncate("ünïcödé text", 6), "ünïcö…");
+
+        let description = "a".repeat(EMBED_DESCRIPTION_LIMIT + 100);
+        let truncated = truncate(&description, EMBED_DESCRIPTION_LIMIT);
+        assert_eq!(truncated.chars().count(), EMBED_DESCRIPTION_LIMIT);
+        assert!(truncated.ends_with('…'));
+    }
+
+    #[test]
+    fn test_split_into_chunks() {
+        assert_eq!(split_into_chunks("", 10), Vec::<String>::new());
+        assert_eq!(split_into_chunks("a\nb\n", 10), ["a\nb\n"]);
+        assert_eq!(
+            split_into_chunks("aaaa\nbbbb\ncc", 10),
+            ["aaaa\nbbbb\n", "cc"]
+        );
+        assert_eq!(
+            split_into_chunks("a\nbbbbbbbbbbbbbbb", 10),
+            ["a\n", "bbbbbbbbbb", "bbbbb"]
+        );
+
+        let lines = (0..500)
+            .map(|i| format!("`~command{}` — Does something\n", i))
+            .collect::<String>();
+        let chunks = split_into_chunks(&lines, EMBED_DESCRIPTION_LIMIT);
+        assert!(chunks.len() > 1);
+        assert!(chunks
+            .iter()
+            .all(|chunk| chunk.chars().count() <= EMBED_DESCRIPTION_LIMIT));
+        assert!(chunks.iter().all(|chunk| chunk.ends_with('\n')));
+        assert_eq!(chunks.concat(), lines);
+    }
+}
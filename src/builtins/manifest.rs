@@ -0,0 +1,574 @@
+//! Offline snapshots of the application command surface, for reviewing changes in CI
+
+use super::register::{command_identity, json_field, json_number, normalize_command_json};
+use crate::serenity_prelude as serenity;
+use crate::serenity_prelude::json::Value;
+#[allow(unused_imports)] // required for simd-json
+use ::serenity::json::prelude::*;
+
+/// A stable, sorted JSON snapshot of all application commands created by
+/// [`super::create_application_commands`]
+///
+/// Includes everything Discord knows about the commands, i.e. localizations, choices, min/max
+/// values, channel types and permissions. Commands are sorted by name and JSON object keys are
+/// sorted alphabetically, so the serialized manifest only changes when the commands do. No bot
+/// token or network access is needed, which makes this suitable for snapshot tests:
+///
+/// ```rust,no_run
+/// # fn commands() -> Vec<poise::Command<(), ()>> { vec![] }
+/// let manifest = poise::builtins::CommandManifest::from_commands(&commands()).unwrap();
+/// let old = std::fs::read_to_string("commands.json").unwrap();
+/// let old = poise::builtins::CommandManifest::from_json(&old).unwrap();
+///
+/// let breaking_changes = old.breaking_changes(&manifest);
+/// assert!(breaking_changes.is_empty(), "{:#?}", breaking_changes);
+/// std::fs::write("commands.json", manifest.to_json()).unwrap();
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct CommandManifest {
+    /// Normalized JSON of each command, sorted by name and type
+    commands: Vec<Value>,
+}
+
+impl CommandManifest {
+    /// Creates a manifest from the given list of commands
+    pub fn from_commands<U, E>(commands: &[crate::Command<U, E>]) -> Result<Self, serenity::Error> {
+        let commands = super::create_application_commands(commands)
+            .iter()
+            .map(|builder| serenity::json::to_value(builder).map_err(serenity::Error::from))
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Self::from_values(commands))
+    }
+
+    /// Parses a manifest previously generated with [`Self::to_json`]
+    pub fn from_json(json: &str) -> Result<Self, serenity::Error> {
+        let commands =
+            serenity::json::from_str::<Vec<Value>>(json).map_err(serenity::Error::from)?;
+        Ok(Self::from_values(commands))
+    }
+
+    /// Normalizes and sorts the given raw command JSON values
+    fn from_values(commands: Vec<Value>) -> Self {
+        let mut commands = commands
+            .iter()
+            .map(normalize_command_json)
+            .collect::<Vec<_>>();
+        commands.sort_by(|a, b| {
+            let (a_name, a_kind) = command_identity(a);
+            let (b_name, b_kind) = command_identity(b);
+            a_name.cmp(b_name).then(
+                a_kind
+                    .partial_cmp(&b_kind)
+                    .unwrap_or(std::cmp::Ordering::Equal),
+            )
+        });
+        Self { commands }
+    }
+
+    /// Serializes this manifest into pretty-printed JSON, with object keys sorted alphabetically
+    pub fn to_json(&self) -> String {
+        let mut json = String::new();
+        let entries = self.commands.iter().map(|c| (None, c)).collect();
+        write_sorted_json_entries(&mut json, entries, "[", "]", 0);
+        json
+    }
+
+    /// Lists all changes from `self` to `new` which may break existing users of the commands,
+    /// e.g. removed commands, renamed options, removed choices or newly required parameters
+    ///
+    /// Purely additive changes like new commands, new optional parameters or new choices are not
+    /// reported.
+    pub fn breaking_changes(&self, new: &CommandManifest) -> Vec<BreakingChange> {
+        let mut changes = Vec::new();
+        for old_command in &self.commands {
+            let name = command_identity(old_command).0;
+            let new_command = new
+                .commands
+                .iter()
+                .find(|c| command_identity(c) == command_identity(old_command));
+            let new_command = match new_command {
+                Some(x) => x,
+                None => {
+                    changes.push(BreakingChange::CommandRemoved {
+                        command: name.to_owned(),
+                    });
+                    continue;
+                }
+            };
+
+            // `dm_permission` is only present in normalized JSON if it's false
+            let guild_restricted = json_field(old_command, "dm_permission").is_none()
+                && json_field(new_command, "dm_permission").is_some();
+            let old_permissions = json_field(old_command, "default_member_permissions");
+            let new_permissions = json_field(new_command, "default_member_permissions");
+            let permissions_restricted =
+                new_permissions.is_some() && old_permissions != new_permissions;
+            let restricted = guild_restricted || permissions_restricted;
+            if restricted {
+                changes.push(BreakingChange::AccessRestricted {
+                    command: name.to_owned(),
+                });
+            }
+
+            compare_options(
+                name,
+                array_of(old_command, "options"),
+                array_of(new_command, "options"),
+                &mut changes,
+            );
+        }
+        changes
+    }
+}
+
+/// A change between two [`CommandManifest`]s which may break existing users of the commands
+///
+/// `command` is the qualified command name including subcommands, e.g. `config set`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum BreakingChange {
+    /// A top-level command was removed
+    CommandRemoved {
+        /// Qualified name of the command
+        command: String,
+    },
+    /// The command was restricted to guilds, or its required member permissions were added or
+    /// changed
+    AccessRestricted {
+        /// Qualified name of the command
+        command: String,
+    },
+    /// A parameter or subcommand was removed
+    OptionRemoved {
+        /// Qualified name of the command
+        command: String,
+        /// Name of the removed parameter or subcommand
+        option: String,
+    },
+    /// A parameter or subcommand was renamed, i.e. removed and replaced by another one of the same
+    /// type at the same position
+    OptionRenamed {
+        /// Qualified name of the command
+        command: String,
+        /// Previous name of the parameter or subcommand
+        old_name: String,
+        /// New name of the parameter or subcommand
+        new_name: String,
+    },
+    /// The type of a parameter changed
+    OptionTypeChanged {
+        /// Qualified name of the command
+        command: String,
+        /// Name of the parameter
+        option: String,
+    },
+    /// A previously optional parameter became required
+    OptionNowRequired {
+        /// Qualified name of the command
+        command: String,
+        /// Name of the parameter
+        option: String,
+    },
+    /// A new required parameter was added
+    RequiredOptionAdded {
+        /// Qualified name of the command
+        command: String,
+        /// Name of the parameter
+        option: String,
+    },
+    /// A choice was removed from a parameter
+    ChoiceRemoved {
+        /// Qualified name of the command
+        command: String,
+        /// Name of the parameter
+        option: String,
+        /// Name of the removed choice
+        choice: String,
+    },
+    /// A choice of a parameter kept its name, but now submits a different value
+    ChoiceValueChanged {
+        /// Qualified name of the command
+        command: String,
+        /// Name of the parameter
+        option: String,
+        /// Name of the changed choice
+        choice: String,
+    },
+    /// The accepted values of a parameter were narrowed, e.g. by a higher `min_value` or fewer
+    /// `channel_types`
+    ConstraintTightened {
+        /// Qualified name of the command
+        command: String,
+        /// Name of the parameter
+        option: String,
+        /// Which constraint changed, e.g. `max_length`
+        constraint: &'static str,
+    },
+}
+
+impl std::fmt::Display for BreakingChange {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::CommandRemoved { command } => write!(f, "`/{}` was removed", command),
+            Self::AccessRestricted { command } => {
+                write!(f, "`/{}` has new access restrictions", command)
+            }
+            Self::OptionRemoved { command, option } => {
+                write!(f, "`{}` was removed from `/{}`", option, command)
+            }
+            Self::OptionRenamed {
+                command,
+                old_name,
+                new_name,
+            } => write!(
+                f,
+                "`{}` was renamed to `{}` in `/{}`",
+                old_name, new_name, command
+            ),
+            Self::OptionTypeChanged { command, option } => {
+                write!(f, "`{}` in `/{}` changed its type", option, command)
+            }
+            Self::OptionNowRequired { command, option } => {
+                write!(f, "`{}` in `/{}` is now required", option, command)
+            }
+            Self::RequiredOptionAdded { command, option } => {
+                write!(f, "required `{}` was added to `/{}`", option, command)
+            }
+            Self::ChoiceRemoved {
+                command,
+                option,
+                choice,
+            } => write!(
+                f,
+                "choice `{}` was removed from `{}` in `/{}`",
+                choice, option, command
+            ),
+            Self::ChoiceValueChanged {
+                command,
+                option,
+                choice,
+            } => write!(
+                f,
+                "choice `{}` of `{}` in `/{}` changed its value",
+                choice, option, command
+            ),
+            Self::ConstraintTightened {
+                command,
+                option,
+                constraint,
+            } => write!(
+                f,
+                "`{}` of `{}` in `/{}` was tightened",
+                constraint, option, command
+            ),
+        }
+    }
+}
+
+/// Returns the array stored under `key` in a normalized command or option JSON value
+fn array_of<'a>(value: &'a Value, key: &str) -> &'a [Value] {
+    json_field(value, key)
+        .and_then(|x| x.as_array())
+        .map_or(&[], |x| x.as_slice())
+}
+
+/// Returns the string stored under `key` in a normalized command or option JSON value, or an
+/// empty string
+fn str_of<'a>(value: &'a Value, key: &str) -> &'a str {
+    json_field(value, key)
+        .and_then(|x| x.as_str())
+        .unwrap_or_default()
+}
+
+/// Whether an option is a subcommand or subcommand group
+fn is_subcommand(option: &Value) -> bool {
+    matches!(json_field(option, "type").and_then(json_number), Some(t) if t == 1.0 || t == 2.0)
+}
+
+/// Recursively compares the options of a command and appends all breaking changes to `changes`
+fn compare_options(
+    command: &str,
+    old_options: &[Value],
+    new_options: &[Value],
+    changes: &mut Vec<BreakingChange>,
+) {
+    let find =
+        |options: &'_ [Value], name: &str| options.iter().position(|o| str_of(o, "name") == name);
+    let kind = |option: &Value| json_field(option, "type").and_then(json_number);
+
+    let mut rename_targets = Vec::new();
+    for (i, old) in old_options.iter().enumerate() {
+        let old_name = str_of(old, "name").to_owned();
+        let new = match find(new_options, &old_name) {
+            Some(j) => &new_options[j],
+            None => {
+                // An unknown option of the same type at the same position is likely a rename
+                let replacement = new_options.get(i).filter(|new| {
+                    kind(new) == kind(old) && find(old_options, str_of(new, "name")).is_none()
+                });
+                match replacement {
+                    Some(new) => {
+                        rename_targets.push(str_of(new, "name"));
+                        changes.push(BreakingChange::OptionRenamed {
+                            command: command.to_owned(),
+                            old_name,
+                            new_name: str_of(new, "name").to_owned(),
+                        });
+                    }
+                    None => changes.push(BreakingChange::OptionRemoved {
+                        command: command.to_owned(),
+                        option: old_name,
+                    }),
+                }
+                continue;
+            }
+        };
+
+        if kind(old) != kind(new) {
+            changes.push(BreakingChange::OptionTypeChanged {
+                command: command.to_owned(),
+                option: old_name,
+            });
+            continue;
+        }
+        if is_subcommand(old) {
+            let subcommand = format!("{} {}", command, old_name);
+            compare_options(
+                &subcommand,
+                array_of(old, "options"),
+                array_of(new, "options"),
+                changes,
+            );
+            continue;
+        }
+
+        if json_field(old, "required").is_none() && json_field(new, "required").is_some() {
+            changes.push(BreakingChange::OptionNowRequired {
+                command: command.to_owned(),
+                option: old_name.clone(),
+            });
+        }
+        for choice in array_of(old, "choices") {
+            let choice_name = str_of(choice, "name");
+            let new_choice = array_of(new, "choices")
+                .iter()
+                .find(|c| str_of(c, "name") == choice_name);
+            match new_choice {
+                None => changes.push(BreakingChange::ChoiceRemoved {
+                    command: command.to_owned(),
+                    option: old_name.clone(),
+                    choice: choice_name.to_owned(),
+                }),
+                Some(new_choice)
+                    if json_field(new_choice, "value") != json_field(choice, "value") =>
+                {
+                    changes.push(BreakingChange::ChoiceValueChanged {
+                        command: command.to_owned(),
+                        option: old_name.clone(),
+                        choice: choice_name.to_owned(),
+                    })
+                }
+                Some(_) => {}
+            }
+        }
+        for constraint in tightened_constraints(old, new) {
+            changes.push(BreakingChange::ConstraintTightened {
+                command: command.to_owned(),
+                option: old_name.clone(),
+                constraint,
+            });
+        }
+    }
+
+    for new in new_options {
+        let new_name = str_of(new, "name");
+        let is_new = find(old_options, new_name).is_none() && !rename_targets.contains(&new_name);
+        if is_new && !is_subcommand(new) && json_field(new, "required").is_some() {
+            changes.push(BreakingChange::RequiredOptionAdded {
+                command: command.to_owned(),
+                option: new_name.to_owned(),
+            });
+        }
+    }
+}
+
+/// Appends `value` to `out` as pretty-printed JSON, with object keys sorted alphabetically
+/// regardless of the key order of the JSON backend's maps
+fn write_sorted_json(out: &mut String, value: &Value, indent: usize) {
+    if let Some(values) = value.as_array() {
+        let entries = values.iter().map(|v| (None, v)).collect();
+        write_sorted_json_entries(out, entries, "[", "]", indent);
+    } else if let Some(fields) = value.as_object() {
+        let mut entries = fields
+            .iter()
+            .map(|(k, v)| (Some(k.as_str()), v))
+            .collect::<Vec<_>>();
+        entries.sort_by_key(|&(key, _)| key);
+        write_sorted_json_entries(out, entries, "{", "}", indent);
+    } else {
+        // Serializing a scalar can't fail
+        out.push_str(&serenity::json::to_string(value).unwrap_or_default());
+    }
+}
+
+/// Writes the elements of a JSON array (without keys) or the fields of a JSON object (with keys)
+/// for [`write_sorted_json`]
+fn write_sorted_json_entries(
+    out: &mut String,
+    entries: Vec<(Option<&str>, &Value)>,
+    open: &str,
+    close: &str,
+    indent: usize,
+) {
+    out.push_str(open);
+    for (i, (key, value)) in entries.iter().enumerate() {
+        out.push_str(if i == 0 { "\n" } else { ",\n" });
+        out.push_str(&" ".repeat(indent + 2));
+        if let Some(key) = key {
+            // Serializing a string can't fail
+            out.push_str(&serenity::json::to_string(key).unwrap_or_default());
+            out.push_str(": ");
+        }
+        write_sorted_json(out, value, indent + 2);
+    }
+    if !entries.is_empty() {
+        out.push('\n');
+        out.push_str(&" ".repeat(indent));
+    }
+    out.push_str(close);
+}
+
+/// Lists the constraints of an option that accept fewer values in `new` than in `old`
+fn tightened_constraints(old: &Value, new: &Value) -> Vec<&'static str> {
+    let mut tightened = Vec::new();
+    for (constraint, is_lower_bound) in [
+        ("min_value", true),
+        ("max_value", false),
+        ("min_length", true),
+        ("max_length", false),
+    ] {
+        let bound = |option: &Value| json_field(option, constraint).and_then(json_number);
+        let is_tightened = match (bound(old), bound(new)) {
+            (None, Some(_)) => true,
+            (Some(old), Some(new)) if is_lower_bound => new > old,
+            (Some(old), Some(new)) => new < old,
+            _ => false,
+        };
+        if is_tightened {
+            tightened.push(constraint);
+        }
+    }
+
+    // No channel types means all channel types are allowed
+    let old_channel_types = array_of(old, "channel_types");
+    let new_channel_types = array_of(new, "channel_types");
+    if !new_channel_types.is_empty()
+        && (old_channel_types.is_empty()
+            || old_channel_types
+                .iter()
+                .any(|t| !new_channel_types.contains(t)))
+    {
+        tightened.push("channel_types");
+    }
+
+    tightened
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::serenity_prelude::json::json;
+
+    #[test]
+    fn test_breaking_changes() {
+        let old = json!([{
+            "name": "ban",
+            "description": "Bans a user",
+            "options": [
+                { "type": 6, "name": "user", "description": "User", "required": true },
+                { "type": 3, "name": "reason", "description": "Reason" },
+                { "type": 4, "name": "days", "description": "Days", "min_value": 0 },
+                {
+                    "type": 4,
+                    "name": "mode",
+                    "description": "Mode",
+                    "choices": [{ "name": "soft", "value": 0 }, { "name": "hard", "value": 1 }],
+                },
+            ],
+        }, {
+            "name": "ping",
+            "description": "Pong",
+        }]);
+        let new = json!([{
+            "name": "ban",
+            "description": "Bans a member",
+            "options": [
+                { "type": 6, "name": "member", "description": "Member", "required": true },
+                { "type": 3, "name": "reason", "description": "Reason", "required": true },
+                { "type": 4, "name": "days", "description": "Days", "min_value": 1 },
+                {
+                    "type": 4,
+                    "name": "mode",
+                    "description": "Mode",
+                    "choices": [{ "name": "hard", "value": 0 }],
+                },
+                { "type": 5, "name": "silent", "description": "Silent" },
+            ],
+        }]);
+        let old = CommandManifest::from_json(&serenity::json::to_string(&old).unwrap()).unwrap();
+        let new = CommandManifest::from_json(&serenity::json::to_string(&new).unwrap()).unwrap();
+
+        let command = || String::from("ban");
+        assert_eq!(
+            old.breaking_changes(&new),
+            vec![
+                BreakingChange::OptionRenamed {
+                    command: command(),
+                    old_name: "user".into(),
+                    new_name: "member".into(),
+                },
+                BreakingChange::OptionNowRequired {
+                    command: command(),
+                    option: "reason".into(),
+                },
+                BreakingChange::ConstraintTightened {
+                    command: command(),
+                    option: "days".into(),
+                    constraint: "min_value",
+                },
+                BreakingChange::ChoiceRemoved {
+                    command: command(),
+                    option: "mode".into(),
+                    choice: "soft".into(),
+                },
+                BreakingChange::ChoiceValueChanged {
+                    command: command(),
+                    option: "mode".into(),
+                    choice: "hard".into(),
+                },
+                BreakingChange::CommandRemoved {
+                    command: "ping".into(),
+                },
+            ]
+        );
+        assert!(new.breaking_changes(&new).is_empty());
+
+        // Serializing and parsing again yields the same manifest
+        assert_eq!(CommandManifest::from_json(&new.to_json()).unwrap(), new);
+
+        let manifest = CommandManifest::from_json(
+            r#"[{ "name": "ping", "description": "Pong", "nsfw": true, "dm_permission": false }]"#,
+        )
+        .unwrap();
+        assert_eq!(
+            manifest.to_json(),
+            r#"[
+  {
+    "description": "Pong",
+    "dm_permission": false,
+    "name": "ping",
+    "nsfw": true
+  }
+]"#
+        );
+    }
+}
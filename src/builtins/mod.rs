@@ -3,17 +3,24 @@
 //! This file provides sample commands and utility functions like help menus or error handlers to
 //! use as a starting point for the framework.
 
+mod confirm;
+pub use confirm::*;
+
 mod help;
 pub use help::*;
 
+mod manifest;
+pub use manifest::*;
+
 mod register;
 pub use register::*;
 
-#[cfg(any(feature = "chrono", feature = "time"))]
 mod paginate;
-#[cfg(any(feature = "chrono", feature = "time"))]
 pub use paginate::*;
 
+mod suggest;
+pub use suggest::*;
+
 use crate::serenity_prelude as serenity;
 
 /// Utility function to avoid verbose
@@ -32,11 +39,39 @@ async fn say_ephemeral<U, E>(
     Ok(())
 }
 
+/// Renders the line of `text` containing `span` with the span underlined by carets and labelled,
+/// similar to rustc's diagnostics. Returns None if the line can't be displayed in a code block
+fn render_span(text: &str, span: std::ops::Range<usize>, label: &str) -> Option<String> {
+    let start = span.start.min(text.len());
+    let line_start = text[..start].rfind('\n').map_or(0, |i| i + 1);
+    let line_end = text[start..].find('\n').map_or(text.len(), |i| start + i);
+    let line = text.get(line_start..line_end)?;
+    if line.contains("```") {
+        return None;
+    }
+
+    // Multi-line spans are cut off at the end of the line
+    let end = span.end.clamp(start, line_end);
+    let indent = text.get(line_start..start)?.chars().count();
+    let width = text.get(start..end)?.chars().count().max(1);
+    Some(format!(
+        "```\n{}\n{}{} {}\n```\n",
+        line,
+        " ".repeat(indent),
+        "^".repeat(width),
+        label
+    ))
+}
+
 /// An error handler that logs errors either via the [`log`] crate or via a Discord message. Set
 /// up a logger (e.g. `env_logger::init()`) to see the logged errors from this method.
 ///
 /// If the user invoked the command wrong ([`crate::FrameworkError::ArgumentParse`]), the command
-/// help is displayed and the user is directed to the help menu.
+/// usage (see [`crate::Command::usage`]) and help text are displayed. In prefix commands, the
+/// offending part of the message is underlined.
+///
+/// The texts of the messages come from [`crate::FrameworkOptions::messages`], so they can be
+/// translated.
 ///
 /// Can return an error if sending the Discord error message failed. You can decide for yourself
 /// how to handle this, for example:
@@ -71,41 +106,84 @@ pub async fn on_error<U, E: std::fmt::Display + std::fmt::Debug>(
                 .iter()
                 .map(|s| &*s.name)
                 .collect::<Vec<_>>();
-            let response = format!(
-                "You must specify one of the following subcommands: {}",
-                subcommands.join(", ")
-            );
-            ctx.send(|b| b.content(response).ephemeral(true)).await?;
+            let options = ctx.framework().options();
+            let mut response = options
+                .messages
+                .subcommand_required(ctx.locale(), &subcommands);
+            // A mistyped subcommand ends up as the arguments of the parent command
+            if let crate::Context::Prefix(prefix_ctx) = ctx {
+                let suggestions = suggest_commands(
+                    &ctx.command().subcommands,
+                    prefix_ctx.args,
+                    options.prefix_options.case_insensitive_commands,
+                    options.owners.contains(&ctx.author().id),
+                );
+                if !suggestions.is_empty() {
+                    response += "\n";
+                    response +=
+                        &options
+                            .messages
+                            .did_you_mean(ctx.locale(), ctx.prefix(), &suggestions);
+                }
+            }
+            say_ephemeral(ctx, &response, true).await?;
         }
         crate::FrameworkError::CommandPanic { ctx, payload: _ } => {
             // Not showing the payload to the user because it may contain sensitive info
-            ctx.send(|b| {
-                b.embed(|b| {
-                    b.title("Internal error")
-                        .color((255, 0, 0))
-                        .description("An unexpected internal error has occurred.")
-                })
-                .ephemeral(true)
-            })
-            .await?;
+            let messages = &ctx.framework().options().messages;
+            let title = messages.internal_error_title(ctx.locale());
+            let description = messages.internal_error_description(ctx.locale());
+            let embed = serenity::CreateEmbed::new()
+                .title(title)
+                .color((255, 0, 0))
+                .description(description);
+            ctx.send(crate::CreateReply::default().embed(embed).ephemeral(true))
+                .await?;
         }
-        crate::FrameworkError::ArgumentParse { ctx, input, error } => {
+        crate::FrameworkError::ArgumentParse {
+            ctx,
+            input,
+            error,
+            parameter,
+            expected_type,
+            span,
+        } => {
             // If we caught an argument parse error, give a helpful error message with the
             // command explanation if available
-            let usage = match ctx.command().help_text {
-                Some(help_text) => help_text(),
-                None => "Please check the help menu for usage information".into(),
-            };
-            let response = if let Some(input) = input {
-                format!(
-                    "**Cannot parse `{}` as argument: {}**\n{}",
-                    input, error, usage
-                )
-            } else {
-                format!("**{}**\n{}", error, usage)
-            };
+            let mut usage = format!("```\n{}\n```\n", ctx.command().usage(ctx.prefix()));
+            if let Some(help_text) = ctx.command().help_text {
+                usage += &help_text();
+            }
+            let messages = &ctx.framework().options().messages;
+            let error = messages.argument_error(ctx.locale(), &*error);
+            let mut response =
+                messages.argument_parse_error(ctx.locale(), input.as_deref(), parameter, &error);
+            response += "\n";
+            if let (crate::Context::Prefix(prefix_ctx), Some(span)) = (ctx, span) {
+                let label = match expected_type {
+                    Some(expected_type) => messages.expected_type(ctx.locale(), expected_type),
+                    None => error,
+                };
+                if let Some(snippet) = render_span(&prefix_ctx.msg.content, span, &label) {
+                    response += &snippet;
+                }
+            }
+            response += &usage;
             ctx.say(response).await?;
         }
+        crate::FrameworkError::ArgumentValidation {
+            error,
+            input,
+            parameter,
+            ctx,
+        } => {
+            let response = ctx
+                .framework()
+                .options()
+                .messages
+                .argument_validation_error(ctx.locale(), input.as_deref(), parameter, &error);
+            say_ephemeral(ctx, &response, true).await?;
+        }
         crate::FrameworkError::CommandStructureMismatch { ctx, description } => {
             log::error!(
                 "Error: failed to deserialize interaction arguments for `/{}`: {}",
@@ -124,58 +202,119 @@ pub async fn on_error<U, E: std::fmt::Display + std::fmt::Debug>(
         crate::FrameworkError::CooldownHit {
             remaining_cooldown,
             ctx,
+            ..
         } => {
-            let msg = format!(
-                "You're too fast. Please wait {} seconds before retrying",
-                remaining_cooldown.as_secs()
-            );
+            let msg = ctx
+                .framework()
+                .options()
+                .messages
+                .cooldown_hit(ctx.locale(), remaining_cooldown);
             say_ephemeral(ctx, &msg, true).await?;
         }
         crate::FrameworkError::MissingBotPermissions {
             missing_permissions,
             ctx,
         } => {
-            let msg = format!(
-                "Command cannot be executed because the bot is lacking permissions: {}",
-                missing_permissions,
-            );
+            let msg = ctx
+                .framework()
+                .options()
+                .messages
+                .missing_bot_permissions(ctx.locale(), missing_permissions);
             say_ephemeral(ctx, &msg, true).await?;
         }
         crate::FrameworkError::MissingUserPermissions {
             missing_permissions,
             ctx,
         } => {
-            let response = if let Some(missing_permissions) = missing_permissions {
-                format!(
-                    "You're lacking permissions for `{}{}`: {}",
-                    ctx.prefix(),
-                    ctx.command().name,
-                    missing_permissions,
-                )
-            } else {
-                format!(
-                    "You may be lacking permissions for `{}{}`. Not executing for safety",
-                    ctx.prefix(),
-                    ctx.command().name,
-                )
-            };
+            let command = format!("{}{}", ctx.prefix(), ctx.command().name);
+            let response = ctx.framework().options().messages.missing_user_permissions(
+                ctx.locale(),
+                &command,
+                missing_permissions,
+            );
             say_ephemeral(ctx, &response, true).await?;
         }
         crate::FrameworkError::NotAnOwner { ctx } => {
-            let response = "Only bot owners can call this command";
-            say_ephemeral(ctx, response, true).await?;
+            let response = ctx
+                .framework()
+                .options()
+                .messages
+                .not_an_owner(ctx.locale());
+            say_ephemeral(ctx, &response, true).await?;
         }
         crate::FrameworkError::GuildOnly { ctx } => {
-            let response = "You cannot run this command in DMs.";
-            say_ephemeral(ctx, response, true).await?;
+            let response = ctx.framework().options().messages.guild_only(ctx.locale());
+            say_ephemeral(ctx, &response, true).await?;
         }
         crate::FrameworkError::DmOnly { ctx } => {
-            let response = "You cannot run this command outside DMs.";
-            say_ephemeral(ctx, response, true).await?;
+            let response = ctx.framework().options().messages.dm_only(ctx.locale());
+            say_ephemeral(ctx, &response, true).await?;
         }
         crate::FrameworkError::NsfwOnly { ctx } => {
-            let response = "You cannot run this command outside NSFW channels.";
-            say_ephemeral(ctx, response, true).await?;
+            let response = ctx.framework().options().messages.nsfw_only(ctx.locale());
+            say_ephemeral(ctx, &response, true).await?;
+        }
+        crate::FrameworkError::CommandDisabled { ctx } => {
+            let response = ctx
+                .framework()
+                .options()
+                .messages
+                .command_disabled(ctx.locale());
+            say_ephemeral(ctx, &response, true).await?;
+        }
+        crate::FrameworkError::GuildCommandDisabled { ctx } => {
+            let response = ctx
+                .framework()
+                .options()
+                .messages
+                .guild_command_disabled(ctx.locale());
+            say_ephemeral(ctx, &response, true).await?;
+        }
+        crate::FrameworkError::GuildCategoryDisabled { category, ctx } => {
+            let response = ctx
+                .framework()
+                .options()
+                .messages
+                .guild_category_disabled(ctx.locale(), category);
+            say_ephemeral(ctx, &response, true).await?;
+        }
+        crate::FrameworkError::ChannelNotAllowed { ctx } => {
+            let response = ctx
+                .framework()
+                .options()
+                .messages
+                .channel_not_allowed(ctx.locale());
+            say_ephemeral(ctx, &response, true).await?;
+        }
+        crate::FrameworkError::MissingRequiredRole {
+            required_roles,
+            ctx,
+        } => {
+            let response = ctx
+                .framework()
+                .options()
+                .messages
+                .missing_required_role(ctx.locale(), &required_roles);
+            say_ephemeral(ctx, &response, true).await?;
+        }
+        crate::FrameworkError::AttachmentConstraint {
+            violation,
+            parameter,
+            ctx,
+        } => {
+            let response = ctx.framework().options().messages.attachment_constraint(
+                ctx.locale(),
+                parameter,
+                &violation,
+            );
+            say_ephemeral(ctx, &response, true).await?;
+        }
+        crate::FrameworkError::GuildSettings { error, ctx } => {
+            log::error!(
+                "Failed to retrieve settings of guild {:?}: {}",
+                ctx.guild_id,
+                error
+            );
         }
         crate::FrameworkError::DynamicPrefix { error, msg, .. } => {
             log::error!(
@@ -184,9 +323,21 @@ pub async fn on_error<U, E: std::fmt::Display + std::fmt::Debug>(
                 error
             );
         }
+        crate::FrameworkError::LocaleResolver { error, msg, .. } => {
+            log::error!(
+                "Locale resolver failed for message {:?}: {}",
+                msg.content,
+                error
+            );
+        }
         crate::FrameworkError::UnknownCommand {
+            ctx,
+            msg,
             msg_content,
             prefix,
+            framework,
+            suggestions,
+            locale,
             ..
         } => {
             log::warn!(
@@ -194,6 +345,17 @@ pub async fn on_error<U, E: std::fmt::Display + std::fmt::Debug>(
                 prefix,
                 msg_content,
             );
+            if framework.options.prefix_options.suggest_unknown_commands && !suggestions.is_empty()
+            {
+                let unknown_command = msg_content.split_whitespace().next().unwrap_or("");
+                let response = framework.options.messages.unknown_command(
+                    locale,
+                    prefix,
+                    unknown_command,
+                    &suggestions,
+                );
+                msg.reply(ctx, response).await?;
+            }
         }
         crate::FrameworkError::UnknownInteraction { interaction, .. } => {
             log::warn!(
@@ -319,3 +481,26 @@ pub async fn servers<U, E>(ctx: crate::Context<'_, U, E>) -> Result<(), serenity
 
     Ok(())
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_render_span() {
+        assert_eq!(
+            render_span("~pay @bob 1O", 10..12, "expected u32").unwrap(),
+            "```\n~pay @bob 1O\n          ^^ expected u32\n```\n",
+        );
+        assert_eq!(
+            render_span("~pay\n@bob ü 5", 10..12, "unexpected").unwrap(),
+            "```\n@bob ü 5\n     ^ unexpected\n```\n",
+        );
+        // Empty spans (e.g. missing arguments) point behind the input
+        assert_eq!(
+            render_span("~pay @bob", 9..9, "expected u32").unwrap(),
+            "```\n~pay @bob\n         ^ expected u32\n```\n",
+        );
+        assert!(render_span("~eval ```code```", 6..16, "").is_none());
+    }
+}
@@ -1,17 +1,414 @@
-//! Sample pagination implementation
+//! Interactive pagination with navigation buttons
 
 use crate::serenity_prelude as serenity;
 
-/// This is an example implementation of pagination. To tweak the behavior, copy the source code and
-/// adjust to your needs:
-/// - change embed appearance
-/// - use different emojis for the navigation buttons
-/// - add more navigation buttons
-/// - change timeout duration
-/// - add a page selector dropdown
-/// - use reactions instead of buttons
-/// - remove message after navigation timeout
-/// - ...
+/// What to do with the navigation buttons of a [`Paginator`] once it times out
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum PaginatorTimeoutAction {
+    /// Keep the buttons, but grey them out
+    DisableButtons,
+    /// Remove the buttons from the message
+    RemoveButtons,
+}
+
+/// Where a [`Paginator`] gets its pages from
+enum PaginatorPages<'a> {
+    /// All pages have been built upfront
+    Static(Vec<crate::CreateReply>),
+    /// Pages are built on demand
+    Lazy {
+        /// Total number of pages
+        num_pages: usize,
+        /// Builds the page with the given index
+        producer: Box<dyn Fn(usize) -> crate::BoxFuture<'a, crate::CreateReply> + Send + Sync + 'a>,
+    },
+}
+
+/// Builder for an interactive message with buttons to flip through a list of pages
+///
+/// Pages can be given upfront as embeds ([`Self::from_embeds`]) or whole replies ([`Self::new`]),
+/// or be produced on demand by an async closure ([`Self::lazy`]) for when there are too many pages
+/// to build all of them.
+///
+/// By default, the message has buttons to go to the first, previous, next and last page, plus a
+/// page counter which opens a modal to jump to a specific page when clicked. Only the command
+/// invoker can flip pages, and the buttons are disabled after 5 minutes of inactivity.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// # use poise::serenity_prelude as serenity;
+/// # async fn _test(ctx: poise::Context<'_, (), serenity::Error>) -> Result<(), serenity::Error> {
+/// // Prebuilt pages
+/// poise::builtins::Paginator::from_embeds(vec![
+///     serenity::CreateEmbed::default().description("Content of first page"),
+///     serenity::CreateEmbed::default().description("Content of second page"),
+/// ])
+/// .run(ctx)
+/// .await?;
+///
+/// // Pages built on demand
+/// poise::builtins::Paginator::lazy(1000, |page| {
+///     Box::pin(async move {
+///         let first_entry = page * 10;
+///         poise::CreateReply::default().content(format!("Entries starting at {}", first_entry))
+///     })
+/// })
+/// .timeout(std::time::Duration::from_secs(60))
+/// .on_timeout(poise::builtins::PaginatorTimeoutAction::RemoveButtons)
+/// .run(ctx)
+/// .await?;
+/// # Ok(()) }
+/// ```
+pub struct Paginator<'a> {
+    /// The pages to flip through
+    pages: PaginatorPages<'a>,
+    /// Index of the page that is shown initially
+    start_page: usize,
+    /// How long to wait for a button press before giving up
+    timeout: std::time::Duration,
+    /// What to do with the buttons after the timeout
+    timeout_action: PaginatorTimeoutAction,
+    /// Whether only the command invoker may press the buttons
+    restrict_to_author: bool,
+    /// Whether to show the buttons to go to the first and last page
+    first_last_buttons: bool,
+    /// Whether the page counter can be clicked to jump to a page
+    jump_button: bool,
+    /// Whether the message is ephemeral (only has an effect in application commands)
+    ephemeral: Option<bool>,
+}
+
+impl<'a> Paginator<'a> {
+    /// Creates a paginator over the given pages
+    pub fn new(pages: Vec<crate::CreateReply>) -> Self {
+        Self::with_pages(PaginatorPages::Static(pages))
+    }
+
+    /// Creates a paginator where each page consists of a single embed
+    pub fn from_embeds(embeds: impl IntoIterator<Item = serenity::CreateEmbed>) -> Self {
+        Self::new(
+            embeds
+                .into_iter()
+                .map(|embed| crate::CreateReply::default().embed(embed))
+                .collect(),
+        )
+    }
+
+    /// Creates a paginator over `num_pages` pages which are built on demand by `producer`, given
+    /// the page index
+    ///
+    /// The producer is called whenever a page is shown. If building a page can fail, render the
+    /// error into the page.
+    pub fn lazy(
+        num_pages: usize,
+        producer: impl Fn(usize) -> crate::BoxFuture<'a, crate::CreateReply> + Send + Sync + 'a,
+    ) -> Self {
+        Self::with_pages(PaginatorPages::Lazy {
+            num_pages,
+            producer: Box::new(producer),
+        })
+    }
+
+    /// Creates a paginator with default settings
+    fn with_pages(pages: PaginatorPages<'a>) -> Self {
+        Self {
+            pages,
+            start_page: 0,
+            timeout: std::time::Duration::from_secs(5 * 60),
+            timeout_action: PaginatorTimeoutAction::DisableButtons,
+            restrict_to_author: true,
+            first_last_buttons: true,
+            jump_button: true,
+            ephemeral: None,
+        }
+    }
+
+    /// Sets the index of the page that is shown initially. Defaults to the first page
+    pub fn start_page(mut self, page: usize) -> Self {
+        self.start_page = page;
+        self
+    }
+
+    /// Sets how long to wait for the next button press before disabling the paginator. Defaults
+    /// to 5 minutes
+    pub fn timeout(mut self, timeout: std::time::Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Sets what to do with the buttons once the paginator times out. Defaults to
+    /// [`PaginatorTimeoutAction::DisableButtons`]
+    pub fn on_timeout(mut self, action: PaginatorTimeoutAction) -> Self {
+        self.timeout_action = action;
+        self
+    }
+
+    /// Sets whether only the command invoker may flip pages. Defaults to true
+    pub fn restrict_to_author(mut self, restrict: bool) -> Self {
+        self.restrict_to_author = restrict;
+        self
+    }
+
+    /// Sets whether buttons to go to the first and last page are shown. Defaults to true
+    pub fn first_last_buttons(mut self, show: bool) -> Self {
+        self.first_last_buttons = show;
+        self
+    }
+
+    /// Sets whether the page counter can be clicked to jump to a specific page. Defaults to true
+    pub fn jump_button(mut self, enable: bool) -> Self {
+        self.jump_button = enable;
+        self
+    }
+
+    /// Sets whether the paginator message is ephemeral (only has an effect in application
+    /// commands). Defaults to [`crate::Command::ephemeral`]
+    pub fn ephemeral(mut self, ephemeral: bool) -> Self {
+        self.ephemeral = Some(ephemeral);
+        self
+    }
+
+    /// Total number of pages
+    fn num_pages(&self) -> usize {
+        match &self.pages {
+            PaginatorPages::Static(pages) => pages.len(),
+            PaginatorPages::Lazy { num_pages, .. } => *num_pages,
+        }
+    }
+
+    /// Retrieves or builds the page with the given index, without navigation buttons
+    async fn page(&self, index: usize) -> crate::CreateReply {
+        match &self.pages {
+            PaginatorPages::Static(pages) => pages[index].clone(),
+            PaginatorPages::Lazy { producer, .. } => producer(index).await,
+        }
+    }
+
+    /// Creates the interaction response which shows the given page with navigation buttons
+    fn update_response(
+        &self,
+        page: &crate::CreateReply,
+        id_prefix: &str,
+        current_page: usize,
+    ) -> serenity::CreateInteractionResponse {
+        serenity::CreateInteractionResponse::UpdateMessage(
+            page.clone()
+                .components(vec![self.buttons(id_prefix, current_page, false)])
+                .to_slash_initial_response(),
+        )
+    }
+
+    /// Creates the row of navigation buttons
+    fn buttons(
+        &self,
+        id_prefix: &str,
+        current_page: usize,
+        disabled: bool,
+    ) -> serenity::CreateActionRow {
+        let button = |id: &str| {
+            serenity::CreateButton::new(format!("{}:{}", id_prefix, id))
+                .style(serenity::ButtonStyle::Secondary)
+                .disabled(disabled)
+        };
+
+        let mut buttons = Vec::new();
+        if self.first_last_buttons {
+            buttons.push(button("first").emoji('⏮'));
+        }
+        buttons.push(button("prev").emoji('◀'));
+        buttons.push(
+            button("jump")
+                .label(format!("{} / {}", current_page + 1, self.num_pages()))
+                .disabled(disabled || !self.jump_button),
+        );
+        buttons.push(button("next").emoji('▶'));
+        if self.first_last_buttons {
+            buttons.push(button("last").emoji('⏭'));
+        }
+        serenity::CreateActionRow::Buttons(buttons)
+    }
+
+    /// Sends the paginator and handles button presses until the timeout is reached
+    ///
+    /// Note: this is a long-running function. It will only return once the timeout for
+    /// navigation button interactions has been reached.
+    pub async fn run<U, E>(self, ctx: crate::Context<'_, U, E>) -> Result<(), serenity::Error> {
+        let num_pages = self.num_pages();
+        if num_pages == 0 {
+            return Ok(());
+        }
+
+        // Define a unique prefix for the navigation button IDs
+        let id_prefix = ctx.id().to_string();
+
+        let mut current_page = self.start_page.min(num_pages - 1);
+        let mut page = self.page(current_page).await;
+        if let Some(ephemeral) = self.ephemeral {
+            page = page.ephemeral(ephemeral);
+        }
+        let reply = ctx
+            .send(
+                page.clone()
+                    .components(vec![self.buttons(&id_prefix, current_page, false)]),
+            )
+            .await?;
+
+        let messages = &ctx.framework().options().messages;
+        loop {
+            // Loop through incoming interactions with the navigation buttons
+            let filter_prefix = id_prefix.clone();
+            let press = serenity::ComponentInteractionCollector::new(&ctx.serenity_context().shard)
+                // We defined our button IDs as `id_prefix:action`. If they aren't, some other
+                // command's button was pressed
+                .filter(move |press| button_action(&press.data.custom_id, &filter_prefix).is_some())
+                .timeout(self.timeout)
+                .await;
+            let press = match press {
+                Some(x) => x,
+                None => break,
+            };
+
+            if self.restrict_to_author && press.user.id != ctx.author().id {
+                press
+                    .create_response(
+                        ctx.serenity_context(),
+                        serenity::CreateInteractionResponse::Message(
+                            serenity::CreateInteractionResponseMessage::new()
+                                .content(messages.paginator_not_invoker(ctx.locale()))
+                                .ephemeral(true),
+                        ),
+                    )
+                    .await?;
+                continue;
+            }
+
+            // Depending on which button was pressed, go to another page
+            let new_page = match button_action(&press.data.custom_id, &id_prefix) {
+                Some("first") => 0,
+                Some("prev") => current_page.checked_sub(1).unwrap_or(num_pages - 1),
+                Some("next") => (current_page + 1) % num_pages,
+                Some("last") => num_pages - 1,
+                Some("jump") => {
+                    // The modal submission is responded to instead of the button press
+                    if let Some((new_page, submission)) =
+                        ask_page_number(ctx, &press, num_pages, self.timeout).await?
+                    {
+                        current_page = new_page;
+                        page = self.page(current_page).await;
+                        submission
+                            .create_response(
+                                ctx.serenity_context(),
+                                self.update_response(&page, &id_prefix, current_page),
+                            )
+                            .await?;
+                    }
+                    continue;
+                }
+                // This is an unrelated button interaction
+                _ => continue,
+            };
+
+            // Update the message with the new page contents
+            current_page = new_page;
+            page = self.page(current_page).await;
+            press
+                .create_response(
+                    ctx.serenity_context(),
+                    self.update_response(&page, &id_prefix, current_page),
+                )
+                .await?;
+        }
+
+        let components = match self.timeout_action {
+            PaginatorTimeoutAction::DisableButtons => {
+                vec![self.buttons(&id_prefix, current_page, true)]
+            }
+            PaginatorTimeoutAction::RemoveButtons => vec![],
+        };
+        reply.edit(ctx, page.components(components)).await?;
+
+        Ok(())
+    }
+}
+
+/// Actions of the navigation buttons, which are appended to their custom IDs
+const BUTTON_ACTIONS: &[&str] = &["first", "prev", "jump", "next", "last"];
+
+/// Returns the action of the navigation button with the given custom ID, or None if the button
+/// doesn't belong to the paginator with the given ID prefix
+fn button_action<'a>(custom_id: &'a str, id_prefix: &str) -> Option<&'a str> {
+    let action = custom_id.strip_prefix(id_prefix)?.strip_prefix(':')?;
+    BUTTON_ACTIONS.contains(&action).then(|| action)
+}
+
+/// Responds to the page counter button press with a modal asking for a page number and waits
+/// for the submission
+///
+/// Returns the chosen page index and the modal submission, which still needs to be responded to.
+/// Returns None on timeout or invalid input.
+async fn ask_page_number<U, E>(
+    ctx: crate::Context<'_, U, E>,
+    press: &serenity::ComponentInteraction,
+    num_pages: usize,
+    timeout: std::time::Duration,
+) -> Result<Option<(usize, serenity::ModalInteraction)>, serenity::Error> {
+    let messages = &ctx.framework().options().messages;
+    let modal_id = format!("{}:modal", press.id);
+    let title = messages.paginator_jump_title(ctx.locale());
+    press
+        .create_response(
+            ctx.serenity_context(),
+            serenity::CreateInteractionResponse::Modal(
+                serenity::CreateModal::new(&modal_id, title).components(vec![
+                    serenity::CreateActionRow::InputText(
+                        serenity::CreateInputText::new(
+                            serenity::InputTextStyle::Short,
+                            messages.paginator_page_number(ctx.locale(), num_pages),
+                            "page",
+                        )
+                        .required(true),
+                    ),
+                ]),
+            ),
+        )
+        .await?;
+
+    let filter_id = modal_id.clone();
+    let submission = serenity::ModalInteractionCollector::new(&ctx.serenity_context().shard)
+        .filter(move |submission| submission.data.custom_id == filter_id)
+        .timeout(timeout)
+        .await;
+    let mut submission = match submission {
+        Some(x) => x,
+        None => return Ok(None),
+    };
+
+    let page = crate::find_modal_text(&mut submission.data, "page")
+        .and_then(|page| page.trim().parse::<usize>().ok())
+        .filter(|&page| page >= 1 && page <= num_pages);
+    match page {
+        Some(page) => Ok(Some((page - 1, submission))),
+        None => {
+            submission
+                .create_response(
+                    ctx.serenity_context(),
+                    serenity::CreateInteractionResponse::Message(
+                        serenity::CreateInteractionResponseMessage::new()
+                            .content(
+                                messages.paginator_invalid_page_number(ctx.locale(), num_pages),
+                            )
+                            .ephemeral(true),
+                    ),
+                )
+                .await?;
+            Ok(None)
+        }
+    }
+}
+
+/// This is an example implementation of pagination. For more options, like embeds, lazily built
+/// pages or jump-to-page, use [`Paginator`] directly.
 ///
 /// Note: this is a long-running function. It will only return once the timeout for navigation
 /// button interactions has been reached.
@@ -27,7 +424,7 @@ use crate::serenity_prelude as serenity;
 ///     "Content of fourth page",
 /// ];
 ///
-/// poise::samples::paginate(ctx, pages).await?;
+/// poise::builtins::paginate(ctx, pages).await?;
 /// # Ok(()) }
 /// ```
 ///
@@ -36,54 +433,28 @@ pub async fn paginate<U, E>(
     ctx: crate::Context<'_, U, E>,
     pages: &[&str],
 ) -> Result<(), serenity::Error> {
-    // Define some unique identifiers for the navigation buttons
-    let ctx_id = ctx.id();
-    let prev_button_id = format!("{}prev", ctx.id());
-    let next_button_id = format!("{}next", ctx.id());
-
-    // Send the embed with the first page as content
-    let mut current_page = 0;
-    ctx.send(|b| {
-        b.embed(|b| b.description(pages[current_page]))
-            .components(|b| {
-                b.create_action_row(|b| {
-                    b.create_button(|b| b.custom_id(&prev_button_id).emoji('◀'))
-                        .create_button(|b| b.custom_id(&next_button_id).emoji('▶'))
-                })
-            })
-    })
-    .await?;
-
-    // Loop through incoming interactions with the navigation buttons
-    while let Some(press) = serenity::CollectComponentInteraction::new(ctx)
-        // We defined our button IDs to start with `ctx_id`. If they don't, some other command's
-        // button was pressed
-        .filter(move |press| press.data.custom_id.starts_with(&ctx_id.to_string()))
-        // Timeout when no navigation button has been pressed for 24 hours
-        .timeout(std::time::Duration::from_secs(3600 * 24))
-        .await
-    {
-        // Depending on which button was pressed, go to next or previous page
-        if press.data.custom_id == next_button_id {
-            current_page += 1;
-            if current_page >= pages.len() {
-                current_page = 0;
-            }
-        } else if press.data.custom_id == prev_button_id {
-            current_page = current_page.checked_sub(1).unwrap_or(pages.len() - 1);
-        } else {
-            // This is an unrelated button interaction
-            continue;
-        }
+    Paginator::from_embeds(
+        pages
+            .iter()
+            .map(|&page| serenity::CreateEmbed::default().description(page)),
+    )
+    .run(ctx)
+    .await
+}
 
-        // Update the message with the new page contents
-        press
-            .create_interaction_response(ctx, |b| {
-                b.kind(serenity::InteractionResponseType::UpdateMessage)
-                    .interaction_response_data(|b| b.embed(|b| b.description(pages[current_page])))
-            })
-            .await?;
-    }
+#[cfg(test)]
+mod test {
+    use super::*;
 
-    Ok(())
+    #[test]
+    fn test_button_action() {
+        assert_eq!(button_action("12:next", "12"), Some("next"));
+        assert_eq!(button_action("12:jump", "12"), Some("jump"));
+        // Buttons of paginators whose ID starts with the same digits
+        assert_eq!(button_action("123:next", "12"), None);
+        assert_eq!(button_action("12:next", "123"), None);
+        assert_eq!(button_action("12next", "12"), None);
+        assert_eq!(button_action("12:nextpage", "12"), None);
+        assert_eq!(button_action("12:modal", "12"), None);
+    }
 }
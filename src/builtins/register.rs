@@ -16,8 +16,8 @@ use crate::serenity_prelude as serenity;
 /// serenity::Command::set_global_commands(ctx.discord(), create_commands).await?;
 /// # Ok(()) }
 /// ```
-pub fn create_application_commands<U, E>(
-    commands: &[crate::Command<U, E>],
+pub fn create_application_commands<'a, U: 'a, E: 'a>(
+    commands: impl IntoIterator<Item = &'a crate::Command<U, E>>,
 ) -> Vec<serenity::CreateCommand> {
     /// We decided to extract context menu commands recursively, despite the subcommand hierarchy
     /// not being preserved. Because it's more confusing to just silently discard context menu
@@ -81,10 +81,374 @@ pub async fn register_in_guild<U, E>(
     Ok(())
 }
 
+/// Returns the field `key` of a JSON object, or None if it's missing or `value` isn't an object
+pub(super) fn json_field<'a>(
+    value: &'a serenity::json::Value,
+    key: &str,
+) -> Option<&'a serenity::json::Value> {
+    #[allow(unused_imports)] // required for simd-json
+    use ::serenity::json::prelude::*;
+
+    value.as_object()?.get(key)
+}
+
+/// Reads a JSON number as float, regardless of whether it's stored as integer or float
+pub(super) fn json_number(value: &serenity::json::Value) -> Option<f64> {
+    #[allow(unused_imports)] // required for simd-json
+    use ::serenity::json::prelude::*;
+
+    value
+        .as_f64()
+        .or_else(|| value.as_i64().map(|n| n as f64))
+        .or_else(|| value.as_u64().map(|n| n as f64))
+}
+
+/// Converts a JSON-serialized application command (either a [`serenity::CreateCommand`] builder or
+/// a [`serenity::Command`] fetched from Discord) into a canonical form, so that two commands compare
+/// equal if and only if Discord treats them the same
+///
+/// Only the fields which are part of the command definition are kept, while IDs and versions are
+/// dropped. Null and empty values as well as values equal to Discord's defaults are removed, and
+/// numbers and permission bitsets are normalized to floats. Values that aren't JSON objects are
+/// returned unchanged.
+pub(super) fn normalize_command_json(command: &serenity::json::Value) -> serenity::json::Value {
+    use crate::serenity_prelude::json::Value;
+    #[allow(unused_imports)] // required for simd-json
+    use ::serenity::json::prelude::*;
+
+    /// Fields of a command which are part of its definition
+    const DEFINITION_FIELDS: &[&str] = &[
+        "type",
+        "name",
+        "name_localizations",
+        "description",
+        "description_localizations",
+        "options",
+        "default_member_permissions",
+        "dm_permission",
+        "nsfw",
+    ];
+
+    fn normalize(key: &str, value: &Value) -> Option<Value> {
+        if value.is_null() {
+            return None;
+        }
+        if let Some(value) = value.as_bool() {
+            let is_default = match key {
+                "required" | "autocomplete" | "nsfw" => !value,
+                "dm_permission" => value,
+                _ => false,
+            };
+            return (!is_default).then(|| Value::from(value));
+        }
+        if let Some(value) = value.as_str() {
+            if value.is_empty() {
+                return None;
+            }
+            // Permissions are sent as strings, but may be serialized as numbers by serenity
+            if key == "default_member_permissions" {
+                return Some(Value::from(value.parse::<u64>().ok()? as f64));
+            }
+            return Some(Value::from(value));
+        }
+        if let Some(value) = json_number(value) {
+            return Some(Value::from(value));
+        }
+        if let Some(values) = value.as_array() {
+            let values = values
+                .iter()
+                .filter_map(|v| normalize("", v))
+                .collect::<Vec<_>>();
+            return (!values.is_empty()).then(|| Value::from(values));
+        }
+
+        let fields = value
+            .as_object()?
+            .iter()
+            .filter_map(|(k, v)| Some((k.clone(), normalize(k, v)?)))
+            .collect::<serenity::json::JsonMap>();
+        (!fields.is_empty()).then(|| Value::from(fields))
+    }
+
+    let fields = match command.as_object() {
+        Some(fields) => fields,
+        None => return command.clone(),
+    };
+    let fields = fields
+        .iter()
+        .filter(|(key, _)| DEFINITION_FIELDS.contains(&key.as_str()))
+        // Chat input is the default command type
+        .filter(|(key, value)| !(*key == "type" && json_number(value) == Some(1.0)))
+        .filter_map(|(key, value)| Some((key.clone(), normalize(key, value)?)))
+        .collect::<serenity::json::JsonMap>();
+    Value::from(fields)
+}
+
+/// Returns the name and type of a command normalized with [`normalize_command_json`], which
+/// together uniquely identify a command on Discord
+pub(super) fn command_identity(command: &serenity::json::Value) -> (&str, f64) {
+    #[allow(unused_imports)] // required for simd-json
+    use ::serenity::json::prelude::*;
+
+    let name = json_field(command, "name")
+        .and_then(|name| name.as_str())
+        .unwrap_or("");
+    let kind = json_field(command, "type")
+        .and_then(json_number)
+        .unwrap_or(1.0);
+    (name, kind)
+}
+
+/// A single change made by [`sync_globally`] or [`sync_in_guild`] (or, in dry-run mode, a change
+/// that would be made)
+#[derive(Clone, Debug, PartialEq)]
+pub enum CommandChange {
+    /// The command isn't registered on Discord yet and is created
+    Created {
+        /// Name of the command
+        name: String,
+    },
+    /// The command is registered on Discord, but differs from the local definition and is edited
+    Edited {
+        /// Name of the command
+        name: String,
+        /// ID of the registered command
+        id: serenity::CommandId,
+        /// Names of the top-level fields that differ, e.g. `options` or `dm_permission`
+        changed_fields: Vec<String>,
+    },
+    /// The command is registered on Discord, but doesn't exist locally anymore and is deleted
+    Deleted {
+        /// Name of the command
+        name: String,
+        /// ID of the registered command
+        id: serenity::CommandId,
+    },
+}
+
+/// Report returned by [`sync_globally`] and [`sync_in_guild`]
+///
+/// The [`std::fmt::Display`] implementation renders a human readable diff.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct CommandSyncReport {
+    /// Commands that were created, edited or deleted
+    pub changes: Vec<CommandChange>,
+    /// Names of commands that were already up to date
+    pub unchanged: Vec<String>,
+    /// Whether this was a dry run, i.e. the changes were only computed and not applied
+    pub dry_run: bool,
+}
+
+impl CommandSyncReport {
+    /// Whether the registered commands already matched the local definitions
+    pub fn is_up_to_date(&self) -> bool {
+        self.changes.is_empty()
+    }
+}
+
+impl std::fmt::Display for CommandSyncReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for change in &self.changes {
+            match change {
+                CommandChange::Created { name } => writeln!(f, "+ {}", name)?,
+                CommandChange::Edited {
+                    name,
+                    changed_fields,
+                    ..
+                } => writeln!(f, "~ {} ({})", name, changed_fields.join(", "))?,
+                CommandChange::Deleted { name, .. } => writeln!(f, "- {}", name)?,
+            }
+        }
+        write!(
+            f,
+            "{} changed, {} unchanged{}",
+            self.changes.len(),
+            self.unchanged.len(),
+            if self.dry_run { " (dry run)" } else { "" }
+        )
+    }
+}
+
+/// Error returned by [`sync_globally`] and [`sync_in_guild`] when a request to Discord failed
+///
+/// Commands are created, edited and deleted one by one, so some changes may have been applied
+/// before the failure. Those are listed in [`Self::report`].
+#[derive(Debug)]
+pub struct CommandSyncError {
+    /// The error of the failed request
+    pub error: serenity::Error,
+    /// The changes that were applied before the failure
+    pub report: CommandSyncReport,
+}
+
+impl std::fmt::Display for CommandSyncError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "failed to sync application commands after {} changes: {}",
+            self.report.changes.len(),
+            self.error
+        )
+    }
+}
+
+impl std::error::Error for CommandSyncError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(&self.error)
+    }
+}
+
+/// Shared implementation of [`sync_globally`], [`sync_in_guild`] and
+/// [`crate::CommandRegistry::resync`]
+pub(crate) async fn sync_application_commands(
+    http: &serenity::Http,
+    builders: Vec<serenity::CreateCommand>,
+    guild_id: Option<serenity::GuildId>,
+    dry_run: bool,
+) -> Result<CommandSyncReport, CommandSyncError> {
+    let mut report = CommandSyncReport {
+        dry_run,
+        ..Default::default()
+    };
+    match apply_command_sync(http, builders, guild_id, &mut report).await {
+        Ok(()) => Ok(report),
+        Err(error) => Err(CommandSyncError { error, report }),
+    }
+}
+
+/// Compares the registered commands with the local ones and applies the differences, recording
+/// every applied change in `report` right away
+async fn apply_command_sync(
+    http: &serenity::Http,
+    builders: Vec<serenity::CreateCommand>,
+    guild_id: Option<serenity::GuildId>,
+    report: &mut CommandSyncReport,
+) -> Result<(), serenity::Error> {
+    #[allow(unused_imports)] // required for simd-json
+    use ::serenity::json::prelude::*;
+
+    let dry_run = report.dry_run;
+    let registered = match guild_id {
+        Some(guild_id) => guild_id.get_commands(http).await?,
+        None => serenity::Command::get_global_commands(http).await?,
+    };
+    let mut registered = registered
+        .into_iter()
+        .map(|command| {
+            let json = serenity::json::to_value(&command).map_err(serenity::Error::from)?;
+            Ok((command.id, normalize_command_json(&json)))
+        })
+        .collect::<Result<Vec<_>, serenity::Error>>()?;
+
+    for builder in builders {
+        let local = serenity::json::to_value(&builder).map_err(serenity::Error::from)?;
+        let local = normalize_command_json(&local);
+        let name = command_identity(&local).0.to_owned();
+
+        let position = registered
+            .iter()
+            .position(|(_, remote)| command_identity(remote) == command_identity(&local));
+        let (id, remote) = match position {
+            Some(i) => registered.remove(i),
+            None => {
+                if !dry_run {
+                    match guild_id {
+                        Some(guild_id) => guild_id.create_command(http, builder).await?,
+                        None => serenity::Command::create_global_command(http, builder).await?,
+                    };
+                }
+                report.changes.push(CommandChange::Created { name });
+                continue;
+            }
+        };
+
+        if local == remote {
+            report.unchanged.push(name);
+            continue;
+        }
+
+        let mut changed_fields = Vec::new();
+        if let (Some(local), Some(remote)) = (local.as_object(), remote.as_object()) {
+            for key in local.keys().chain(remote.keys()) {
+                if local.get(key) != remote.get(key) && !changed_fields.contains(key) {
+                    changed_fields.push(key.clone());
+                }
+            }
+        }
+        changed_fields.sort();
+        if !dry_run {
+            match guild_id {
+                Some(guild_id) => guild_id.edit_command(http, id, builder).await?,
+                None => serenity::Command::edit_global_command(http, id, builder).await?,
+            };
+        }
+        report.changes.push(CommandChange::Edited {
+            name,
+            id,
+            changed_fields,
+        });
+    }
+
+    // Whatever is left has no local counterpart anymore
+    for (id, remote) in registered {
+        if !dry_run {
+            match guild_id {
+                Some(guild_id) => guild_id.delete_command(http, id).await?,
+                None => serenity::Command::delete_global_command(http, id).await?,
+            }
+        }
+        let name = command_identity(&remote).0.to_owned();
+        report.changes.push(CommandChange::Deleted { name, id });
+    }
+
+    Ok(())
+}
+
+/// Brings the global application commands registered on Discord in line with the given commands,
+/// by only creating, editing and deleting the commands that changed
+///
+/// Unlike [`register_globally`], unchanged commands aren't re-uploaded, which saves Discord's
+/// daily command creation limits. Commands are compared by all fields that make up their
+/// definition, including options, localizations, permissions and `dm_permission`.
+///
+/// With `dry_run` set, the registered commands are fetched and compared but nothing is changed.
+/// Print the returned report to see the diff:
+///
+/// ```rust,no_run
+/// # use poise::serenity_prelude as serenity;
+/// # async fn _test(ctx: serenity::Context, commands: &[poise::Command<(), ()>]) -> Result<(), poise::builtins::CommandSyncError> {
+/// let report = poise::builtins::sync_globally(&ctx, commands, true).await?;
+/// println!("{}", report);
+/// # Ok(()) }
+/// ```
+pub async fn sync_globally<U, E>(
+    http: impl AsRef<serenity::Http>,
+    commands: &[crate::Command<U, E>],
+    dry_run: bool,
+) -> Result<CommandSyncReport, CommandSyncError> {
+    let builders = create_application_commands(commands);
+    sync_application_commands(http.as_ref(), builders, None, dry_run).await
+}
+
+/// Brings the application commands registered in the given guild in line with the given
+/// commands, by only creating, editing and deleting the commands that changed
+///
+/// See [`sync_globally`] for details.
+pub async fn sync_in_guild<U, E>(
+    http: impl AsRef<serenity::Http>,
+    commands: &[crate::Command<U, E>],
+    guild_id: serenity::GuildId,
+    dry_run: bool,
+) -> Result<CommandSyncReport, CommandSyncError> {
+    let builders = create_application_commands(commands);
+    sync_application_commands(http.as_ref(), builders, Some(guild_id), dry_run).await
+}
+
 /// _Note: you probably want [`register_application_commands_buttons`] instead; it's easier and more
 /// powerful_
 ///
 /// Wraps [`create_application_commands`] and adds a bot owner permission check and status messages.
+/// Commands added or removed at runtime via [`crate::CommandRegistry`] are taken into account.
 ///
 /// This function is supposed to be a ready-to-use implementation for a `~register` command of your
 /// bot. So if you want, you can copy paste this help message for the command:
@@ -104,7 +468,9 @@ pub async fn register_application_commands<U, E>(
         return Ok(());
     }
 
-    let commands_builder = create_application_commands(&ctx.framework().options().commands);
+    let options = ctx.framework().options();
+    let commands_builder =
+        create_application_commands(options.command_registry.commands(&options.commands));
     let num_commands = commands_builder.len();
 
     if global {
@@ -134,7 +500,9 @@ pub async fn register_application_commands<U, E>(
 
 /// Spawns four buttons to register or delete application commands globally or in the current guild
 ///
-/// Upgraded version of [`register_application_commands`]
+/// Upgraded version of [`register_application_commands`]. Registering uses
+/// [`crate::CommandRegistry::resync`], so only changed commands are uploaded, and commands added
+/// or removed at runtime are taken into account.
 ///
 /// ![Screenshot of output](https://imgur.com/rTbTaDs.png)
 ///
@@ -162,8 +530,9 @@ pub async fn register_application_commands<U, E>(
 pub async fn register_application_commands_buttons<U, E>(
     ctx: crate::Context<'_, U, E>,
 ) -> Result<(), serenity::Error> {
-    let create_commands = create_application_commands(&ctx.framework().options().commands);
-    let num_commands = create_commands.len();
+    let options = ctx.framework().options();
+    let (registry, commands) = (&options.command_registry, &options.commands);
+    let num_commands = create_application_commands(registry.commands(commands)).len();
 
     let is_bot_owner = ctx.framework().options().owners.contains(&ctx.author().id);
     if !is_bot_owner {
@@ -242,11 +611,12 @@ pub async fn register_application_commands_buttons<U, E>(
     if global {
         if register {
             ctx.say(format!(
-                ":gear: Registering {} global commands...",
+                ":gear: Syncing {} global commands...",
                 num_commands
             ))
             .await?;
-            serenity::Command::set_global_commands(ctx.discord(), create_commands).await?;
+            let report = registry.resync(ctx.discord(), commands, None).await?;
+            ctx.say(format!("```diff\n{}\n```", report)).await?;
         } else {
             ctx.say(":gear: Unregistering global commands...").await?;
             serenity::Command::set_global_commands(ctx.discord(), Vec::new()).await?;
@@ -260,14 +630,12 @@ pub async fn register_application_commands_buttons<U, E>(
             }
         };
         if register {
-            ctx.say(format!(
-                ":gear: Registering {} guild commands...",
-                num_commands
-            ))
-            .await?;
-            guild_id
-                .set_commands(ctx.discord(), create_commands)
+            ctx.say(format!(":gear: Syncing {} guild commands...", num_commands))
                 .await?;
+            let report = registry
+                .resync(ctx.discord(), commands, Some(guild_id))
+                .await?;
+            ctx.say(format!("```diff\n{}\n```", report)).await?;
         } else {
             ctx.say(":gear: Unregistering guild commands...").await?;
             guild_id.set_commands(ctx.discord(), Vec::new()).await?;
@@ -284,3 +652,56 @@ pub async fn register_application_commands_buttons<U, E>(
 
     Ok(())
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::serenity_prelude::json::json;
+
+    #[test]
+    fn test_normalize_command_json() {
+        let local = json!({
+            "name": "ban",
+            "description": "Bans a user",
+            "options": [{ "type": 6, "name": "user", "description": "User", "required": true }],
+            "default_member_permissions": "4",
+        });
+        let registered = json!({
+            "id": "1234",
+            "application_id": "5678",
+            "version": "91011",
+            "type": 1,
+            "name": "ban",
+            "name_localizations": null,
+            "description": "Bans a user",
+            "description_localizations": {},
+            "options": [{
+                "type": 6,
+                "name": "user",
+                "description": "User",
+                "required": true,
+                "autocomplete": false,
+                "choices": [],
+            }],
+            "default_member_permissions": 4,
+            "dm_permission": true,
+            "nsfw": false,
+        });
+        assert_eq!(
+            normalize_command_json(&local),
+            normalize_command_json(&registered)
+        );
+
+        let changed = json!({
+            "name": "ban",
+            "description": "Bans a user",
+            "options": [{ "type": 6, "name": "user", "description": "User", "required": true }],
+            "default_member_permissions": "4",
+            "dm_permission": false,
+        });
+        assert_ne!(
+            normalize_command_json(&local),
+            normalize_command_json(&changed)
+        );
+    }
+}
@@ -0,0 +1,181 @@
+//! Contains "did you mean" suggestions for mistyped command names
+
+/// Number of single-character insertions, deletions, substitutions and transpositions of adjacent
+/// characters that turn `a` into `b` (optimal string alignment distance)
+fn edit_distance(a: &str, b: &str) -> usize {
+    let (a, b) = (a.chars().collect::<Vec<_>>(), b.chars().collect::<Vec<_>>());
+    // rows[i][j] is the distance between the first i chars of a and the first j chars of b
+    let mut rows = vec![vec![0; b.len() + 1]; a.len() + 1];
+    for (i, row) in rows.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    for j in 0..=b.len() {
+        rows[0][j] = j;
+    }
+
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let substitution_cost = if a[i - 1] == b[j - 1] { 0 } else { 1 };
+            let mut distance = (rows[i - 1][j] + 1)
+                .min(rows[i][j - 1] + 1)
+                .min(rows[i - 1][j - 1] + substitution_cost);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                distance = distance.min(rows[i - 2][j - 2] + 1);
+            }
+            rows[i][j] = distance;
+        }
+    }
+    rows[a.len()][b.len()]
+}
+
+/// How many typos are tolerated in a word of the given length
+fn max_distance(word: &str) -> usize {
+    ((word.chars().count() + 2) / 3).max(1)
+}
+
+/// Recursive function to collect suggestions for the given words within the given commands
+fn collect_suggestions<'a, U: 'a, E: 'a>(
+    commands: impl IntoIterator<Item = &'a crate::Command<U, E>>,
+    words: &[&str],
+    case_insensitive: bool,
+    include_owner_commands: bool,
+    parent_distance: usize,
+    suggestions: &mut Vec<(usize, String)>,
+) {
+    let word = match words.first() {
+        Some(&word) if case_insensitive => word.to_lowercase(),
+        Some(&word) => word.to_owned(),
+        None => return,
+    };
+
+    for command in commands {
+        if command.hide_in_help
+            || (command.owners_only && !include_owner_commands)
+            || command.prefix_action.is_none()
+        {
+            continue;
+        }
+
+        let distance = std::iter::once(command.name.as_str())
+            .chain(command.aliases.iter().copied())
+            .map(|name| match case_insensitive {
+                true => edit_distance(&word, &name.to_lowercase()),
+                false => edit_distance(&word, name),
+            })
+            .min()
+            .unwrap_or(usize::MAX);
+        if distance > max_distance(&word) {
+            continue;
+        }
+        let distance = parent_distance + distance;
+
+        // If the next word resembles a subcommand, suggest the subcommand instead of the parent
+        let suggestion_count = suggestions.len();
+        collect_suggestions(
+            &command.subcommands,
+            &words[1..],
+            case_insensitive,
+            include_owner_commands,
+            distance,
+            suggestions,
+        );
+        if suggestions.len() == suggestion_count {
+            suggestions.push((distance, command.qualified_name.clone()));
+        }
+    }
+}
+
+/// Finds the commands which were most likely meant by a mistyped prefix command invocation
+///
+/// `input` is the message content after the prefix, like in
+/// [`crate::FrameworkError::UnknownCommand::msg_content`]. Command names and aliases are compared
+/// to the words of the input, allowing a few typos depending on the word length. Subcommands are
+/// suggested if the words following the command name resemble a subcommand.
+///
+/// Returns the qualified names of the suggested commands, best match first. Commands with
+/// [`crate::Command::hide_in_help`] are never suggested; commands with
+/// [`crate::Command::owners_only`] only if `include_owner_commands` is set.
+///
+/// ```rust
+/// # use poise::builtins::suggest_commands;
+/// #[poise::command(prefix_command)]
+/// async fn help(ctx: poise::Context<'_, (), ()>) -> Result<(), ()> { Ok(()) }
+/// let commands = vec![help()];
+///
+/// assert_eq!(suggest_commands(&commands, "hlep me", true, false), ["help"]);
+/// assert!(suggest_commands(&commands, "ping", true, false).is_empty());
+/// ```
+pub fn suggest_commands<'a, U: 'a, E: 'a>(
+    commands: impl IntoIterator<Item = &'a crate::Command<U, E>>,
+    input: &str,
+    case_insensitive: bool,
+    include_owner_commands: bool,
+) -> Vec<String> {
+    let words = input.split_whitespace().collect::<Vec<_>>();
+    let mut suggestions = Vec::new();
+    collect_suggestions(
+        commands,
+        &words,
+        case_insensitive,
+        include_owner_commands,
+        0,
+        &mut suggestions,
+    );
+
+    suggestions.sort();
+    suggestions.into_iter().map(|(_, name)| name).collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_edit_distance() {
+        assert_eq!(edit_distance("help", "help"), 0);
+        assert_eq!(edit_distance("hlep", "help"), 1);
+        assert_eq!(edit_distance("hep", "help"), 1);
+        assert_eq!(edit_distance("kitten", "sitting"), 3);
+        assert_eq!(edit_distance("", "ban"), 3);
+    }
+
+    #[test]
+    fn test_suggest_commands() {
+        fn command(name: &str, subcommands: Vec<crate::Command<(), ()>>) -> crate::Command<(), ()> {
+            let mut command = crate::Command {
+                name: name.into(),
+                qualified_name: name.into(),
+                subcommands,
+                prefix_action: Some(|_| Box::pin(async { Ok(()) })),
+                ..Default::default()
+            };
+            crate::set_qualified_names(std::slice::from_mut(&mut command));
+            command
+        }
+        let mut commands = vec![
+            command("help", vec![]),
+            command(
+                "config",
+                vec![command("set", vec![]), command("reset", vec![])],
+            ),
+            command("shutdown", vec![]),
+        ];
+        commands[2].owners_only = true;
+
+        assert_eq!(suggest_commands(&commands, "HLEP", true, false), ["help"]);
+        assert!(suggest_commands(&commands, "HLEP", false, false).is_empty());
+        assert_eq!(
+            suggest_commands(&commands, "confg rset", true, false),
+            ["config reset", "config set"]
+        );
+        assert_eq!(
+            suggest_commands(&commands, "confg xyz", true, false),
+            ["config"]
+        );
+        assert!(suggest_commands(&commands, "shutdwn", true, false).is_empty());
+        assert_eq!(
+            suggest_commands(&commands, "shutdwn", true, true),
+            ["shutdown"]
+        );
+    }
+}
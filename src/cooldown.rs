@@ -2,109 +2,802 @@
 
 use crate::serenity_prelude as serenity;
 // I usually don't really do imports, but these are very convenient
-use crate::util::OrderedMap;
-use std::time::{Duration, Instant};
+use std::collections::HashMap;
+use std::time::{Duration, SystemTime};
+
+/// A rate limit of [`Self::uses`] invocations per [`Self::window`]
+///
+/// This is implemented as a token bucket: the bucket holds up to [`Self::burst`] uses and is
+/// refilled at a rate of `uses` per `window`. With the default `burst == uses`, up to `uses`
+/// invocations can be made back-to-back, after which one use is regained every
+/// `window / uses`.
+///
+/// A plain [`Duration`] converts into a rate of one use per that duration, which is the classic
+/// cooldown.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Hash)]
+pub struct CooldownRate {
+    /// How many uses are regained per [`Self::window`]
+    pub uses: u32,
+    /// Length of the window
+    pub window: Duration,
+    /// How many uses can be made back-to-back when the bucket is full. Usually equal to
+    /// [`Self::uses`]; set higher to allow bursts
+    pub burst: u32,
+}
+
+impl CooldownRate {
+    /// Creates a rate limit of `uses` per `window`, with a burst allowance of `uses`
+    pub fn new(uses: u32, window: Duration) -> Self {
+        Self {
+            uses,
+            window,
+            burst: uses,
+        }
+    }
+
+    /// Sets how many uses can be made back-to-back when the bucket is full
+    pub fn with_burst(mut self, burst: u32) -> Self {
+        self.burst = burst;
+        self
+    }
+
+    /// Time it takes to regain a single use
+    fn emission_interval(&self) -> Duration {
+        self.window / self.uses.max(1)
+    }
+
+    /// How far the bucket may be depleted before invocations are rejected
+    fn burst_tolerance(&self) -> Duration {
+        self.emission_interval() * (self.burst.max(1) - 1)
+    }
+
+    /// How many uses are left in a bucket which is completely refilled after `backlog`
+    fn remaining_uses(&self, backlog: Duration) -> u32 {
+        let emission_interval = self.emission_interval().as_nanos().max(1);
+        // Uses that haven't been regained yet, counting partially regained ones as consumed
+        let consumed = (backlog.as_nanos() + emission_interval - 1) / emission_interval;
+        self.burst
+            .saturating_sub(std::convert::TryFrom::try_from(consumed).unwrap_or(u32::MAX))
+    }
+}
+
+impl From<Duration> for CooldownRate {
+    fn from(window: Duration) -> Self {
+        Self::new(1, window)
+    }
+}
 
 /// Configuration struct for [`Cooldowns`]
 #[derive(Default, Clone, PartialEq, Eq, Debug, Hash)]
 pub struct CooldownConfig {
     /// This cooldown operates on a global basis
-    pub global: Option<Duration>,
+    pub global: Option<CooldownRate>,
     /// This cooldown operates on a per-user basis
-    pub user: Option<Duration>,
+    pub user: Option<CooldownRate>,
     /// This cooldown operates on a per-guild basis
-    pub guild: Option<Duration>,
+    pub guild: Option<CooldownRate>,
     /// This cooldown operates on a per-channel basis
-    pub channel: Option<Duration>,
+    pub channel: Option<CooldownRate>,
     /// This cooldown operates on a per-member basis
-    pub member: Option<Duration>,
+    pub member: Option<CooldownRate>,
+}
+
+/// Returned by [`crate::FrameworkOptions::cooldown_override`] to adjust the cooldowns of a
+/// single invocation
+#[derive(Clone, PartialEq, Eq, Debug, Hash)]
+pub enum CooldownOverride {
+    /// Apply the command's own cooldown configuration
+    Default,
+    /// Apply this cooldown configuration instead of the command's own
+    Config(CooldownConfig),
+    /// Don't apply any cooldowns to this invocation, and don't count it towards any cooldowns
+    Exempt,
+}
+
+/// A single cooldown bucket, i.e. the scope in which invocations count towards a cooldown
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Hash)]
+pub enum CooldownBucket {
+    /// Shared by all invocations
+    Global,
+    /// Shared by all invocations of this user
+    User(serenity::UserId),
+    /// Shared by all invocations in this guild
+    Guild(serenity::GuildId),
+    /// Shared by all invocations in this channel
+    Channel(serenity::ChannelId),
+    /// Shared by all invocations of this user in this guild
+    Member(serenity::UserId, serenity::GuildId),
+}
+
+impl std::fmt::Display for CooldownBucket {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Global => write!(f, "global"),
+            Self::User(user_id) => write!(f, "user:{}", user_id),
+            Self::Guild(guild_id) => write!(f, "guild:{}", guild_id),
+            Self::Channel(channel_id) => write!(f, "channel:{}", channel_id),
+            Self::Member(user_id, guild_id) => write!(f, "member:{}:{}", user_id, guild_id),
+        }
+    }
+}
+
+/// Error thrown when parsing a malformed [`CooldownBucket`] string
+#[derive(Default, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct InvalidCooldownBucket;
+impl std::fmt::Display for InvalidCooldownBucket {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("invalid cooldown bucket")
+    }
+}
+impl std::error::Error for InvalidCooldownBucket {}
+
+impl std::str::FromStr for CooldownBucket {
+    type Err = InvalidCooldownBucket;
+
+    /// Parses the format produced by the [`std::fmt::Display`] implementation
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        // IDs are non-zero, so parse into NonZeroU64 to not panic in the ID constructors
+        let id = |s: &str| {
+            s.parse::<std::num::NonZeroU64>()
+                .map(|id| id.get())
+                .map_err(|_| InvalidCooldownBucket)
+        };
+
+        let mut parts = s.split(':');
+        let bucket = match (parts.next(), parts.next(), parts.next()) {
+            (Some("global"), None, None) => Self::Global,
+            (Some("user"), Some(user_id), None) => Self::User(serenity::UserId::new(id(user_id)?)),
+            (Some("guild"), Some(guild_id), None) => {
+                Self::Guild(serenity::GuildId::new(id(guild_id)?))
+            }
+            (Some("channel"), Some(channel_id), None) => {
+                Self::Channel(serenity::ChannelId::new(id(channel_id)?))
+            }
+            (Some("member"), Some(user_id), Some(guild_id)) => Self::Member(
+                serenity::UserId::new(id(user_id)?),
+                serenity::GuildId::new(id(guild_id)?),
+            ),
+            _ => return Err(InvalidCooldownBucket),
+        };
+        if parts.next().is_some() {
+            return Err(InvalidCooldownBucket);
+        }
+        Ok(bucket)
+    }
+}
+
+/// Identifies the cooldown state of one bucket of one command in a [`CooldownStore`]
+#[derive(Clone, PartialEq, Eq, Debug, Hash)]
+pub struct CooldownKey {
+    /// [`crate::Command::identifying_name`] of the command
+    pub command: String,
+    /// The bucket within the command
+    pub bucket: CooldownBucket,
+}
+
+/// Storage backend for cooldown state, see [`crate::FrameworkOptions::cooldown_store`]
+///
+/// The store maps [`CooldownKey`]s to wall-clock timestamps at which the respective bucket is
+/// completely refilled (for one-use-per-duration cooldowns, that's simply the time the cooldown
+/// expires). Wall-clock time (as opposed to [`std::time::Instant`]) is used so that cooldowns
+/// remain valid across restarts when the store is persistent.
+///
+/// The methods are synchronous because they're called while checking cooldowns before every
+/// command invocation. Implementations which talk to a remote database should cache accordingly.
+pub trait CooldownStore: Send + Sync {
+    /// Returns the timestamp stored for the given key, if any
+    fn get(&self, key: &CooldownKey) -> Option<SystemTime>;
+
+    /// Stores a timestamp for the given key, replacing any previous one
+    fn set(&self, key: CooldownKey, timestamp: SystemTime);
+}
+
+/// Cooldown timestamps of a [`CooldownStore`], with amortized removal of expired entries
+#[derive(Default, Debug)]
+struct CooldownEntries {
+    /// Stored timestamps
+    map: HashMap<CooldownKey, SystemTime>,
+    /// Number of entries at which expired entries are removed next
+    prune_at: usize,
+}
+
+impl CooldownEntries {
+    /// Stores a timestamp, removing expired entries first whenever the number of entries has
+    /// doubled since the last time
+    fn insert(&mut self, key: CooldownKey, timestamp: SystemTime) {
+        if self.map.len() >= self.prune_at {
+            self.prune();
+            self.prune_at = (self.map.len() * 2).max(64);
+        }
+        self.map.insert(key, timestamp);
+    }
+
+    /// Removes the entries whose timestamp has passed. Those buckets are completely refilled, which
+    /// is the same as having no entry
+    fn prune(&mut self) {
+        let now = SystemTime::now();
+        self.map.retain(|_, timestamp| *timestamp > now);
+    }
+}
+
+/// Default [`CooldownStore`] which keeps cooldowns in memory. Cooldowns are lost on restart
+#[derive(Default, Debug)]
+pub struct InMemoryCooldownStore {
+    /// Stored timestamps
+    entries: std::sync::Mutex<CooldownEntries>,
+}
+
+impl CooldownStore for InMemoryCooldownStore {
+    fn get(&self, key: &CooldownKey) -> Option<SystemTime> {
+        self.entries.lock().unwrap().map.get(key).copied()
+    }
+
+    fn set(&self, key: CooldownKey, timestamp: SystemTime) {
+        self.entries.lock().unwrap().insert(key, timestamp);
+    }
+}
+
+/// [`CooldownStore`] which persists cooldowns to a file, so they survive restarts
+///
+/// All entries are held in memory. Changes are written to the file by a background thread, batched
+/// over [`Self::FLUSH_DELAY`], so [`CooldownStore::set`] never blocks on file IO. Expired entries
+/// are left out. The file is written a last time when the store is dropped. This is fine for small
+/// to medium bots; for bigger bots, implement [`CooldownStore`] on top of a database.
+///
+/// The file contains one entry per line in the form `<unix timestamp in ms>\t<bucket>\t<command>`.
+#[derive(Debug)]
+pub struct FileCooldownStore {
+    /// State shared with the flush thread
+    shared: std::sync::Arc<FileCooldownStoreShared>,
+    /// Thread which writes changes to the file. Only `None` while dropping
+    flush_thread: Option<std::thread::JoinHandle<()>>,
+}
+
+/// State of a [`FileCooldownStore`] which is shared with its flush thread
+#[derive(Debug)]
+struct FileCooldownStoreShared {
+    /// File to persist the entries to
+    path: std::path::PathBuf,
+    /// Stored timestamps
+    entries: std::sync::Mutex<CooldownEntries>,
+    /// Whether there are unwritten changes, and whether the store was dropped
+    flush_state: std::sync::Mutex<FlushState>,
+    /// Wakes up the flush thread when [`Self::flush_state`] changes
+    flush_wakeup: std::sync::Condvar,
+    /// Held while writing the file, so that [`FileCooldownStore::flush`] and the flush thread
+    /// don't write at the same time
+    write_lock: std::sync::Mutex<()>,
+}
+
+/// See [`FileCooldownStoreShared::flush_state`]
+#[derive(Default, Debug)]
+struct FlushState {
+    /// Entries were changed since the last write
+    dirty: bool,
+    /// The store was dropped, so the flush thread should write a last time and exit
+    shutdown: bool,
+}
+
+impl FileCooldownStore {
+    /// How long the flush thread waits after a change before writing the file, so that changes in
+    /// quick succession are written together
+    pub const FLUSH_DELAY: Duration = Duration::from_secs(1);
+
+    /// Loads the cooldown file at the given path, or starts out empty if the file doesn't exist
+    ///
+    /// Malformed lines are skipped with a warning.
+    pub fn open(path: impl Into<std::path::PathBuf>) -> std::io::Result<Self> {
+        let path = path.into();
+
+        let contents = match std::fs::read_to_string(&path) {
+            Ok(x) => x,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+            Err(e) => return Err(e),
+        };
+        let mut entries = CooldownEntries::default();
+        for line in contents.lines().filter(|line| !line.trim().is_empty()) {
+            match parse_cooldown_file_line(line) {
+                Some((key, timestamp)) => entries.insert(key, timestamp),
+                None => log::warn!("skipping malformed line in cooldown file: {:?}", line),
+            }
+        }
+        entries.prune();
+
+        let shared = std::sync::Arc::new(FileCooldownStoreShared {
+            path,
+            entries: std::sync::Mutex::new(entries),
+            flush_state: std::sync::Mutex::new(FlushState::default()),
+            flush_wakeup: std::sync::Condvar::new(),
+            write_lock: std::sync::Mutex::new(()),
+        });
+        let flush_thread = {
+            let shared = shared.clone();
+            std::thread::Builder::new()
+                .name("poise-cooldown-flush".into())
+                .spawn(move || shared.flush_loop())?
+        };
+
+        Ok(Self {
+            shared,
+            flush_thread: Some(flush_thread),
+        })
+    }
+
+    /// Writes all entries to the file right away, instead of waiting for the flush thread
+    ///
+    /// This blocks on file IO, so don't call it from async code without
+    /// [`tokio::task::spawn_blocking`].
+    pub fn flush(&self) -> std::io::Result<()> {
+        self.shared.flush_state.lock().unwrap().dirty = false;
+        self.shared.write()
+    }
+}
+
+impl FileCooldownStoreShared {
+    /// Body of the flush thread: waits for changes and writes them, until the store is dropped
+    fn flush_loop(&self) {
+        let mut state = self.flush_state.lock().unwrap();
+        loop {
+            state = self
+                .flush_wakeup
+                .wait_while(state, |state| !state.dirty && !state.shutdown)
+                .unwrap();
+            if !state.shutdown {
+                // Batch up the changes coming in during the delay
+                state = self
+                    .flush_wakeup
+                    .wait_timeout_while(state, FileCooldownStore::FLUSH_DELAY, |state| {
+                        !state.shutdown
+                    })
+                    .unwrap()
+                    .0;
+            }
+
+            let (dirty, shutdown) = (state.dirty, state.shutdown);
+            state.dirty = false;
+            drop(state);
+
+            if dirty {
+                if let Err(e) = self.write() {
+                    log::warn!(
+                        "failed to write cooldown file {}: {}",
+                        self.path.display(),
+                        e
+                    );
+                }
+            }
+            if shutdown {
+                return;
+            }
+            state = self.flush_state.lock().unwrap();
+        }
+    }
+
+    /// Writes all unexpired entries to the file, going through a temporary file to not leave a
+    /// half written file behind on crashes
+    fn write(&self) -> std::io::Result<()> {
+        let _write_lock = self.write_lock.lock().unwrap();
+
+        let mut contents = String::new();
+        {
+            let mut entries = self.entries.lock().unwrap();
+            entries.prune();
+            for (key, timestamp) in &entries.map {
+                let millis = timestamp
+                    .duration_since(SystemTime::UNIX_EPOCH)
+                    .unwrap_or_default()
+                    .as_millis();
+                contents += &format!("{}\t{}\t{}\n", millis, key.bucket, key.command);
+            }
+        }
+
+        let mut temp_path = self.path.clone().into_os_string();
+        temp_path.push(".tmp");
+        std::fs::write(&temp_path, contents)?;
+        std::fs::rename(&temp_path, &self.path)
+    }
+}
+
+/// Parses a line of the [`FileCooldownStore`] file format
+fn parse_cooldown_file_line(line: &str) -> Option<(CooldownKey, SystemTime)> {
+    let mut parts = line.splitn(3, '\t');
+    let millis = parts.next()?.parse::<u64>().ok()?;
+    let bucket = parts.next()?.parse().ok()?;
+    let command = parts.next()?.to_owned();
+
+    let key = CooldownKey { command, bucket };
+    Some((key, SystemTime::UNIX_EPOCH + Duration::from_millis(millis)))
+}
+
+impl CooldownStore for FileCooldownStore {
+    fn get(&self, key: &CooldownKey) -> Option<SystemTime> {
+        self.shared.entries.lock().unwrap().map.get(key).copied()
+    }
+
+    fn set(&self, key: CooldownKey, timestamp: SystemTime) {
+        self.shared.entries.lock().unwrap().insert(key, timestamp);
+        self.shared.flush_state.lock().unwrap().dirty = true;
+        self.shared.flush_wakeup.notify_all();
+    }
+}
+
+impl Drop for FileCooldownStore {
+    fn drop(&mut self) {
+        self.shared.flush_state.lock().unwrap().shutdown = true;
+        self.shared.flush_wakeup.notify_all();
+        if let Some(flush_thread) = self.flush_thread.take() {
+            let _ = flush_thread.join();
+        }
+    }
 }
 
 /// Handles cooldowns for a single command
 ///
 /// You probably don't need to use this directly. `#[poise::command]` automatically generates a
 /// cooldown handler.
+///
+/// The cooldown state itself lives in [`crate::FrameworkOptions::cooldown_store`]; this struct
+/// only holds the configuration.
 #[derive(Default, Clone, Debug, PartialEq, Eq, Hash)]
 pub struct Cooldowns {
     /// Stores the cooldown durations
     cooldown: CooldownConfig,
-
-    /// Stores the timestamp of the last global invocation
-    global_invocation: Option<Instant>,
-    /// Stores the timestamps of the last invocation per user
-    user_invocations: OrderedMap<serenity::UserId, Instant>,
-    /// Stores the timestamps of the last invocation per guild
-    guild_invocations: OrderedMap<serenity::GuildId, Instant>,
-    /// Stores the timestamps of the last invocation per channel
-    channel_invocations: OrderedMap<serenity::ChannelId, Instant>,
-    /// Stores the timestamps of the last invocation per member (user and guild)
-    member_invocations: OrderedMap<(serenity::UserId, serenity::GuildId), Instant>,
 }
 
 impl Cooldowns {
     /// Create a new cooldown handler with the given cooldown durations
     pub fn new(config: CooldownConfig) -> Self {
-        Self {
-            cooldown: config,
+        Self { cooldown: config }
+    }
+
+    /// Returns the configured cooldown durations
+    pub fn config(&self) -> &CooldownConfig {
+        &self.cooldown
+    }
 
-            global_invocation: None,
-            user_invocations: OrderedMap::new(),
-            guild_invocations: OrderedMap::new(),
-            channel_invocations: OrderedMap::new(),
-            member_invocations: OrderedMap::new(),
+    /// Returns the cooldowns that apply to this invocation of the given command, i.e. the
+    /// command's cooldowns with [`crate::FrameworkOptions::cooldown_override`] applied. Returns None
+    /// if the invocation is exempt from cooldowns
+    ///
+    /// [`crate::Command::cooldowns`] is locked only to read the configuration, so this must not be
+    /// called while holding that lock.
+    pub async fn effective_for<U, E>(
+        ctx: crate::Context<'_, U, E>,
+        command: &crate::Command<U, E>,
+    ) -> Option<Self> {
+        let config = command.cooldowns.lock().unwrap().cooldown.clone();
+        let cooldown_override = match ctx.framework().options().cooldown_override {
+            Some(cooldown_override) => cooldown_override(ctx, command, &config).await,
+            None => CooldownOverride::Default,
+        };
+        match cooldown_override {
+            CooldownOverride::Default => Some(Self::new(config)),
+            CooldownOverride::Config(config) => Some(Self::new(config)),
+            CooldownOverride::Exempt => None,
         }
     }
 
-    /// Queries the cooldown buckets and checks if all cooldowns have expired and command
-    /// execution may proceed. If not, Some is returned with the remaining cooldown
-    pub fn remaining_cooldown<U, E>(&self, ctx: crate::Context<'_, U, E>) -> Option<Duration> {
-        let mut cooldown_data = vec![
-            (self.cooldown.global, self.global_invocation),
-            (
-                self.cooldown.user,
-                self.user_invocations.get(&ctx.author().id).copied(),
-            ),
-            (
-                self.cooldown.channel,
-                self.channel_invocations.get(&ctx.channel_id()).copied(),
-            ),
+    /// Lists the configured cooldowns together with the bucket that this invocation falls into
+    fn buckets<U, E>(&self, ctx: crate::Context<'_, U, E>) -> Vec<(CooldownRate, CooldownBucket)> {
+        let config = &self.cooldown;
+        let author_id = ctx.author().id;
+        let mut buckets = vec![
+            (config.global, CooldownBucket::Global),
+            (config.user, CooldownBucket::User(author_id)),
+            (config.channel, CooldownBucket::Channel(ctx.channel_id())),
         ];
 
         if let Some(guild_id) = ctx.guild_id() {
-            cooldown_data.push((
-                self.cooldown.guild,
-                self.guild_invocations.get(&guild_id).copied(),
-            ));
-            cooldown_data.push((
-                self.cooldown.member,
-                self.member_invocations
-                    .get(&(ctx.author().id, guild_id))
-                    .copied(),
-            ));
+            buckets.push((config.guild, CooldownBucket::Guild(guild_id)));
+            buckets.push((config.member, CooldownBucket::Member(author_id, guild_id)));
         }
 
-        cooldown_data
-            .iter()
-            .filter_map(|&(cooldown, last_invocation)| {
-                let duration_since = Instant::now().saturating_duration_since(last_invocation?);
-                let cooldown_left = cooldown?.checked_sub(duration_since)?;
-                Some(cooldown_left)
+        buckets
+            .into_iter()
+            .filter_map(|(rate, bucket)| Some((rate?, bucket)))
+            .collect()
+    }
+
+    /// Queries the cooldown buckets and checks if all cooldowns have expired and command
+    /// execution may proceed. If not, Some is returned with the remaining cooldown
+    ///
+    /// The cooldown state is looked up for the invoked command, [`crate::Context::command`].
+    /// [`crate::FrameworkOptions::cooldown_override`] isn't applied; use [`Self::effective_for`]
+    /// for that.
+    pub fn remaining_cooldown<U, E>(&self, ctx: crate::Context<'_, U, E>) -> Option<Duration> {
+        self.remaining_cooldown_for(ctx, ctx.command())
+    }
+
+    /// Like [`Self::remaining_cooldown`], but looks up the cooldown state of the given command.
+    /// Used for checking parent command cooldowns
+    pub fn remaining_cooldown_for<U, E>(
+        &self,
+        ctx: crate::Context<'_, U, E>,
+        command: &crate::Command<U, E>,
+    ) -> Option<Duration> {
+        self.tripped_cooldown_for(ctx, command)
+            .map(|tripped| tripped.remaining_cooldown)
+    }
+
+    /// Like [`Self::remaining_cooldown_for`], but returns details about the bucket that tripped
+    ///
+    /// If multiple buckets tripped, the one with the longest remaining cooldown is returned.
+    pub fn tripped_cooldown_for<U, E>(
+        &self,
+        ctx: crate::Context<'_, U, E>,
+        command: &crate::Command<U, E>,
+    ) -> Option<TrippedCooldown> {
+        let store = &*ctx.framework().options().cooldown_store;
+        let now = SystemTime::now();
+
+        self.buckets(ctx)
+            .into_iter()
+            .filter_map(|(rate, bucket)| {
+                let key = CooldownKey {
+                    command: command.identifying_name.clone(),
+                    bucket,
+                };
+                // How long until the bucket is completely refilled
+                let backlog = store.get(&key)?.duration_since(now).unwrap_or_default();
+                let remaining_cooldown = backlog.checked_sub(rate.burst_tolerance())?;
+                if remaining_cooldown.is_zero() {
+                    return None;
+                }
+
+                Some(TrippedCooldown {
+                    bucket,
+                    rate,
+                    remaining_cooldown,
+                    remaining_uses: rate.remaining_uses(backlog),
+                    window_reset: backlog,
+                })
             })
-            .max()
+            .max_by_key(|tripped| tripped.remaining_cooldown)
     }
 
     /// Indicates that a command has been executed and all associated cooldowns should start running
-    pub fn start_cooldown<U, E>(&mut self, ctx: crate::Context<'_, U, E>) {
-        let now = Instant::now();
+    ///
+    /// [`crate::FrameworkOptions::cooldown_override`] isn't applied; use [`Self::effective_for`]
+    /// for that.
+    pub fn start_cooldown<U, E>(&self, ctx: crate::Context<'_, U, E>) {
+        self.start_cooldown_for(ctx, ctx.command());
+    }
 
-        self.global_invocation = Some(now);
-        self.user_invocations.insert(ctx.author().id, now);
-        self.channel_invocations.insert(ctx.channel_id(), now);
+    /// Like [`Self::start_cooldown`], but stores the cooldown state for the given command
+    pub fn start_cooldown_for<U, E>(
+        &self,
+        ctx: crate::Context<'_, U, E>,
+        command: &crate::Command<U, E>,
+    ) {
+        let store = &*ctx.framework().options().cooldown_store;
+        let now = SystemTime::now();
 
-        if let Some(guild_id) = ctx.guild_id() {
-            self.guild_invocations.insert(guild_id, now);
-            self.member_invocations
-                .insert((ctx.author().id, guild_id), now);
+        for (rate, bucket) in self.buckets(ctx) {
+            let key = CooldownKey {
+                command: command.identifying_name.clone(),
+                bucket,
+            };
+            // Take one use out of the bucket, i.e. push back the time it will be refilled
+            let refilled_at = store.get(&key).filter(|&t| t > now).unwrap_or(now);
+            store.set(key, refilled_at + rate.emission_interval());
+        }
+    }
+}
+
+/// Details about a cooldown bucket that prevented an invocation. Returned by
+/// [`Cooldowns::tripped_cooldown_for`]
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Hash)]
+pub struct TrippedCooldown {
+    /// The bucket that tripped
+    pub bucket: CooldownBucket,
+    /// The rate limit configured for this bucket
+    pub rate: CooldownRate,
+    /// Time until the next use is available
+    pub remaining_cooldown: Duration,
+    /// How many uses are left in the bucket
+    pub remaining_uses: u32,
+    /// Time until the bucket is completely refilled, i.e. all [`CooldownRate::burst`] uses are
+    /// available again
+    pub window_reset: Duration,
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_cooldown_bucket_roundtrip() {
+        for bucket in [
+            CooldownBucket::Global,
+            CooldownBucket::User(serenity::UserId::new(123)),
+            CooldownBucket::Guild(serenity::GuildId::new(456)),
+            CooldownBucket::Channel(serenity::ChannelId::new(789)),
+            CooldownBucket::Member(serenity::UserId::new(123), serenity::GuildId::new(456)),
+        ] {
+            assert_eq!(bucket.to_string().parse::<CooldownBucket>(), Ok(bucket));
         }
+
+        assert!("user".parse::<CooldownBucket>().is_err());
+        assert!("user:0".parse::<CooldownBucket>().is_err());
+        assert!("global:1".parse::<CooldownBucket>().is_err());
+    }
+
+    #[test]
+    fn test_remaining_uses() {
+        let rate = CooldownRate::new(3, Duration::from_secs(60));
+        assert_eq!(rate.remaining_uses(Duration::ZERO), 3);
+        assert_eq!(rate.remaining_uses(Duration::from_secs(20)), 2);
+        // A partially regained use doesn't count yet
+        assert_eq!(rate.remaining_uses(Duration::from_secs(21)), 1);
+        assert_eq!(rate.remaining_uses(Duration::from_secs(60)), 0);
+        assert_eq!(
+            rate.with_burst(5).remaining_uses(Duration::from_secs(60)),
+            2
+        );
+    }
+
+    /// Starts the cooldowns of the invoked command, and records whether they tripped afterwards
+    fn use_cooldown(
+        ctx: crate::PrefixContext<'_, std::sync::Mutex<Vec<Option<TrippedCooldown>>>, ()>,
+    ) -> crate::BoxFuture<
+        '_,
+        Result<(), crate::FrameworkError<'_, std::sync::Mutex<Vec<Option<TrippedCooldown>>>, ()>>,
+    > {
+        Box::pin(async move {
+            let cooldowns = ctx.command.cooldowns.lock().unwrap();
+            cooldowns.start_cooldown_for(ctx.into(), ctx.command);
+            let tripped = cooldowns.tripped_cooldown_for(ctx.into(), ctx.command);
+            ctx.data.lock().unwrap().push(tripped);
+            Ok(())
+        })
+    }
+
+    #[tokio::test]
+    async fn test_start_and_trip_cooldown() {
+        let rate = CooldownRate::new(2, Duration::from_secs(60));
+        let harness = crate::testing::TestHarness::with_prefix_commands(
+            vec![crate::Command {
+                name: "limited".into(),
+                prefix_action: Some(use_cooldown),
+                cooldowns: std::sync::Mutex::new(Cooldowns::new(CooldownConfig {
+                    user: Some(rate),
+                    ..Default::default()
+                })),
+                ..Default::default()
+            }],
+            Default::default(),
+            std::sync::Mutex::new(Vec::new()),
+        )
+        .await;
+
+        for _ in 0..3 {
+            harness.dispatch_message(harness.message("~limited")).await;
+        }
+
+        let results = std::mem::take(&mut *harness.framework().user_data.lock().unwrap());
+        assert_eq!(results.len(), 2);
+        // One use is left after the first invocation
+        assert_eq!(results[0], None);
+        let tripped = results[1].expect("cooldown should trip after two uses");
+        assert_eq!(
+            tripped.bucket,
+            CooldownBucket::User(crate::testing::AUTHOR_ID)
+        );
+        assert_eq!(tripped.rate, rate);
+        assert_eq!(tripped.remaining_uses, 0);
+        assert!(tripped.remaining_cooldown <= Duration::from_secs(30));
+        assert!(tripped.remaining_cooldown > Duration::from_secs(29));
+        assert!(tripped.window_reset > Duration::from_secs(59));
+
+        // The third invocation was rejected with an error message
+        let replies = harness.replies();
+        assert_eq!(replies.len(), 1);
+        assert!(replies[0].content.as_deref().unwrap().contains("too fast"));
+    }
+
+    /// Counts its invocations and starts cooldowns like the `#[poise::command]` macro does
+    fn count_invocation(
+        ctx: crate::PrefixContext<'_, std::sync::atomic::AtomicUsize, ()>,
+    ) -> crate::BoxFuture<
+        '_,
+        Result<(), crate::FrameworkError<'_, std::sync::atomic::AtomicUsize, ()>>,
+    > {
+        Box::pin(async move {
+            if let Some(cooldowns) = Cooldowns::effective_for(ctx.into(), ctx.command).await {
+                cooldowns.start_cooldown(ctx.into());
+            }
+            ctx.data.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            Ok(())
+        })
+    }
+
+    /// Exempts invocations via the `vip` alias, and limits others to one per minute
+    fn vip_override<'a>(
+        ctx: crate::Context<'a, std::sync::atomic::AtomicUsize, ()>,
+        _: &'a crate::Command<std::sync::atomic::AtomicUsize, ()>,
+        _: &'a CooldownConfig,
+    ) -> crate::BoxFuture<'a, CooldownOverride> {
+        Box::pin(async move {
+            // Stands in for a database lookup
+            tokio::task::yield_now().await;
+            if ctx.invoked_command_name() == "vip" {
+                CooldownOverride::Exempt
+            } else {
+                CooldownOverride::Config(CooldownConfig {
+                    user: Some(Duration::from_secs(60).into()),
+                    ..Default::default()
+                })
+            }
+        })
+    }
+
+    #[tokio::test]
+    async fn test_cooldown_override() {
+        let harness = crate::testing::TestHarness::with_prefix_commands(
+            vec![crate::Command {
+                name: "limited".into(),
+                aliases: &["vip"],
+                prefix_action: Some(count_invocation),
+                ..Default::default()
+            }],
+            crate::FrameworkOptions {
+                cooldown_override: Some(vip_override),
+                ..Default::default()
+            },
+            std::sync::atomic::AtomicUsize::new(0),
+        )
+        .await;
+
+        for content in ["~limited", "~limited", "~vip", "~vip"] {
+            harness.dispatch_message(harness.message(content)).await;
+        }
+
+        let invocations = harness
+            .framework()
+            .user_data
+            .load(std::sync::atomic::Ordering::SeqCst);
+        assert_eq!(invocations, 3);
+        // Only the second invocation hit the cooldown
+        assert_eq!(harness.replies().len(), 1);
+    }
+
+    #[test]
+    fn test_file_cooldown_store() {
+        let path =
+            std::env::temp_dir().join(format!("poise_test_cooldowns_{}.txt", std::process::id()));
+        let key = |user_id| CooldownKey {
+            command: "ping".into(),
+            bucket: CooldownBucket::User(serenity::UserId::new(user_id)),
+        };
+        // Whole milliseconds, because that's what's stored in the file
+        let now = SystemTime::UNIX_EPOCH
+            + Duration::from_millis(
+                SystemTime::now()
+                    .duration_since(SystemTime::UNIX_EPOCH)
+                    .unwrap()
+                    .as_millis() as u64,
+            );
+        let timestamp = now + Duration::from_secs(3600);
+
+        let store = FileCooldownStore::open(&path).unwrap();
+        assert_eq!(store.get(&key(1)), None);
+        store.set(key(1), timestamp);
+        store.set(key(2), now - Duration::from_secs(1));
+        store.flush().unwrap();
+
+        let reopened = FileCooldownStore::open(&path).unwrap();
+        assert_eq!(reopened.get(&key(1)), Some(timestamp));
+        // Expired entries aren't written
+        assert_eq!(reopened.get(&key(2)), None);
+        drop(reopened);
+
+        // Dropping the store writes pending changes
+        store.set(key(3), timestamp);
+        drop(store);
+        let reopened = FileCooldownStore::open(&path).unwrap();
+        assert_eq!(reopened.get(&key(3)), Some(timestamp));
+        drop(reopened);
+
+        std::fs::remove_file(&path).unwrap();
     }
 }
@@ -179,12 +179,82 @@ async fn check_permissions_and_cooldown_single<'a, U, E>(
     }
 
     if !ctx.framework().options().manual_cooldowns {
-        let cooldowns = &cmd.cooldowns;
-        let remaining_cooldown = cooldowns.lock().unwrap().remaining_cooldown(ctx);
-        if let Some(remaining_cooldown) = remaining_cooldown {
+        let tripped_cooldown = crate::Cooldowns::effective_for(ctx, cmd)
+            .await
+            .and_then(|cooldowns| cooldowns.tripped_cooldown_for(ctx, cmd));
+        if let Some(tripped) = tripped_cooldown {
             return Err(crate::FrameworkError::CooldownHit {
                 ctx,
-                remaining_cooldown,
+                remaining_cooldown: tripped.remaining_cooldown,
+                bucket: tripped.bucket,
+                remaining_uses: tripped.remaining_uses,
+                window_reset: tripped.window_reset,
+            });
+        }
+    }
+
+    Ok(())
+}
+
+/// Applies the [`crate::GuildSettings`] of the invocation guild, if
+/// [`crate::FrameworkOptions::guild_settings`] is set. They're only fetched if they aren't passed
+/// in `guild_settings` already
+async fn check_guild_settings<'a, U, E>(
+    ctx: crate::Context<'a, U, E>,
+    guild_settings: Option<std::sync::Arc<crate::GuildSettings>>,
+) -> Result<(), crate::FrameworkError<'a, U, E>> {
+    let options = ctx.framework().options();
+    let (provider, guild_id) = match (&options.guild_settings, ctx.guild_id()) {
+        (Some(provider), Some(guild_id)) => (provider, guild_id),
+        _ => return Ok(()),
+    };
+    if options.skip_checks_for_owners && options.owners.contains(&ctx.author().id) {
+        return Ok(());
+    }
+
+    let settings = match guild_settings {
+        Some(settings) => Ok(settings),
+        None => provider.guild_settings(guild_id).await,
+    };
+    let settings = match settings {
+        Ok(settings) => settings,
+        Err(error) => {
+            return Err(crate::FrameworkError::GuildSettings {
+                error,
+                ctx: ctx.into(),
+            })
+        }
+    };
+
+    let command = ctx.command();
+    if settings.is_command_disabled(&command.qualified_name) {
+        return Err(crate::FrameworkError::GuildCommandDisabled { ctx });
+    }
+
+    let disabled_category = ctx
+        .parent_commands()
+        .iter()
+        .copied()
+        .chain(std::iter::once(command))
+        .filter_map(|command| command.category)
+        .find(|&category| settings.disabled_categories.contains(category));
+    if let Some(category) = disabled_category {
+        return Err(crate::FrameworkError::GuildCategoryDisabled { category, ctx });
+    }
+
+    if !settings.is_channel_allowed(ctx.channel_id()) {
+        return Err(crate::FrameworkError::ChannelNotAllowed { ctx });
+    }
+
+    if !settings.required_roles.is_empty() {
+        // If the member can't be retrieved, treat them as having no roles
+        let member = ctx.author_member().await;
+        let member_roles = member.as_ref().map_or(&[][..], |member| &member.roles[..]);
+        if let Some(roles) = settings.unmet_role_requirement(&command.qualified_name, member_roles)
+        {
+            return Err(crate::FrameworkError::MissingRequiredRole {
+                required_roles: roles.to_vec(),
+                ctx,
             });
         }
     }
@@ -201,6 +271,30 @@ async fn check_permissions_and_cooldown_single<'a, U, E>(
 pub async fn check_permissions_and_cooldown<'a, U, E>(
     ctx: crate::Context<'a, U, E>,
 ) -> Result<(), crate::FrameworkError<'a, U, E>> {
+    check_permissions_and_cooldown_inner(ctx, None).await
+}
+
+/// See [`check_permissions_and_cooldown`]. `guild_settings` are the already fetched settings of
+/// the invocation guild, if any
+#[allow(clippy::needless_lifetimes)] // false positive (clippy issue 7271)
+pub(super) async fn check_permissions_and_cooldown_inner<'a, U, E>(
+    ctx: crate::Context<'a, U, E>,
+    guild_settings: Option<std::sync::Arc<crate::GuildSettings>>,
+) -> Result<(), crate::FrameworkError<'a, U, E>> {
+    // Also covers disabled parent commands. Like other checks, skipped for owners if configured
+    let options = ctx.framework().options();
+    let skip_for_owner =
+        options.skip_checks_for_owners && options.owners.contains(&ctx.author().id);
+    if !skip_for_owner
+        && options
+            .command_registry
+            .is_disabled(&ctx.command().qualified_name, ctx.guild_id())
+    {
+        return Err(crate::FrameworkError::CommandDisabled { ctx });
+    }
+
+    check_guild_settings(ctx, guild_settings).await?;
+
     for parent_command in ctx.parent_commands() {
         check_permissions_and_cooldown_single(ctx, parent_command).await?;
     }
@@ -4,11 +4,14 @@ use crate::serenity_prelude as serenity;
 
 /// Checks if this message is a bot invocation by attempting to strip the prefix
 ///
-/// Returns tuple of stripped prefix and rest of the message, if any prefix matches
+/// Returns tuple of stripped prefix and rest of the message, if any prefix matches. The guild
+/// settings fetched to check the guild prefix are stored into `guild_settings`, so that they can
+/// be reused for the checks of the invoked command
 async fn strip_prefix<'a, U, E>(
     framework: crate::FrameworkContext<'a, U, E>,
     ctx: &'a serenity::Context,
     msg: &'a serenity::Message,
+    guild_settings: &mut Option<std::sync::Arc<crate::GuildSettings>>,
 ) -> Option<(&'a str, &'a str)> {
     let partial_ctx = crate::PartialContext {
         guild_id: msg.guild_id,
@@ -19,6 +22,30 @@ async fn strip_prefix<'a, U, E>(
         data: framework.user_data,
     };
 
+    if let (Some(provider), Some(guild_id)) = (&framework.options.guild_settings, msg.guild_id) {
+        match provider.guild_settings(guild_id).await {
+            Ok(settings) => {
+                let prefix_len = settings
+                    .prefix
+                    .as_deref()
+                    // An empty prefix would turn every message into a command invocation
+                    .filter(|prefix| !prefix.is_empty() && msg.content.starts_with(prefix))
+                    .map(str::len);
+                *guild_settings = Some(settings);
+                if let Some(prefix_len) = prefix_len {
+                    return Some(msg.content.split_at(prefix_len));
+                }
+            }
+            Err(error) => {
+                (framework.options.on_error)(crate::FrameworkError::GuildSettings {
+                    error,
+                    ctx: partial_ctx,
+                })
+                .await;
+            }
+        }
+    }
+
     if let Some(dynamic_prefix) = framework.options.prefix_options.dynamic_prefix {
         match dynamic_prefix(partial_ctx).await {
             Ok(prefix) => {
@@ -100,10 +127,71 @@ async fn strip_prefix<'a, U, E>(
     None
 }
 
+/// Determines the locale of the message author, see
+/// [`crate::PrefixFrameworkOptions::locale_resolver`]
+async fn resolve_locale<U, E>(
+    framework: crate::FrameworkContext<'_, U, E>,
+    ctx: &serenity::Context,
+    msg: &serenity::Message,
+) -> Option<&'static str> {
+    let locale = resolve_locale_string(framework, ctx, msg).await?;
+    let known_locale = crate::util::discord_locale(&locale);
+    if known_locale.is_none() {
+        log::warn!(
+            "ignoring locale `{}` which isn't supported by Discord",
+            locale
+        );
+    }
+    known_locale
+}
+
+/// See [`resolve_locale`]
+async fn resolve_locale_string<U, E>(
+    framework: crate::FrameworkContext<'_, U, E>,
+    ctx: &serenity::Context,
+    msg: &serenity::Message,
+) -> Option<String> {
+    if let Some(locale_resolver) = framework.options.prefix_options.locale_resolver {
+        let partial_ctx = crate::PartialContext {
+            guild_id: msg.guild_id,
+            channel_id: msg.channel_id,
+            author: &msg.author,
+            serenity_context: ctx,
+            framework,
+            data: framework.user_data,
+        };
+        match locale_resolver(partial_ctx).await {
+            Ok(Some(locale)) => return Some(locale),
+            Ok(None) => {}
+            Err(error) => {
+                (framework.options.on_error)(crate::FrameworkError::LocaleResolver {
+                    error,
+                    ctx: partial_ctx,
+                    msg,
+                })
+                .await;
+            }
+        }
+    }
+
+    #[cfg(feature = "cache")]
+    if let Some(guild_id) = msg.guild_id {
+        return ctx
+            .cache
+            .guild(guild_id)
+            .map(|guild| guild.preferred_locale.clone());
+    }
+
+    None
+}
+
 /// Find a command or subcommand within `&[Command]`, given a command invocation without a prefix.
 /// Returns the verbatim command name string as well as the command arguments (i.e. the remaining
 /// string).
 ///
+/// To respect commands added or removed at runtime, pass
+/// [`crate::CommandRegistry::commands`] instead of the plain command list.
+///
 /// The API must be like this (as opposed to just taking the command name upfront) because of
 /// subcommands.
 ///
@@ -137,7 +225,7 @@ async fn strip_prefix<'a, U, E>(
 /// );
 /// assert!(parent_commands.is_empty());
 pub fn find_command<'a, U, E>(
-    commands: &'a [crate::Command<U, E>],
+    commands: impl IntoIterator<Item = &'a crate::Command<U, E>>,
     remaining_message: &'a str,
     case_insensitive: bool,
     parent_commands: &mut Vec<&'a crate::Command<U, E>>,
@@ -193,17 +281,19 @@ pub async fn dispatch_message<'a, U: Send + Sync, E>(
     invocation_data: &'a tokio::sync::Mutex<Box<dyn std::any::Any + Send + Sync>>,
     parent_commands: &'a mut Vec<&'a crate::Command<U, E>>,
 ) -> Result<(), crate::FrameworkError<'a, U, E>> {
-    if let Some(ctx) = parse_invocation(
+    let mut guild_settings = None;
+    if let Some(ctx) = parse_invocation_inner(
         framework,
         ctx,
         msg,
         trigger,
         invocation_data,
         parent_commands,
+        &mut guild_settings,
     )
     .await?
     {
-        crate::catch_unwind_maybe(run_invocation(ctx))
+        crate::catch_unwind_maybe(run_invocation_inner(ctx, guild_settings))
             .await
             .map_err(|payload| crate::FrameworkError::CommandPanic {
                 payload,
@@ -222,6 +312,29 @@ pub async fn parse_invocation<'a, U: Send + Sync, E>(
     trigger: crate::MessageDispatchTrigger,
     invocation_data: &'a tokio::sync::Mutex<Box<dyn std::any::Any + Send + Sync>>,
     parent_commands: &'a mut Vec<&'a crate::Command<U, E>>,
+) -> Result<Option<crate::PrefixContext<'a, U, E>>, crate::FrameworkError<'a, U, E>> {
+    parse_invocation_inner(
+        framework,
+        ctx,
+        msg,
+        trigger,
+        invocation_data,
+        parent_commands,
+        &mut None,
+    )
+    .await
+}
+
+/// See [`parse_invocation`]. The guild settings fetched while parsing are stored into
+/// `guild_settings`
+async fn parse_invocation_inner<'a, U: Send + Sync, E>(
+    framework: crate::FrameworkContext<'a, U, E>,
+    ctx: &'a serenity::Context,
+    msg: &'a serenity::Message,
+    trigger: crate::MessageDispatchTrigger,
+    invocation_data: &'a tokio::sync::Mutex<Box<dyn std::any::Any + Send + Sync>>,
+    parent_commands: &'a mut Vec<&'a crate::Command<U, E>>,
+    guild_settings: &mut Option<std::sync::Arc<crate::GuildSettings>>,
 ) -> Result<Option<crate::PrefixContext<'a, U, E>>, crate::FrameworkError<'a, U, E>> {
     // Check if we're allowed to invoke from bot messages
     if msg.author.bot && framework.options.prefix_options.ignore_bots {
@@ -235,27 +348,49 @@ pub async fn parse_invocation<'a, U: Send + Sync, E>(
     }
 
     // Strip prefix, trim whitespace between prefix and rest, split rest into command name and args
-    let (prefix, msg_content) = match strip_prefix(framework, ctx, msg).await {
+    let (prefix, msg_content) = match strip_prefix(framework, ctx, msg, guild_settings).await {
         Some(x) => x,
         None => return Ok(None),
     };
     let msg_content = msg_content.trim_start();
 
-    let (command, invoked_command_name, args) = find_command(
-        &framework.options.commands,
+    let options = framework.options;
+    let case_insensitive = options.prefix_options.case_insensitive_commands;
+    let (command, invoked_command_name, args) = match find_command(
+        options.command_registry.commands(&options.commands),
         msg_content,
-        framework.options.prefix_options.case_insensitive_commands,
+        case_insensitive,
         parent_commands,
-    )
-    .ok_or(crate::FrameworkError::UnknownCommand {
-        ctx,
-        msg,
-        prefix,
-        msg_content,
-        framework,
-        invocation_data,
-        trigger,
-    })?;
+    ) {
+        Some(x) => x,
+        None => {
+            // The search over all commands and the locale are only needed if the suggestions
+            // are shown
+            let (suggestions, locale) = match options.prefix_options.suggest_unknown_commands {
+                true => (
+                    crate::builtins::suggest_commands(
+                        options.command_registry.commands(&options.commands),
+                        msg_content,
+                        case_insensitive,
+                        options.owners.contains(&msg.author.id),
+                    ),
+                    resolve_locale(framework, ctx, msg).await,
+                ),
+                false => (Vec::new(), None),
+            };
+            return Err(crate::FrameworkError::UnknownCommand {
+                ctx,
+                msg,
+                prefix,
+                msg_content,
+                framework,
+                invocation_data,
+                trigger,
+                suggestions,
+                locale,
+            });
+        }
+    };
 
     let action = match command.prefix_action {
         Some(x) => x,
@@ -263,6 +398,8 @@ pub async fn parse_invocation<'a, U: Send + Sync, E>(
         None => return Ok(None),
     };
 
+    let locale = resolve_locale(framework, ctx, msg).await;
+
     Ok(Some(crate::PrefixContext {
         serenity_context: ctx,
         msg,
@@ -274,6 +411,7 @@ pub async fn parse_invocation<'a, U: Send + Sync, E>(
         parent_commands,
         command,
         invocation_data,
+        locale,
         trigger,
         action,
         __non_exhaustive: (),
@@ -284,6 +422,15 @@ pub async fn parse_invocation<'a, U: Send + Sync, E>(
 /// before and after code like checks and built in filters from edit tracking
 pub async fn run_invocation<U, E>(
     ctx: crate::PrefixContext<'_, U, E>,
+) -> Result<(), crate::FrameworkError<'_, U, E>> {
+    run_invocation_inner(ctx, None).await
+}
+
+/// See [`run_invocation`]. `guild_settings` are the settings of the invocation guild if they were
+/// already fetched during parsing, so that they aren't fetched again for the checks
+async fn run_invocation_inner<U, E>(
+    ctx: crate::PrefixContext<'_, U, E>,
+    guild_settings: Option<std::sync::Arc<crate::GuildSettings>>,
 ) -> Result<(), crate::FrameworkError<'_, U, E>> {
     // Check if we should disregard this invocation if it was triggered by an edit
     if ctx.trigger == crate::MessageDispatchTrigger::MessageEdit && !ctx.command.invoke_on_edit {
@@ -303,7 +450,7 @@ pub async fn run_invocation<U, E>(
         });
     }
 
-    super::common::check_permissions_and_cooldown(ctx.into()).await?;
+    super::common::check_permissions_and_cooldown_inner(ctx.into(), guild_settings).await?;
 
     // Typing is broadcasted as long as this object is alive
     let _typing_broadcaster = if ctx.command.broadcast_typing {
@@ -332,3 +479,62 @@ pub async fn run_invocation<U, E>(
 
     Ok(())
 }
+
+#[cfg(test)]
+mod test {
+    /// How often [`resolve_de`] was called
+    static RESOLVES: std::sync::atomic::AtomicUsize = std::sync::atomic::AtomicUsize::new(0);
+
+    fn resolve_de(
+        _: crate::PartialContext<'_, (), ()>,
+    ) -> crate::BoxFuture<'_, Result<Option<String>, ()>> {
+        Box::pin(async {
+            RESOLVES.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            Ok(Some("de".into()))
+        })
+    }
+
+    fn reply_locale(
+        ctx: crate::PrefixContext<'_, (), ()>,
+    ) -> crate::BoxFuture<'_, Result<(), crate::FrameworkError<'_, (), ()>>> {
+        Box::pin(async move {
+            let _ = crate::say_reply(ctx.into(), ctx.locale.unwrap_or("none")).await;
+            Ok(())
+        })
+    }
+
+    #[tokio::test]
+    async fn test_locale_resolver() {
+        let harness = crate::testing::TestHarness::with_prefix_commands(
+            vec![crate::Command {
+                name: "locale".into(),
+                prefix_action: Some(reply_locale),
+                ..Default::default()
+            }],
+            crate::FrameworkOptions {
+                prefix_options: crate::PrefixFrameworkOptions {
+                    locale_resolver: Some(resolve_de),
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            (),
+        )
+        .await;
+
+        // Unknown commands and messages without prefix don't hit the resolver
+        harness.dispatch_message(harness.message("~unknown")).await;
+        harness.dispatch_message(harness.message("locale")).await;
+        assert_eq!(RESOLVES.load(std::sync::atomic::Ordering::SeqCst), 0);
+
+        harness.dispatch_message(harness.message("~locale")).await;
+        harness.dispatch_message(harness.message("~locale")).await;
+        assert_eq!(RESOLVES.load(std::sync::atomic::Ordering::SeqCst), 2);
+        let replies = harness
+            .replies()
+            .into_iter()
+            .map(|reply| reply.content.unwrap_or_default())
+            .collect::<Vec<_>>();
+        assert_eq!(replies, ["de", "de"]);
+    }
+}
@@ -6,10 +6,10 @@ use crate::serenity_prelude as serenity;
 fn find_matching_command<'a, 'b, U, E>(
     interaction_name: &str,
     interaction_options: &'b [serenity::ResolvedOption<'b>],
-    commands: &'a [crate::Command<U, E>],
+    commands: impl IntoIterator<Item = &'a crate::Command<U, E>>,
     parent_commands: &mut Vec<&'a crate::Command<U, E>>,
 ) -> Option<(&'a crate::Command<U, E>, &'b [serenity::ResolvedOption<'b>])> {
-    commands.iter().find_map(|cmd| {
+    commands.into_iter().find_map(|cmd| {
         if interaction_name != cmd.name && Some(interaction_name) != cmd.context_menu_name {
             return None;
         }
@@ -52,7 +52,10 @@ fn extract_command<'a, U, E>(
     let search_result = find_matching_command(
         &interaction.data().name,
         options,
-        &framework.options.commands,
+        framework
+            .options
+            .command_registry
+            .commands(&framework.options.commands),
         parent_commands,
     );
     let (command, leaf_interaction_options) =
@@ -174,7 +177,7 @@ pub async fn dispatch_interaction<'a, U, E>(
     let ctx = extract_command(
         framework,
         ctx,
-        crate::CommandOrAutocompleteInteraction::Autocomplete(interaction),
+        crate::CommandOrAutocompleteInteraction::Command(interaction),
         has_sent_initial_response,
         invocation_data,
         options,
@@ -11,6 +11,7 @@ mod builder;
 /// Technically, this is just an optional abstraction over [`crate::dispatch_event`] with some
 /// additional conveniences built-in:
 /// - fills in correct values for [`crate::Command::qualified_name`]: [`set_qualified_names`]
+/// - applies [`crate::FrameworkOptions::translations`] to the commands (`fluent` feature)
 /// - spawns a background task to periodically clear edit tracker cache
 /// - sets up user data on the first Ready event
 /// - keeps track of shard manager and bot ID automatically
@@ -44,6 +45,8 @@ pub struct Framework<U, E> {
 
     /// Handle to the background task in order to `abort()` it on `Drop`
     edit_tracker_purge_task: once_cell::sync::OnceCell<tokio::task::JoinHandle<()>>,
+    /// Handle to the background task in order to `abort()` it on `Drop`
+    response_registry_purge_task: once_cell::sync::OnceCell<tokio::task::JoinHandle<()>>,
 }
 
 impl<U, E> Framework<U, E> {
@@ -91,6 +94,7 @@ impl<U, E> Framework<U, E> {
             options,
             shard_manager: once_cell::sync::OnceCell::new(),
             edit_tracker_purge_task: once_cell::sync::OnceCell::new(),
+            response_registry_purge_task: once_cell::sync::OnceCell::new(),
         }
     }
 
@@ -134,6 +138,13 @@ async fn block_until_set<D>(cell: &once_cell::sync::OnceCell<D>) -> &D {
 #[async_trait::async_trait]
 impl<U: Send + Sync, E: Send> serenity::Framework for Framework<U, E> {
     async fn init(&mut self, client: &serenity::Client) {
+        // Must happen first because it changes command names
+        #[cfg(feature = "fluent")]
+        if let Some(translations) = &self.options.translations {
+            for missing in translations.apply(&mut self.options.commands) {
+                log::warn!("{}", missing);
+            }
+        }
         set_qualified_names(&mut self.options.commands);
 
         message_content_intent_sanity_check(
@@ -154,6 +165,13 @@ impl<U: Send + Sync, E: Send> serenity::Framework for Framework<U, E> {
                 .edit_tracker_purge_task
                 .set(spawn_edit_tracker_purge_task(edit_tracker.clone()));
         }
+        if let Some(response_registry) = &self.options.response_registry {
+            let _ = self
+                .response_registry_purge_task
+                .set(spawn_response_registry_purge_task(
+                    response_registry.clone(),
+                ));
+        }
     }
 
     async fn dispatch(&self, event: serenity::FullEvent) {
@@ -167,6 +185,9 @@ impl<U, E> Drop for Framework<U, E> {
         if let Some(task) = self.edit_tracker_purge_task.get() {
             task.abort();
         }
+        if let Some(task) = self.response_registry_purge_task.get() {
+            task.abort();
+        }
     }
 }
 
@@ -289,3 +310,17 @@ fn spawn_edit_tracker_purge_task(
         }
     })
 }
+
+/// Spawns a background task that periodically purges expired responses from the response registry
+///
+/// Not pub for the same reasons as [`spawn_edit_tracker_purge_task`]
+fn spawn_response_registry_purge_task(
+    response_registry: std::sync::Arc<crate::ResponseRegistry>,
+) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        loop {
+            response_registry.purge();
+            tokio::time::sleep(std::time::Duration::from_secs(60)).await;
+        }
+    })
+}
@@ -0,0 +1,324 @@
+//! Per-guild command configuration, queried by the framework during dispatch
+
+use crate::serenity_prelude as serenity;
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// Command configuration of a single guild, supplied by a [`GuildSettingsProvider`]
+///
+/// Commands are referred to by their qualified name (e.g. `config set`). Settings for a command
+/// also apply to all its subcommands.
+#[derive(Clone, Default, Debug)]
+pub struct GuildSettings {
+    /// Prefix for prefix commands in this guild. Recognized in addition to the prefixes in
+    /// [`crate::PrefixFrameworkOptions`]
+    pub prefix: Option<String>,
+    /// Qualified names of the commands that can't be used in this guild
+    pub disabled_commands: HashSet<String>,
+    /// Categories (see [`crate::Command::category`]) whose commands can't be used in this guild
+    pub disabled_categories: HashSet<String>,
+    /// If not empty, commands can only be used in these channels
+    ///
+    /// Threads are matched by their own channel ID, not their parent channel's.
+    pub allowed_channels: HashSet<serenity::ChannelId>,
+    /// Channels in which commands can't be used. Takes precedence over [`Self::allowed_channels`]
+    pub denied_channels: HashSet<serenity::ChannelId>,
+    /// Qualified command names mapped to roles of which the invoker needs at least one
+    pub required_roles: HashMap<String, Vec<serenity::RoleId>>,
+    #[doc(hidden)]
+    pub __non_exhaustive: (),
+}
+
+impl GuildSettings {
+    /// Whether the command with the given qualified name, or any of its parent commands, is in
+    /// [`Self::disabled_commands`]
+    pub fn is_command_disabled(&self, qualified_name: &str) -> bool {
+        crate::util::qualified_name_prefixes(qualified_name)
+            .any(|name| self.disabled_commands.contains(name))
+    }
+
+    /// Whether commands may be used in the given channel according to
+    /// [`Self::allowed_channels`] and [`Self::denied_channels`]
+    pub fn is_channel_allowed(&self, channel_id: serenity::ChannelId) -> bool {
+        !self.denied_channels.contains(&channel_id)
+            && (self.allowed_channels.is_empty() || self.allowed_channels.contains(&channel_id))
+    }
+
+    /// Returns the first role requirement from [`Self::required_roles`] for the given command or
+    /// its parent commands that isn't met by the given member roles
+    pub fn unmet_role_requirement(
+        &self,
+        qualified_name: &str,
+        member_roles: &[serenity::RoleId],
+    ) -> Option<&[serenity::RoleId]> {
+        crate::util::qualified_name_prefixes(qualified_name)
+            .filter_map(|name| self.required_roles.get(name))
+            .find(|roles| !roles.iter().any(|role| member_roles.contains(role)))
+            .map(|roles| &**roles)
+    }
+}
+
+/// Source of [`GuildSettings`], see [`crate::FrameworkOptions::guild_settings`]
+///
+/// The settings are requested once for every guild message (to check the guild prefix, and then
+/// to apply them to the invoked prefix command) and for every guild application command
+/// invocation. Wrap your provider in [`CachedGuildSettings`] if fetching them is
+/// expensive, for example when they're stored in a database.
+///
+/// ```rust
+/// struct DatabaseSettings;
+///
+/// #[poise::async_trait]
+/// impl poise::GuildSettingsProvider for DatabaseSettings {
+///     async fn guild_settings(
+///         &self,
+///         guild_id: serenity::GuildId,
+///     ) -> Result<std::sync::Arc<poise::GuildSettings>, Box<dyn std::error::Error + Send + Sync>>
+///     {
+///         let mut settings = poise::GuildSettings::default();
+///         settings.prefix = Some("?".into());
+///         settings.disabled_categories.insert("Fun".into());
+///         Ok(std::sync::Arc::new(settings))
+///     }
+/// }
+/// # use poise::serenity_prelude as serenity;
+/// ```
+#[async_trait::async_trait]
+pub trait GuildSettingsProvider: Send + Sync {
+    /// Returns the settings of the given guild
+    ///
+    /// If this fails, the invocation is aborted with [`crate::FrameworkError::GuildSettings`].
+    async fn guild_settings(
+        &self,
+        guild_id: serenity::GuildId,
+    ) -> Result<Arc<GuildSettings>, Box<dyn std::error::Error + Send + Sync>>;
+}
+
+/// [`GuildSettingsProvider`] which caches the settings returned by another provider
+///
+/// Keep a clone of the [`Arc`] you put into [`crate::FrameworkOptions::guild_settings`] to call
+/// [`Self::invalidate`] after a guild's settings changed.
+///
+/// ```rust,no_run
+/// # struct DatabaseSettings;
+/// # #[poise::async_trait]
+/// # impl poise::GuildSettingsProvider for DatabaseSettings {
+/// #     async fn guild_settings(&self, _: serenity::GuildId)
+/// #         -> Result<Arc<poise::GuildSettings>, Box<dyn std::error::Error + Send + Sync>>
+/// #     { todo!() }
+/// # }
+/// # use poise::serenity_prelude as serenity;
+/// use std::sync::Arc;
+///
+/// let settings = Arc::new(
+///     poise::CachedGuildSettings::new(DatabaseSettings)
+///         .with_ttl(std::time::Duration::from_secs(600)),
+/// );
+/// let options = poise::FrameworkOptions::<(), ()> {
+///     guild_settings: Some(settings.clone()),
+///     ..Default::default()
+/// };
+///
+/// // Later, after updating the settings of a guild in the database:
+/// # let guild_id = serenity::GuildId::new(1);
+/// settings.invalidate(guild_id);
+/// ```
+#[derive(Debug)]
+pub struct CachedGuildSettings<P> {
+    /// Provider which is queried on cache misses
+    provider: P,
+    /// How long cached settings are used before they're fetched again
+    ttl: Option<Duration>,
+    /// Cached settings and the time they were fetched
+    entries: Mutex<HashMap<serenity::GuildId, (Instant, Arc<GuildSettings>)>>,
+}
+
+impl<P> CachedGuildSettings<P> {
+    /// Wraps the given provider. Cached settings don't expire until invalidated
+    pub fn new(provider: P) -> Self {
+        Self {
+            provider,
+            ttl: None,
+            entries: Mutex::default(),
+        }
+    }
+
+    /// Makes cached settings expire after the given duration
+    pub fn with_ttl(mut self, ttl: Duration) -> Self {
+        self.ttl = Some(ttl);
+        self
+    }
+
+    /// Returns the wrapped provider
+    pub fn provider(&self) -> &P {
+        &self.provider
+    }
+
+    /// Removes the cached settings of the given guild, so that they're fetched again on next use
+    pub fn invalidate(&self, guild_id: serenity::GuildId) {
+        self.entries.lock().unwrap().remove(&guild_id);
+    }
+
+    /// Removes the cached settings of all guilds
+    pub fn invalidate_all(&self) {
+        self.entries.lock().unwrap().clear();
+    }
+}
+
+#[async_trait::async_trait]
+impl<P: GuildSettingsProvider> GuildSettingsProvider for CachedGuildSettings<P> {
+    async fn guild_settings(
+        &self,
+        guild_id: serenity::GuildId,
+    ) -> Result<Arc<GuildSettings>, Box<dyn std::error::Error + Send + Sync>> {
+        if let Some((fetched_at, settings)) = self.entries.lock().unwrap().get(&guild_id) {
+            if self.ttl.map_or(true, |ttl| fetched_at.elapsed() < ttl) {
+                return Ok(settings.clone());
+            }
+        }
+
+        // Errors aren't cached, so the next invocation tries again
+        let settings = self.provider.guild_settings(guild_id).await?;
+        self.entries
+            .lock()
+            .unwrap()
+            .insert(guild_id, (Instant::now(), settings.clone()));
+        Ok(settings)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_guild_settings() {
+        let (mods, admins) = (serenity::RoleId::new(1), serenity::RoleId::new(2));
+        let mut settings = GuildSettings::default();
+        settings.disabled_commands.insert("config set".into());
+        settings
+            .required_roles
+            .insert("config".into(), vec![mods, admins]);
+        settings
+            .required_roles
+            .insert("config reset".into(), vec![admins]);
+
+        assert!(settings.is_command_disabled("config set value"));
+        assert!(!settings.is_command_disabled("config"));
+
+        assert_eq!(settings.unmet_role_requirement("config get", &[mods]), None);
+        assert_eq!(
+            settings.unmet_role_requirement("config reset", &[mods]),
+            Some(&[admins][..])
+        );
+        assert_eq!(
+            settings.unmet_role_requirement("config", &[]),
+            Some(&[mods, admins][..])
+        );
+
+        let (general, spam) = (serenity::ChannelId::new(1), serenity::ChannelId::new(2));
+        assert!(settings.is_channel_allowed(general));
+        settings.allowed_channels.insert(general);
+        assert!(settings.is_channel_allowed(general));
+        assert!(!settings.is_channel_allowed(spam));
+        settings.denied_channels.insert(general);
+        assert!(!settings.is_channel_allowed(general));
+    }
+
+    /// Provider which returns the same settings for every guild and counts how often it's queried
+    struct CountingProvider {
+        settings: Arc<GuildSettings>,
+        fetches: Arc<std::sync::atomic::AtomicUsize>,
+    }
+
+    #[async_trait::async_trait]
+    impl GuildSettingsProvider for CountingProvider {
+        async fn guild_settings(
+            &self,
+            _: serenity::GuildId,
+        ) -> Result<Arc<GuildSettings>, Box<dyn std::error::Error + Send + Sync>> {
+            self.fetches
+                .fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            Ok(self.settings.clone())
+        }
+    }
+
+    async fn harness(
+        settings: GuildSettings,
+    ) -> (
+        crate::testing::TestHarness<(), ()>,
+        Arc<std::sync::atomic::AtomicUsize>,
+    ) {
+        let fetches = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let command = |name: &str| crate::Command {
+            name: name.into(),
+            prefix_action: Some(crate::testing::pong),
+            ..Default::default()
+        };
+        let harness = crate::testing::TestHarness::with_prefix_commands(
+            vec![command("ping"), command("secret")],
+            crate::FrameworkOptions {
+                guild_settings: Some(Arc::new(CountingProvider {
+                    settings: Arc::new(settings),
+                    fetches: fetches.clone(),
+                })),
+                ..Default::default()
+            },
+            (),
+        )
+        .await;
+        (harness, fetches)
+    }
+
+    /// Creates a message with the given content in a guild
+    fn guild_message(
+        harness: &crate::testing::TestHarness<(), ()>,
+        content: &str,
+    ) -> serenity::Message {
+        let mut msg = harness.message(content);
+        msg.guild_id = Some(serenity::GuildId::new(1));
+        msg
+    }
+
+    #[tokio::test]
+    async fn test_dispatch_guild_settings() {
+        let mut settings = GuildSettings::default();
+        settings.prefix = Some("?".into());
+        settings.disabled_commands.insert("secret".into());
+        let (harness, fetches) = harness(settings).await;
+        let replies = || {
+            let replies = harness.replies();
+            harness.clear();
+            replies
+                .into_iter()
+                .map(|reply| reply.content.unwrap_or_default())
+                .collect::<Vec<_>>()
+        };
+
+        // The settings fetched for the guild prefix are reused for the checks
+        harness
+            .dispatch_message(guild_message(&harness, "?ping"))
+            .await;
+        assert_eq!(replies(), ["Pong!"]);
+        assert_eq!(fetches.load(std::sync::atomic::Ordering::SeqCst), 1);
+
+        harness
+            .dispatch_message(guild_message(&harness, "?secret"))
+            .await;
+        assert_eq!(replies(), ["This command is disabled in this server."]);
+        assert_eq!(fetches.load(std::sync::atomic::Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_empty_guild_prefix() {
+        let mut settings = GuildSettings::default();
+        settings.prefix = Some("".into());
+        let (harness, _) = harness(settings).await;
+
+        harness
+            .dispatch_message(guild_message(&harness, "ping"))
+            .await;
+        assert!(harness.replies().is_empty());
+    }
+}
@@ -374,6 +374,10 @@ fn test_calc() {
 }
 ```
 
+If you want to test a command end-to-end, including argument parsing, checks and error handling,
+enable the `testing` feature and use `testing::TestHarness`. It dispatches synthetic messages and interactions onto your commands
+without a Discord connection and records all replies instead of sending them.
+
 # About the weird name
 I'm bad at names. Google lists "poise" as a synonym to "serenity" which is the Discord library
 underlying this framework, so that's what I chose.
@@ -381,17 +385,28 @@ underlying this framework, so that's what I chose.
 Also, poise is a stat in Dark Souls
 */
 
+pub mod argument_types;
+pub mod attachment;
 pub mod builtins;
 pub mod cooldown;
 pub mod dispatch;
 pub mod framework;
+pub mod guild_settings;
+#[cfg(feature = "fluent")]
+pub mod localization;
+pub mod messages;
 pub mod modal;
+pub mod parameter_group;
 pub mod prefix_argument;
+pub mod registry;
 pub mod reply;
 pub mod slash_argument;
 pub mod structs;
+#[cfg(any(test, feature = "testing"))]
+pub mod testing;
 pub mod track_edits;
 mod util;
+pub mod validation;
 pub mod macros {
     //! Procedural macros used in poise, like [`command`]
     #[doc(inline)]
@@ -400,8 +415,9 @@ pub mod macros {
 
 #[doc(no_inline)]
 pub use {
-    cooldown::*, dispatch::*, framework::*, macros::*, modal::*, prefix_argument::*, reply::*,
-    slash_argument::*, structs::*, track_edits::*,
+    argument_types::*, attachment::*, cooldown::*, dispatch::*, framework::*, guild_settings::*,
+    macros::*, messages::*, modal::*, parameter_group::*, prefix_argument::*, registry::*,
+    reply::*, slash_argument::*, structs::*, track_edits::*, validation::*,
 };
 
 /// See [`builtins`]
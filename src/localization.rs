@@ -0,0 +1,431 @@
+//! Localization of commands and responses with [Fluent](https://projectfluent.org/) translation
+//! files. Requires the `fluent` feature
+//!
+//! Every locale has one `.ftl` file, named like the locale (`en-US.ftl`, `de.ftl`...). Commands,
+//! their parameters and choices are localized with these messages:
+//!
+//! ```ftl
+//! # Command name, with the description and parameters as attributes. Subcommands are named
+//! # like `parent-subcommand`
+//! welcome = welcome
+//!     .description = Welcomes a user
+//!     .user = user
+//!     .user-description = The user to welcome
+//!
+//! # Choices of choice parameters are named like the choice
+//! Coffee = Hey, do you want a coffee?
+//! ```
+//!
+//! Set [`crate::FrameworkOptions::translations`] to apply the translation files to all commands on
+//! startup, and retrieve other messages with [`crate::Context::tr`] or [`crate::tr!`].
+
+use std::collections::HashMap;
+
+pub use fluent::{FluentArgs, FluentValue};
+
+/// A thread-safe Fluent bundle of a single locale
+type FluentBundle = fluent::bundle::FluentBundle<
+    fluent::FluentResource,
+    intl_memoizer::concurrent::IntlLangMemoizer,
+>;
+
+/// Error while loading translation files, see [`Translations::load`]
+#[derive(Debug)]
+pub enum TranslationsError {
+    /// A translation file or the translations directory couldn't be read
+    Io {
+        /// Path of the file or directory
+        path: std::path::PathBuf,
+        /// Underlying error
+        error: std::io::Error,
+    },
+    /// A file name isn't a valid locale
+    InvalidLocale(String),
+    /// A translation file contains syntax errors or redefines a message
+    Invalid {
+        /// Locale of the translation file
+        locale: String,
+        /// Human readable description of the errors
+        description: String,
+    },
+    /// There's no translation file for the main locale
+    MissingMainLocale(String),
+}
+
+impl std::fmt::Display for TranslationsError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Io { path, error } => write!(f, "failed to read {}: {}", path.display(), error),
+            Self::InvalidLocale(locale) => write!(f, "invalid locale `{}`", locale),
+            Self::Invalid {
+                locale,
+                description,
+            } => write!(f, "invalid translations for {}: {}", locale, description),
+            Self::MissingMainLocale(locale) => {
+                write!(f, "no translations for the main locale {}", locale)
+            }
+        }
+    }
+}
+
+impl std::error::Error for TranslationsError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io { error, .. } => Some(error),
+            _ => None,
+        }
+    }
+}
+
+/// A message or message attribute which [`Translations::apply`] didn't find
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct MissingTranslation {
+    /// Locale whose translation file lacks the message
+    pub locale: String,
+    /// Message identifier, followed by the attribute like `welcome.description` if the message
+    /// exists but the attribute doesn't
+    pub key: String,
+}
+
+impl std::fmt::Display for MissingTranslation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "missing translation `{}` for {}", self.key, self.locale)
+    }
+}
+
+/// Translation files of all locales
+pub struct Translations {
+    /// Locale which is used when the user's locale has no translation of a message
+    main_locale: String,
+    /// Translations of each locale, including the main locale
+    bundles: HashMap<String, FluentBundle>,
+}
+
+impl Translations {
+    /// Loads every `.ftl` file in the given directory, named like the locale (`en-US.ftl`). Other
+    /// files are ignored
+    ///
+    /// The main locale is used for the default names and descriptions of commands, and as fallback
+    /// for messages which are missing in the user's locale.
+    pub fn load(
+        dir: impl AsRef<std::path::Path>,
+        main_locale: &str,
+    ) -> Result<Self, TranslationsError> {
+        let dir = dir.as_ref();
+        let io_error = |path: &std::path::Path| {
+            let path = path.to_owned();
+            move |error| TranslationsError::Io { path, error }
+        };
+
+        let mut sources = Vec::new();
+        for entry in std::fs::read_dir(dir).map_err(io_error(dir))? {
+            let path = entry.map_err(io_error(dir))?.path();
+            if path.extension().map_or(true, |x| x != "ftl") {
+                continue;
+            }
+            let locale = path
+                .file_stem()
+                .and_then(|x| x.to_str())
+                .ok_or_else(|| TranslationsError::InvalidLocale(path.display().to_string()))?
+                .to_owned();
+            let source = std::fs::read_to_string(&path).map_err(io_error(&path))?;
+            sources.push((locale, source));
+        }
+        Self::from_sources(main_locale, sources)
+    }
+
+    /// Parses translations which are already in memory, for example embedded with
+    /// `include_str!`. Takes pairs of locale and Fluent source
+    pub fn from_sources(
+        main_locale: &str,
+        sources: impl IntoIterator<Item = (String, String)>,
+    ) -> Result<Self, TranslationsError> {
+        let mut bundles = HashMap::new();
+        for (locale, source) in sources {
+            let language = locale
+                .parse()
+                .map_err(|_| TranslationsError::InvalidLocale(locale.clone()))?;
+            let resource = fluent::FluentResource::try_new(source).map_err(|(_, errors)| {
+                TranslationsError::Invalid {
+                    locale: locale.clone(),
+                    description: format!("{:?}", errors),
+                }
+            })?;
+
+            let mut bundle = FluentBundle::new_concurrent(vec![language]);
+            bundle
+                .add_resource(resource)
+                .map_err(|errors| TranslationsError::Invalid {
+                    locale: locale.clone(),
+                    description: format!("{:?}", errors),
+                })?;
+            bundles.insert(locale, bundle);
+        }
+
+        if !bundles.contains_key(main_locale) {
+            return Err(TranslationsError::MissingMainLocale(main_locale.to_owned()));
+        }
+        Ok(Self {
+            main_locale: main_locale.to_owned(),
+            bundles,
+        })
+    }
+
+    /// The locale used for default names and descriptions and as fallback
+    pub fn main_locale(&self) -> &str {
+        &self.main_locale
+    }
+
+    /// Formats the given message or message attribute in the given locale, falling back to the
+    /// main locale if the locale is unknown or lacks the message
+    pub fn format(
+        &self,
+        locale: Option<&str>,
+        id: &str,
+        attribute: Option<&str>,
+        args: Option<&FluentArgs<'_>>,
+    ) -> Option<String> {
+        locale
+            .and_then(|locale| self.format_in(locale, id, attribute, args))
+            .or_else(|| self.format_in(&self.main_locale, id, attribute, args))
+    }
+
+    /// Formats the given message or message attribute in exactly the given locale
+    fn format_in(
+        &self,
+        locale: &str,
+        id: &str,
+        attribute: Option<&str>,
+        args: Option<&FluentArgs<'_>>,
+    ) -> Option<String> {
+        let bundle = self.bundles.get(locale)?;
+        let message = bundle.get_message(id)?;
+        let pattern = match attribute {
+            Some(attribute) => message.get_attribute(attribute)?.value(),
+            None => message.value()?,
+        };
+        let formatted = bundle.format_pattern(pattern, args, &mut vec![]);
+        Some(formatted.into_owned())
+    }
+
+    /// Fills in the names, descriptions and choices of the given commands and their subcommands
+    /// from the translation files. The main locale sets the default values, other locales add
+    /// localizations
+    ///
+    /// Parameter names of the main locale aren't applied, because slash command options are
+    /// looked up by their original name.
+    ///
+    /// Returns the messages and attributes which are missing, so they can be reported. A command
+    /// without message in a locale isn't localized in that locale.
+    pub fn apply<U, E>(&self, commands: &mut [crate::Command<U, E>]) -> Vec<MissingTranslation> {
+        let mut missing = Vec::new();
+        for command in commands {
+            self.apply_to_command(command, None, &mut missing);
+        }
+        missing
+    }
+
+    /// Applies the translations to a single command and its subcommands, see [`Self::apply`]
+    fn apply_to_command<U, E>(
+        &self,
+        command: &mut crate::Command<U, E>,
+        parent_id: Option<&str>,
+        missing: &mut Vec<MissingTranslation>,
+    ) {
+        let id = match parent_id {
+            Some(parent_id) => format!("{}-{}", parent_id, command.name),
+            None => command.name.clone(),
+        };
+        let mut lookup = |locale: &str, attribute: Option<&str>| {
+            let translation = self.format_in(locale, &id, attribute, None);
+            if translation.is_none() {
+                let key = match attribute {
+                    Some(attribute) => format!("{}.{}", id, attribute),
+                    None => id.clone(),
+                };
+                missing.push(MissingTranslation {
+                    locale: locale.to_owned(),
+                    key,
+                });
+            }
+            translation
+        };
+
+        // Sorted for deterministic order of reported missing translations
+        let mut locales = self.bundles.keys().collect::<Vec<_>>();
+        locales.sort();
+        let mut main_translations = None;
+        for locale in locales {
+            let is_main = *locale == self.main_locale;
+            let name = match lookup(locale, None) {
+                Some(x) => x,
+                None => continue,
+            };
+            let description = lookup(locale, Some("description"));
+            let parameters = command
+                .parameters
+                .iter()
+                .map(|parameter| {
+                    let name = match is_main {
+                        true => None,
+                        false => lookup(locale, Some(&parameter.name)),
+                    };
+                    let description_id = format!("{}-description", parameter.name);
+                    (name, lookup(locale, Some(&description_id)))
+                })
+                .collect::<Vec<_>>();
+
+            if is_main {
+                main_translations = Some((name, description, parameters));
+                continue;
+            }
+            command.name_localizations.insert(locale.clone(), name);
+            if let Some(description) = description {
+                command
+                    .description_localizations
+                    .insert(locale.clone(), description);
+            }
+            for (parameter, (name, description)) in command.parameters.iter_mut().zip(parameters) {
+                if let Some(name) = name {
+                    parameter.name_localizations.insert(locale.clone(), name);
+                }
+                if let Some(description) = description {
+                    parameter
+                        .description_localizations
+                        .insert(locale.clone(), description);
+                }
+            }
+        }
+
+        for parameter in &mut command.parameters {
+            for choice in &mut parameter.choices {
+                for locale in self.bundles.keys() {
+                    let translation = match self.format_in(locale, &choice.name, None, None) {
+                        Some(x) => x,
+                        None => {
+                            missing.push(MissingTranslation {
+                                locale: locale.clone(),
+                                key: choice.name.clone(),
+                            });
+                            continue;
+                        }
+                    };
+                    if *locale != self.main_locale {
+                        choice.localizations.insert(locale.clone(), translation);
+                    }
+                }
+                if let Some(name) = self.format_in(&self.main_locale, &choice.name, None, None) {
+                    choice.name = name;
+                }
+            }
+        }
+
+        // The main locale is applied last, because the other locales are looked up by the
+        // original names
+        if let Some((name, description, parameters)) = main_translations {
+            command.name = name;
+            if description.is_some() {
+                command.description = description;
+            }
+            for (parameter, (_, description)) in command.parameters.iter_mut().zip(parameters) {
+                if description.is_some() {
+                    parameter.description = description;
+                }
+            }
+        }
+
+        for subcommand in &mut command.subcommands {
+            self.apply_to_command(subcommand, Some(&id), missing);
+        }
+    }
+}
+
+/// Retrieves a translated message in the locale of the invoking user, optionally with arguments.
+/// Use like:
+/// - `poise::tr!(ctx, "identifier")` (no arguments)
+/// - `poise::tr!(ctx, "identifier", arg1: VALUE1, arg2: VALUE2)` (with arguments)
+///
+/// See [`crate::Context::tr`]. Requires the `fluent` feature
+#[macro_export]
+macro_rules! tr {
+    ( $ctx:expr, $id:expr $(, $argname:ident: $argvalue:expr )* $(,)? ) => {{
+        #[allow(unused_mut)]
+        let mut args = $crate::localization::FluentArgs::new();
+        $( args.set(stringify!($argname), $argvalue); )*
+
+        $ctx.tr($id, Some(&args))
+    }};
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_apply() {
+        let translations = Translations::from_sources(
+            "en-US",
+            vec![
+                (
+                    "en-US".into(),
+                    "greet = greet\n    .description = Greets a user\n    .user-description = \
+                     The user\nCoffee = Coffee\n"
+                        .into(),
+                ),
+                (
+                    "de".into(),
+                    "greet = begrüßen\n    .user = nutzer\n    .user-description = Der Nutzer\n"
+                        .into(),
+                ),
+            ],
+        )
+        .unwrap();
+        assert_eq!(
+            translations
+                .format(Some("de"), "greet", None, None)
+                .as_deref(),
+            Some("begrüßen")
+        );
+        assert_eq!(
+            translations
+                .format(Some("fr"), "greet", Some("description"), None)
+                .as_deref(),
+            Some("Greets a user")
+        );
+
+        let mut commands = vec![crate::Command::<(), ()> {
+            name: "greet".into(),
+            parameters: vec![crate::CommandParameter {
+                name: "user".into(),
+                name_localizations: Default::default(),
+                description: None,
+                description_localizations: Default::default(),
+                required: true,
+                details: crate::CommandParameterDetails {
+                    type_name: "User",
+                    ..Default::default()
+                },
+                channel_types: None,
+                choices: vec![],
+                type_setter: None,
+                autocomplete_callback: None,
+            }],
+            ..Default::default()
+        }];
+        let missing = translations.apply(&mut commands);
+        assert_eq!(
+            missing,
+            vec![MissingTranslation {
+                locale: "de".into(),
+                key: "greet.description".into(),
+            }]
+        );
+
+        let parameter = &commands[0].parameters[0];
+        assert_eq!(commands[0].name_localizations["de"], "begrüßen");
+        assert_eq!(commands[0].description.as_deref(), Some("Greets a user"));
+        assert_eq!(parameter.name, "user");
+        assert_eq!(parameter.name_localizations["de"], "nutzer");
+        assert_eq!(parameter.description.as_deref(), Some("The user"));
+    }
+}
@@ -0,0 +1,452 @@
+//! Texts of the messages which poise sends to users, so that they can be translated
+
+use crate::serenity_prelude as serenity;
+
+/// Provides the texts of the messages which poise sends to users: the error messages of
+/// [`crate::builtins::on_error`], the texts of poise's argument errors like
+/// [`crate::TooFewArguments`], the labels of [`crate::builtins::help`], and the texts of
+/// [`crate::builtins::Paginator`] and [`crate::builtins::confirm`]
+///
+/// Set with [`crate::FrameworkOptions::messages`]. Every method receives the locale of the user,
+/// as returned by [`crate::Context::locale`], and has an English default implementation. So a
+/// translation only overrides the methods it translates, and can fall back to the English text by
+/// calling the default implementation via [`EnglishMessages`]:
+///
+/// ```rust
+/// use poise::MessageCatalog;
+///
+/// struct GermanMessages;
+///
+/// impl MessageCatalog for GermanMessages {
+///     fn guild_only(&self, locale: Option<&str>) -> String {
+///         match locale {
+///             Some("de") => "Dieser Befehl funktioniert nicht in DMs.".into(),
+///             _ => poise::EnglishMessages.guild_only(locale),
+///         }
+///     }
+/// }
+/// ```
+#[allow(unused_variables)] // The default implementations don't use the locale
+pub trait MessageCatalog: Send + Sync {
+    /// A command with `subcommand_required` was invoked without subcommand
+    fn subcommand_required(&self, locale: Option<&str>, subcommands: &[&str]) -> String {
+        format!(
+            "You must specify one of the following subcommands: {}",
+            subcommands.join(", ")
+        )
+    }
+
+    /// Suggests similar commands after an invalid subcommand. The suggestions are command names
+    /// without prefix
+    fn did_you_mean(&self, locale: Option<&str>, prefix: &str, suggestions: &[String]) -> String {
+        format!("Did you mean {}?", list_suggestions(prefix, suggestions))
+    }
+
+    /// A prefix command wasn't found, see [`crate::PrefixFrameworkOptions::suggest_unknown_commands`]
+    fn unknown_command(
+        &self,
+        locale: Option<&str>,
+        prefix: &str,
+        command: &str,
+        suggestions: &[String],
+    ) -> String {
+        format!(
+            "Unknown command `{}{}`, did you mean {}?",
+            prefix,
+            command,
+            list_suggestions(prefix, suggestions)
+        )
+    }
+
+    /// Title of the embed shown when a command panicked
+    fn internal_error_title(&self, locale: Option<&str>) -> String {
+        "Internal error".into()
+    }
+
+    /// Description of the embed shown when a command panicked
+    fn internal_error_description(&self, locale: Option<&str>) -> String {
+        "An unexpected internal error has occurred.".into()
+    }
+
+    /// Headline of a [`crate::FrameworkError::ArgumentParse`] message. `error` is the text of the
+    /// error as returned by [`Self::argument_error`]
+    fn argument_parse_error(
+        &self,
+        locale: Option<&str>,
+        input: Option<&str>,
+        parameter: Option<&str>,
+        error: &str,
+    ) -> String {
+        match (input, parameter) {
+            (Some(input), Some(parameter)) => format!(
+                "**Cannot parse `{}` as argument `{}`: {}**",
+                input, parameter, error
+            ),
+            (Some(input), None) => format!("**Cannot parse `{}` as argument: {}**", input, error),
+            (None, _) => format!("**{}**", error),
+        }
+    }
+
+    /// Label under the offending part of a prefix command whose argument failed to parse
+    fn expected_type(&self, locale: Option<&str>, type_name: &str) -> String {
+        format!("expected {}", type_name)
+    }
+
+    /// Text of an argument parse error. The default implementation uses the methods below for
+    /// poise's own error types, and the [`std::fmt::Display`] implementation for other errors
+    fn argument_error(
+        &self,
+        locale: Option<&str>,
+        error: &(dyn std::error::Error + Send + Sync),
+    ) -> String {
+        if error.is::<crate::TooManyArguments>() {
+            self.too_many_arguments(locale)
+        } else if error.is::<crate::TooFewArguments>() {
+            self.too_few_arguments(locale)
+        } else if error.is::<crate::MissingAttachment>() {
+            self.missing_attachment(locale)
+        } else if error.is::<crate::InvalidChoice>() {
+            self.invalid_choice(locale)
+        } else if error.is::<crate::InvalidBool>() {
+            self.invalid_bool(locale)
+        } else if error.is::<crate::InvalidDuration>() {
+            self.invalid_duration(locale)
+        } else if error.is::<crate::InvalidTimestamp>() {
+            self.invalid_timestamp(locale)
+        } else if error.is::<crate::InvalidColor>() {
+            self.invalid_color(locale)
+        } else if let Some(error) = error.downcast_ref::<crate::NamedOptionError>() {
+            self.named_option_error(locale, error)
+        } else if let Some(error) = error.downcast_ref::<crate::ArgumentValidationError>() {
+            self.validation_error(locale, error)
+        } else {
+            error.to_string()
+        }
+    }
+
+    /// Text of [`crate::TooManyArguments`]
+    fn too_many_arguments(&self, locale: Option<&str>) -> String {
+        crate::TooManyArguments.to_string()
+    }
+
+    /// Text of [`crate::TooFewArguments`]
+    fn too_few_arguments(&self, locale: Option<&str>) -> String {
+        crate::TooFewArguments.to_string()
+    }
+
+    /// Text of [`crate::MissingAttachment`]
+    fn missing_attachment(&self, locale: Option<&str>) -> String {
+        crate::MissingAttachment.to_string()
+    }
+
+    /// Text of [`crate::InvalidChoice`]
+    fn invalid_choice(&self, locale: Option<&str>) -> String {
+        crate::InvalidChoice.to_string()
+    }
+
+    /// Text of [`crate::InvalidBool`]
+    fn invalid_bool(&self, locale: Option<&str>) -> String {
+        crate::InvalidBool.to_string()
+    }
+
+    /// Text of [`crate::InvalidDuration`]
+    fn invalid_duration(&self, locale: Option<&str>) -> String {
+        crate::InvalidDuration.to_string()
+    }
+
+    /// Text of [`crate::InvalidTimestamp`]
+    fn invalid_timestamp(&self, locale: Option<&str>) -> String {
+        crate::InvalidTimestamp.to_string()
+    }
+
+    /// Text of [`crate::InvalidColor`]
+    fn invalid_color(&self, locale: Option<&str>) -> String {
+        crate::InvalidColor.to_string()
+    }
+
+    /// Text of a [`crate::NamedOptionError`]
+    fn named_option_error(&self, locale: Option<&str>, error: &crate::NamedOptionError) -> String {
+        error.to_string()
+    }
+
+    /// Text of an [`crate::ArgumentValidationError`], like "Must be at most 100". Errors of
+    /// `#[validate]` functions are shown as they are
+    fn validation_error(
+        &self,
+        locale: Option<&str>,
+        error: &crate::ArgumentValidationError,
+    ) -> String {
+        error.to_string()
+    }
+
+    /// A parameter value was rejected, see [`crate::FrameworkError::ArgumentValidation`]. The
+    /// default implementation describes the error with [`Self::validation_error`]
+    fn argument_validation_error(
+        &self,
+        locale: Option<&str>,
+        input: Option<&str>,
+        parameter: &str,
+        error: &crate::ArgumentValidationError,
+    ) -> String {
+        let error = self.validation_error(locale, error);
+        match input {
+            Some(input) => format!(
+                "**Invalid value `{}` for `{}`**: {}",
+                input, parameter, error
+            ),
+            None => format!("**Invalid value for `{}`**: {}", parameter, error),
+        }
+    }
+
+    /// An attachment was rejected, see [`crate::FrameworkError::AttachmentConstraint`]
+    fn attachment_constraint(
+        &self,
+        locale: Option<&str>,
+        parameter: &str,
+        violation: &crate::AttachmentConstraintViolation,
+    ) -> String {
+        format!("**Invalid attachment for `{}`**: {}", parameter, violation)
+    }
+
+    /// The command is on cooldown
+    fn cooldown_hit(&self, locale: Option<&str>, remaining: std::time::Duration) -> String {
+        format!(
+            "You're too fast. Please wait {} seconds before retrying",
+            remaining.as_secs()
+        )
+    }
+
+    /// The bot lacks permissions to execute the command
+    fn missing_bot_permissions(
+        &self,
+        locale: Option<&str>,
+        permissions: serenity::Permissions,
+    ) -> String {
+        format!(
+            "Command cannot be executed because the bot is lacking permissions: {}",
+            permissions,
+        )
+    }
+
+    /// The user lacks permissions to execute the command. `permissions` is `None` if they
+    /// couldn't be determined. `command` is the command name with prefix
+    fn missing_user_permissions(
+        &self,
+        locale: Option<&str>,
+        command: &str,
+        permissions: Option<serenity::Permissions>,
+    ) -> String {
+        match permissions {
+            Some(permissions) => format!(
+                "You're lacking permissions for `{}`: {}",
+                command, permissions
+            ),
+            None => format!(
+                "You may be lacking permissions for `{}`. Not executing for safety",
+                command
+            ),
+        }
+    }
+
+    /// An `owners_only` command was invoked by someone else
+    fn not_an_owner(&self, locale: Option<&str>) -> String {
+        "Only bot owners can call this command".into()
+    }
+
+    /// A `guild_only` command was invoked in DMs
+    fn guild_only(&self, locale: Option<&str>) -> String {
+        "You cannot run this command in DMs.".into()
+    }
+
+    /// A `dm_only` command was invoked in a guild
+    fn dm_only(&self, locale: Option<&str>) -> String {
+        "You cannot run this command outside DMs.".into()
+    }
+
+    /// An `nsfw_only` command was invoked outside NSFW channels
+    fn nsfw_only(&self, locale: Option<&str>) -> String {
+        "You cannot run this command outside NSFW channels.".into()
+    }
+
+    /// The command was disabled with [`crate::CommandRegistry`]
+    fn command_disabled(&self, locale: Option<&str>) -> String {
+        "This command is currently disabled.".into()
+    }
+
+    /// The command is disabled in this guild, see [`crate::GuildSettingsProvider`]
+    fn guild_command_disabled(&self, locale: Option<&str>) -> String {
+        "This command is disabled in this server.".into()
+    }
+
+    /// The category of the command is disabled in this guild
+    fn guild_category_disabled(&self, locale: Option<&str>, category: &str) -> String {
+        format!(
+            "Commands in category {} are disabled in this server.",
+            category
+        )
+    }
+
+    /// Commands aren't allowed in this channel of the guild
+    fn channel_not_allowed(&self, locale: Option<&str>) -> String {
+        "You cannot run this command in this channel.".into()
+    }
+
+    /// The user has none of the roles which the guild requires for the command
+    fn missing_required_role(&self, locale: Option<&str>, roles: &[serenity::RoleId]) -> String {
+        let roles = roles
+            .iter()
+            .map(|role| format!("<@&{}>", role))
+            .collect::<Vec<_>>()
+            .join(", ");
+        format!("You need one of these roles to run this command: {}", roles)
+    }
+
+    /// The help command was asked about a command that doesn't exist
+    fn help_unknown_command(&self, locale: Option<&str>, command: &str) -> String {
+        format!("No such command `{}`", command)
+    }
+
+    /// Help text of a command without help text and description
+    fn help_no_help_available(&self, locale: Option<&str>) -> String {
+        "No help available".into()
+    }
+
+    /// Description of a parameter without description in the embed help
+    fn help_no_description(&self, locale: Option<&str>) -> String {
+        "No description".into()
+    }
+
+    /// Title of the usage field in the embed help
+    fn help_usage(&self, locale: Option<&str>) -> String {
+        "Usage".into()
+    }
+
+    /// Marks a parameter as required or optional in the embed help
+    fn help_requirement(&self, locale: Option<&str>, required: bool) -> String {
+        match required {
+            true => "required".into(),
+            false => "optional".into(),
+        }
+    }
+
+    /// Lists the choices of a choice parameter in the embed help
+    fn help_choices(&self, locale: Option<&str>, choices: &str) -> String {
+        format!("Choices: {}", choices)
+    }
+
+    /// Title of the subcommands field in the embed help
+    fn help_subcommands(&self, locale: Option<&str>) -> String {
+        "Subcommands".into()
+    }
+
+    /// Category name of commands without category
+    fn help_default_category(&self, locale: Option<&str>) -> String {
+        "Commands".into()
+    }
+
+    /// Heading of the list of context menu commands
+    fn help_context_menu_commands(&self, locale: Option<&str>) -> String {
+        "Context menu commands".into()
+    }
+
+    /// Line of a context menu command in the help, where `user` tells whether it's a user or a
+    /// message context menu command
+    fn help_context_menu_command(&self, locale: Option<&str>, name: &str, user: bool) -> String {
+        format!("{} (on {})", name, if user { "user" } else { "message" })
+    }
+
+    /// Shown by the embed help if there are no commands
+    fn help_no_commands(&self, locale: Option<&str>) -> String {
+        "No commands".into()
+    }
+
+    /// Someone else than the command invoker pressed a button of a [`crate::builtins::Paginator`]
+    /// which is restricted to the author
+    fn paginator_not_invoker(&self, locale: Option<&str>) -> String {
+        "Only the command invoker can flip pages".into()
+    }
+
+    /// Title of the modal which the page counter of a [`crate::builtins::Paginator`] opens
+    fn paginator_jump_title(&self, locale: Option<&str>) -> String {
+        "Jump to page".into()
+    }
+
+    /// Label of the page number input in the modal of [`Self::paginator_jump_title`]
+    fn paginator_page_number(&self, locale: Option<&str>, num_pages: usize) -> String {
+        format!("Page number (1-{})", num_pages)
+    }
+
+    /// The page number entered into the modal of [`Self::paginator_jump_title`] is invalid
+    fn paginator_invalid_page_number(&self, locale: Option<&str>, num_pages: usize) -> String {
+        format!("Please enter a page number from 1 to {}", num_pages)
+    }
+
+    /// Someone else than the command invoker or the allowed role answered a
+    /// [`crate::builtins::confirm`] prompt
+    fn confirm_not_allowed(&self, locale: Option<&str>) -> String {
+        "You're not allowed to answer this prompt".into()
+    }
+
+    /// Default label of the confirm button of a [`crate::builtins::confirm`] prompt, see
+    /// [`crate::builtins::ConfirmOptions::confirm_label`]
+    fn confirm_label(&self, locale: Option<&str>) -> String {
+        "Confirm".into()
+    }
+
+    /// Default label of the cancel button of a [`crate::builtins::confirm`] prompt, see
+    /// [`crate::builtins::ConfirmOptions::cancel_label`]
+    fn confirm_cancel_label(&self, locale: Option<&str>) -> String {
+        "Cancel".into()
+    }
+}
+
+/// The default, English [`MessageCatalog`]
+#[derive(Clone, Copy, Debug, Default)]
+pub struct EnglishMessages;
+
+impl MessageCatalog for EnglishMessages {}
+
+/// Formats command suggestions like "`~help`, `~helm` or `~hello`"
+fn list_suggestions(prefix: &str, suggestions: &[String]) -> String {
+    let suggestions = suggestions
+        .iter()
+        .take(3)
+        .map(|suggestion| format!("`{}{}`", prefix, suggestion))
+        .collect::<Vec<_>>();
+    match suggestions.split_last() {
+        Some((last, rest)) if !rest.is_empty() => format!("{} or {}", rest.join(", "), last),
+        _ => suggestions.concat(),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_argument_error() {
+        struct Custom;
+        impl MessageCatalog for Custom {
+            fn too_few_arguments(&self, _: Option<&str>) -> String {
+                "Zu wenige Argumente".into()
+            }
+        }
+
+        assert_eq!(
+            Custom.argument_error(Some("de"), &crate::TooFewArguments),
+            "Zu wenige Argumente"
+        );
+        assert_eq!(
+            Custom.argument_error(Some("de"), &crate::InvalidBool),
+            EnglishMessages.invalid_bool(None)
+        );
+        assert_eq!(
+            Custom.argument_error(None, &crate::NamedOptionError::Missing("--days".into())),
+            "Missing required option `--days`"
+        );
+        assert_eq!(
+            EnglishMessages.did_you_mean(None, "~", &["help".into(), "helm".into()]),
+            "Did you mean `~help` or `~helm`?"
+        );
+    }
+}
@@ -0,0 +1,76 @@
+//! Trait for bundles of parameters which are shared between commands
+
+use crate::serenity_prelude as serenity;
+
+/// A struct whose fields are flattened into the parameters of a command. Derive it with
+/// [`macro@crate::ParameterGroup`] and mark the command parameter with `#[flatten]`:
+///
+/// ```rust
+/// # type Error = Box<dyn std::error::Error + Send + Sync>;
+/// # type Context<'a> = poise::Context<'a, (), Error>;
+/// # use poise::serenity_prelude as serenity;
+/// #[derive(poise::ParameterGroup)]
+/// struct Punishment {
+///     #[description = "User to punish"]
+///     user: serenity::User,
+///     #[description = "Reason shown in the audit log"]
+///     reason: Option<String>,
+/// }
+///
+/// #[poise::command(prefix_command, slash_command)]
+/// async fn kick(ctx: Context<'_>, #[flatten] punishment: Punishment) -> Result<(), Error> {
+///     Ok(())
+/// }
+///
+/// #[poise::command(prefix_command, slash_command)]
+/// async fn ban(
+///     ctx: Context<'_>,
+///     #[flatten] punishment: Punishment,
+///     #[description = "Number of days of messages to delete"] days: Option<u8>,
+/// ) -> Result<(), Error> {
+///     Ok(())
+/// }
+/// ```
+///
+/// The fields appear in [`crate::Command::parameters`] in place of the group, so they are
+/// registered as separate slash command options and show up in help and usage messages. In prefix
+/// commands, the fields are parsed one after the other, in one go: parsing of later parameters
+/// can't backtrack into the group.
+#[async_trait::async_trait]
+pub trait ParameterGroup<U, E>: Sized {
+    /// Number of fields, i.e. length of [`Self::parameters`]
+    const PARAMETER_COUNT: usize;
+
+    /// Definitions of the fields as command parameters
+    fn parameters() -> Vec<crate::CommandParameter<U, E>>;
+
+    /// Parses the fields from the front of prefix command arguments. Returns the remaining
+    /// arguments and attachment index like [`crate::PopArgument::pop_from`]
+    ///
+    /// Don't call this method directly! It's used by [`crate::parse_prefix_args!`]
+    async fn pop_from<'a>(
+        args: &'a str,
+        attachment_index: usize,
+        tokenizer: &crate::Tokenizer,
+        ctx: &serenity::Context,
+        msg: &serenity::Message,
+    ) -> Result<(&'a str, usize, Self), crate::PrefixArgumentError>;
+
+    /// Extracts the fields from the options of a slash command
+    ///
+    /// Don't call this method directly! It's used by [`crate::parse_slash_args!`]
+    async fn extract(
+        ctx: &impl serenity::CacheHttp,
+        interaction: crate::CommandOrAutocompleteInteraction<'_>,
+        args: &[serenity::ResolvedOption<'_>],
+    ) -> Result<Self, crate::SlashArgError>;
+
+    /// Checks the fields against their validation attributes like `#[max]` (see
+    /// [`crate::validation`]). `parameter_offset` is the position of the first field in
+    /// [`crate::Command::parameters`]
+    fn validate<'a>(
+        &self,
+        ctx: crate::Context<'a, U, E>,
+        parameter_offset: usize,
+    ) -> Result<(), crate::FrameworkError<'a, U, E>>;
+}
@@ -2,18 +2,35 @@
 //! the auto-deref specialization emulation code to e.g. support more strings for bool parameters
 //! instead of the `FromStr` ones
 
-use super::{pop_string, InvalidBool, MissingAttachment, TooFewArguments};
+use super::{InvalidBool, MissingAttachment, Tokenizer, TooFewArguments};
 use crate::serenity_prelude as serenity;
 use std::marker::PhantomData;
 
 /// Full version of [`crate::PopArgument::pop_from`].
 ///
-/// Uses specialization to get full coverage of types. Pass the type as the first argument
+/// Uses specialization to get full coverage of types. Pass the type as the first argument. The
+/// optional last argument is the [`crate::Tokenizer`] to split words with
 #[macro_export]
 macro_rules! pop_prefix_argument {
-    ($target:ty, $args:expr, $attachment_id:expr, $ctx:expr, $msg:expr) => {{
+    ($target:ty, $args:expr, $attachment_id:expr, $ctx:expr, $msg:expr) => {
+        $crate::pop_prefix_argument!(
+            $target,
+            $args,
+            $attachment_id,
+            $ctx,
+            $msg,
+            &$crate::Tokenizer::default()
+        )
+    };
+    ($target:ty, $args:expr, $attachment_id:expr, $ctx:expr, $msg:expr, $tokenizer:expr) => {{
         use $crate::PopArgumentHack as _;
-        (&std::marker::PhantomData::<$target>).pop_from($args, $attachment_id, $ctx, $msg)
+        (&std::marker::PhantomData::<$target>).pop_from(
+            $args,
+            $attachment_id,
+            $tokenizer,
+            $ctx,
+            $msg,
+        )
     }};
 }
 
@@ -40,6 +57,25 @@ pub trait PopArgument<'a>: Sized {
         ctx: &serenity::Context,
         msg: &serenity::Message,
     ) -> Result<(&'a str, usize, Self), (Box<dyn std::error::Error + Send + Sync>, Option<String>)>;
+
+    /// Like [`Self::pop_from`], but with the [`crate::Tokenizer`] configured in
+    /// [`crate::PrefixFrameworkOptions::tokenizer`]. This is what the framework calls.
+    ///
+    /// Override this method if your type splits the arguments into words, so that it honours the
+    /// configured quoting rules. By default, the tokenizer is ignored and [`Self::pop_from`] is
+    /// called.
+    ///
+    /// Don't call this method directly! Use [`crate::pop_prefix_argument!`]
+    async fn pop_from_tokenized(
+        args: &'a str,
+        attachment_index: usize,
+        tokenizer: &Tokenizer,
+        ctx: &serenity::Context,
+        msg: &serenity::Message,
+    ) -> Result<(&'a str, usize, Self), (Box<dyn std::error::Error + Send + Sync>, Option<String>)>
+    {
+        Self::pop_from(args, attachment_index, ctx, msg).await
+    }
 }
 
 #[doc(hidden)]
@@ -49,6 +85,7 @@ pub trait PopArgumentHack<'a, T>: Sized {
         self,
         args: &'a str,
         attachment_index: usize,
+        tokenizer: &Tokenizer,
         ctx: &serenity::Context,
         msg: &serenity::Message,
     ) -> Result<(&'a str, usize, T), (Box<dyn std::error::Error + Send + Sync>, Option<String>)>;
@@ -63,11 +100,14 @@ where
         self,
         args: &'a str,
         attachment_index: usize,
+        tokenizer: &Tokenizer,
         ctx: &serenity::Context,
         msg: &serenity::Message,
     ) -> Result<(&'a str, usize, T), (Box<dyn std::error::Error + Send + Sync>, Option<String>)>
     {
-        let (args, string) = pop_string(args).map_err(|_| (TooFewArguments.into(), None))?;
+        let (args, string) = tokenizer
+            .pop_word(args)
+            .map_err(|_| (TooFewArguments.into(), None))?;
         let object = T::convert(ctx, msg.guild_id, Some(msg.channel_id), &string)
             .await
             .map_err(|e| (e.into(), Some(string)))?;
@@ -82,11 +122,12 @@ impl<'a, T: PopArgument<'a> + Send + Sync> PopArgumentHack<'a, T> for &PhantomDa
         self,
         args: &'a str,
         attachment_index: usize,
+        tokenizer: &Tokenizer,
         ctx: &serenity::Context,
         msg: &serenity::Message,
     ) -> Result<(&'a str, usize, T), (Box<dyn std::error::Error + Send + Sync>, Option<String>)>
     {
-        T::pop_from(args, attachment_index, ctx, msg).await
+        T::pop_from_tokenized(args, attachment_index, tokenizer, ctx, msg).await
     }
 }
 
@@ -96,11 +137,14 @@ impl<'a> PopArgumentHack<'a, bool> for &PhantomData<bool> {
         self,
         args: &'a str,
         attachment_index: usize,
+        tokenizer: &Tokenizer,
         ctx: &serenity::Context,
         msg: &serenity::Message,
     ) -> Result<(&'a str, usize, bool), (Box<dyn std::error::Error + Send + Sync>, Option<String>)>
     {
-        let (args, string) = pop_string(args).map_err(|_| (TooFewArguments.into(), None))?;
+        let (args, string) = tokenizer
+            .pop_word(args)
+            .map_err(|_| (TooFewArguments.into(), None))?;
 
         let value = match string.to_ascii_lowercase().trim() {
             "yes" | "y" | "true" | "t" | "1" | "enable" | "on" => true,
@@ -118,6 +162,7 @@ impl<'a> PopArgumentHack<'a, serenity::Attachment> for &PhantomData<serenity::At
         self,
         args: &'a str,
         attachment_index: usize,
+        tokenizer: &Tokenizer,
         ctx: &serenity::Context,
         msg: &serenity::Message,
     ) -> Result<
@@ -133,3 +178,28 @@ impl<'a> PopArgumentHack<'a, serenity::Attachment> for &PhantomData<serenity::At
         Ok((args, attachment_index + 1, attachment))
     }
 }
+
+#[async_trait::async_trait]
+impl<'a> PopArgumentHack<'a, Vec<serenity::Attachment>>
+    for &PhantomData<Vec<serenity::Attachment>>
+{
+    async fn pop_from(
+        self,
+        args: &'a str,
+        attachment_index: usize,
+        tokenizer: &Tokenizer,
+        ctx: &serenity::Context,
+        msg: &serenity::Message,
+    ) -> Result<
+        (&'a str, usize, Vec<serenity::Attachment>),
+        (Box<dyn std::error::Error + Send + Sync>, Option<String>),
+    > {
+        // Only reached for Option<Vec<Attachment>>, where no attachments means None
+        let attachments = msg.attachments.get(attachment_index..).unwrap_or_default();
+        if attachments.is_empty() {
+            return Err((MissingAttachment.into(), None));
+        }
+
+        Ok((args, msg.attachments.len(), attachments.to_vec()))
+    }
+}
@@ -15,54 +15,32 @@ impl KeyValueArgs {
     }
 
     /// Reads a single key value pair ("key=value") from the front of the arguments
-    fn pop_single_key_value_pair(args: &str) -> Option<(&str, (String, String))> {
-        // TODO: share quote parsing machinery with PopArgumentAsync impl for String
-
-        if args.is_empty() {
-            return None;
-        }
-
-        let mut key = String::new();
-        let mut inside_string = false;
-        let mut escaping = false;
-
-        let mut chars = args.trim_start().chars();
-        loop {
-            let c = chars.next()?;
-            if escaping {
-                key.push(c);
-                escaping = false;
-            } else if !inside_string && c.is_whitespace() {
-                return None;
-            } else if c == '"' {
-                inside_string = !inside_string;
-            } else if c == '\\' {
-                escaping = true;
-            } else if !inside_string && c == '=' {
-                break;
-            } else if !inside_string && c.is_ascii_punctuation() {
-                // If not enclosed in quotes, keys mustn't contain special characters.
-                // Otherwise this command invocation: "?eval `0..=5`" is parsed as key-value args
-                // with key "`0.." and value "5`". (This was a long-standing issue in rustbot)
-                return None;
-            } else {
-                key.push(c);
-            }
-        }
+    fn pop_single_key_value_pair<'a>(
+        args: &'a str,
+        tokenizer: &Tokenizer,
+    ) -> Option<(&'a str, (String, String))> {
+        // If not enclosed in quotes, keys mustn't contain special characters. Otherwise this
+        // command invocation: "?eval `0..=5`" is parsed as key-value args with key "`0.." and
+        // value "5`". (This was a long-standing issue in rustbot)
+        let (args, key) = tokenizer.pop_until(args.trim_start(), |c| {
+            c.is_whitespace() || c.is_ascii_punctuation()
+        });
+        let args = args.strip_prefix('=')?;
 
-        let args = chars.as_str();
         // `args` used to contain "key=value ...", now it contains "value ...", so pop the value off
-        let (args, value) = super::pop_string(args).unwrap_or((args, String::new()));
+        let (args, value) = tokenizer.pop_word(args).unwrap_or((args, String::new()));
 
         Some((args, (key, value)))
     }
 
     /// Reads as many key-value args as possible from the front of the string and produces a
     /// [`KeyValueArgs`] out of those
-    fn pop_from(mut args: &str) -> (&str, Self) {
+    fn pop_from<'a>(mut args: &'a str, tokenizer: &Tokenizer) -> (&'a str, Self) {
         let mut pairs = std::collections::HashMap::new();
 
-        while let Some((remaining_args, (key, value))) = Self::pop_single_key_value_pair(args) {
+        while let Some((remaining_args, (key, value))) =
+            Self::pop_single_key_value_pair(args, tokenizer)
+        {
             args = remaining_args;
             pairs.insert(key, value);
         }
@@ -76,11 +54,22 @@ impl<'a> PopArgument<'a> for KeyValueArgs {
     async fn pop_from(
         args: &'a str,
         attachment_index: usize,
+        ctx: &serenity::Context,
+        msg: &serenity::Message,
+    ) -> Result<(&'a str, usize, Self), (Box<dyn std::error::Error + Send + Sync>, Option<String>)>
+    {
+        Self::pop_from_tokenized(args, attachment_index, &Tokenizer::default(), ctx, msg).await
+    }
+
+    async fn pop_from_tokenized(
+        args: &'a str,
+        attachment_index: usize,
+        tokenizer: &Tokenizer,
         _: &serenity::Context,
         _: &serenity::Message,
     ) -> Result<(&'a str, usize, Self), (Box<dyn std::error::Error + Send + Sync>, Option<String>)>
     {
-        let (a, b) = Self::pop_from(args);
+        let (a, b) = KeyValueArgs::pop_from(args, tokenizer);
 
         Ok((a, attachment_index, b))
     }
@@ -108,7 +97,7 @@ fn test_key_value_args() {
         (r#"dummyval"#, &[], "dummyval"),
         (r#"dummyval="#, &[("dummyval", "")], ""),
     ] {
-        let (args, kv_args) = KeyValueArgs::pop_from(string);
+        let (args, kv_args) = KeyValueArgs::pop_from(string, &Tokenizer::default());
 
         assert_eq!(
             kv_args.0,
@@ -1,63 +1,259 @@
 //! A macro that generates backtracking-capable argument parsing code, given a list of parameter
 //! types and attributes
 
+/// Error returned by [`crate::parse_prefix_args!`]
+#[derive(Debug)]
+pub struct PrefixArgumentError {
+    /// Error which was thrown by the parameter type's parsing routine
+    pub error: Box<dyn std::error::Error + Send + Sync>,
+    /// If applicable, the input on which parsing failed
+    pub input: Option<String>,
+    /// Position of the parameter which failed to parse in the parameter list. `None` if the error
+    /// isn't tied to a single parameter, e.g. when too many arguments were passed
+    pub parameter_index: Option<usize>,
+    /// If the failed parameter is a [`crate::ParameterGroup`], the position of the field which
+    /// failed to parse within the group
+    pub group_field_index: Option<usize>,
+    /// Byte range of the offending input within the parsed argument string
+    pub span: std::ops::Range<usize>,
+}
+
+impl PrefixArgumentError {
+    /// Maps [`Self::parameter_index`] from a position in the list of parsed parameters to a
+    /// position in [`crate::Command::parameters`], for when not all parameters were parsed
+    /// positionally
+    ///
+    /// Mainly used in the desugared [`crate::command`] macro
+    ///
+    /// The fields of a [`crate::ParameterGroup`] follow the index of the group, so a failed field is
+    /// mapped to the index of the group plus [`Self::group_field_index`].
+    pub fn with_parameter_indices(mut self, parameter_indices: &[usize]) -> Self {
+        let group_field_index = self.group_field_index.take().unwrap_or(0);
+        self.parameter_index = self
+            .parameter_index
+            .and_then(|i| parameter_indices.get(i).copied())
+            .map(|i| i + group_field_index);
+        self
+    }
+
+    /// Converts this error to [`crate::FrameworkError::ArgumentParse`], looking up the failed
+    /// parameter in [`crate::Command::parameters`] and making the span relative to the message
+    /// content
+    ///
+    /// Mainly used in the desugared [`crate::command`] macro
+    pub fn to_framework_error<U, E>(
+        self,
+        ctx: crate::PrefixContext<'_, U, E>,
+    ) -> crate::FrameworkError<'_, U, E> {
+        let parameter = self
+            .parameter_index
+            .and_then(|i| ctx.command.parameters.get(i));
+        // The span can only be translated if the arguments are actually part of the message
+        let content = &ctx.msg.content;
+        let args_offset = (ctx.args.as_ptr() as usize)
+            .checked_sub(content.as_ptr() as usize)
+            .filter(|&offset| offset + ctx.args.len() <= content.len());
+
+        crate::FrameworkError::ArgumentParse {
+            error: self.error,
+            input: self.input,
+            parameter: parameter.map(|p| &*p.name),
+            expected_type: parameter.map(|p| p.details.type_name),
+            span: args_offset.map(|offset| offset + self.span.start..offset + self.span.end),
+            ctx: ctx.into(),
+        }
+    }
+}
+
+impl std::fmt::Display for PrefixArgumentError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.input {
+            Some(input) => write!(f, "Failed to parse `{}` as argument: {}", input, self.error),
+            None => write!(f, "{}", self.error),
+        }
+    }
+}
+
+impl std::error::Error for PrefixArgumentError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(&*self.error)
+    }
+}
+
+/// Collects the failures encountered while [`crate::parse_prefix_args!`] backtracks, and keeps
+/// the one that got furthest into the input. On equal progress, the first failure is kept
+#[doc(hidden)]
+pub struct DeepestArgumentError<'a> {
+    /// The complete argument string
+    args: &'a str,
+    /// Tokenizer used to find the end of a failed word
+    tokenizer: &'a super::Tokenizer,
+    /// Furthest failure so far
+    deepest: Option<PrefixArgumentError>,
+}
+
+impl<'a> DeepestArgumentError<'a> {
+    /// Starts collecting failures for parsing the given argument string
+    pub fn new(args: &'a str, tokenizer: &'a super::Tokenizer) -> Self {
+        Self {
+            args,
+            tokenizer,
+            deepest: None,
+        }
+    }
+
+    /// Records a failure at the front of `remaining`, which must be a suffix of the complete
+    /// argument string. If `to_end` is set, the failure spans all of `remaining` instead of just
+    /// the next word
+    pub fn record(
+        &mut self,
+        (error, input): (Box<dyn std::error::Error + Send + Sync>, Option<String>),
+        parameter_index: Option<usize>,
+        remaining: &str,
+        to_end: bool,
+    ) {
+        let offset_of = |suffix: &str| self.args.len().saturating_sub(suffix.len());
+        let start = offset_of(remaining.trim_start());
+        if self
+            .deepest
+            .as_ref()
+            .map_or(false, |deepest| deepest.span.start >= start)
+        {
+            return;
+        }
+
+        let end = if error.is::<super::MissingAttachment>() {
+            // Attachments don't occupy any text
+            start
+        } else if to_end {
+            self.args.trim_end().len()
+        } else {
+            self.tokenizer
+                .pop_word(remaining)
+                .map_or(start, |(rest, _)| offset_of(rest))
+        };
+        self.deepest = Some(PrefixArgumentError {
+            error,
+            input,
+            parameter_index,
+            group_field_index: None,
+            span: start..end.max(start),
+        });
+    }
+
+    /// Records the failure of a [`crate::ParameterGroup`] which was parsed from the front of
+    /// `remaining`, which must be a suffix of the complete argument string
+    pub fn record_group(
+        &mut self,
+        error: PrefixArgumentError,
+        parameter_index: usize,
+        remaining: &str,
+    ) {
+        let offset = self.args.len().saturating_sub(remaining.len());
+        let span = offset + error.span.start..offset + error.span.end;
+        if self
+            .deepest
+            .as_ref()
+            .map_or(false, |deepest| deepest.span.start >= span.start)
+        {
+            return;
+        }
+
+        self.deepest = Some(PrefixArgumentError {
+            parameter_index: Some(parameter_index),
+            group_field_index: error.parameter_index,
+            span,
+            ..error
+        });
+    }
+
+    /// Returns the furthest failure
+    pub fn into_error(self) -> PrefixArgumentError {
+        let args_len = self.args.len();
+        self.deepest.unwrap_or_else(|| PrefixArgumentError {
+            error: Box::new(super::TooManyArguments),
+            input: None,
+            parameter_index: None,
+            group_field_index: None,
+            span: 0..args_len,
+        })
+    }
+}
+
+/// Counts the given token trees. Used to find out the index of the currently parsed parameter
+#[doc(hidden)]
+#[macro_export]
+macro_rules! _count_prefix_args {
+    () => { 0usize };
+    ( $first:tt $( $rest:tt )* ) => { 1usize + $crate::_count_prefix_args!($( $rest )*) };
+}
+
 #[doc(hidden)]
 #[macro_export]
 macro_rules! _parse_prefix {
     // All arguments have been consumed
-    ( $ctx:ident $msg:ident $args:ident $attachment_index:ident => [ $error:ident $( $name:ident )* ] ) => {
+    ( $ctx:ident $msg:ident $tokenizer:ident $args:ident $attachment_index:ident => [ $error:ident $( $name:ident )* ] ) => {
         if $args.is_empty() {
             return Ok(( $( $name, )* ));
         }
+        $error.record(($crate::TooManyArguments.into(), None), None, $args, true);
+    };
+
+    // Stop parsing and also return the remaining arguments and attachment index. Used by parameter
+    // groups, which are parsed from the middle of the arguments
+    ( $ctx:ident $msg:ident $tokenizer:ident $args:ident $attachment_index:ident => [ $error:ident $( $name:ident )* ]
+        (#[remaining])
+    ) => {
+        return Ok(( $( $name, )* $args, $attachment_index ));
     };
 
     // Consume Option<T> greedy-first
-    ( $ctx:ident $msg:ident $args:ident $attachment_index:ident => [ $error:ident $($preamble:tt)* ]
+    ( $ctx:ident $msg:ident $tokenizer:ident $args:ident $attachment_index:ident => [ $error:ident $($preamble:tt)* ]
         (Option<$type:ty $(,)?>)
         $( $rest:tt )*
     ) => {
         // Try parse the next argument
-        match $crate::pop_prefix_argument!($type, &$args, $attachment_index, $ctx, $msg).await {
+        match $crate::pop_prefix_argument!($type, &$args, $attachment_index, $ctx, $msg, $tokenizer).await {
             // On success, we get a new `$args` which contains only the rest of the args
             Ok(($args, $attachment_index, token)) => {
                 // On success, store `Some(token)` for the parsed argument
                 let token: Option<$type> = Some(token);
                 // And parse the rest of the arguments
-                $crate::_parse_prefix!($ctx $msg $args $attachment_index => [ $error $($preamble)* token ] $($rest)* );
+                $crate::_parse_prefix!($ctx $msg $tokenizer $args $attachment_index => [ $error $($preamble)* token ] $($rest)* );
                 // If the code gets here, parsing the rest of the argument has failed
             },
-            Err(e) => $error = e,
+            Err(e) => $error.record(e, Some($crate::_count_prefix_args!($($preamble)*)), $args, false),
         }
         let token: Option<$type> = None;
         // Parse the next arguments without changing the current arg string, thereby skipping the
         // current param
-        $crate::_parse_prefix!($ctx $msg $args $attachment_index => [ $error $($preamble)* token ] $($rest)* );
+        $crate::_parse_prefix!($ctx $msg $tokenizer $args $attachment_index => [ $error $($preamble)* token ] $($rest)* );
     };
 
     // Consume Option<T> lazy-first
-    ( $ctx:ident $msg:ident $args:ident $attachment_index:ident => [ $error:ident $($preamble:tt)* ]
+    ( $ctx:ident $msg:ident $tokenizer:ident $args:ident $attachment_index:ident => [ $error:ident $($preamble:tt)* ]
         (#[lazy] Option<$type:ty $(,)?>)
         $( $rest:tt )*
     ) => {
         let token: Option<$type> = None;
-        $crate::_parse_prefix!($ctx $msg $args $attachment_index => [ $error $($preamble)* token ] $($rest)* );
-        match $crate::pop_prefix_argument!($type, &$args, $attachment_index, $ctx, $msg).await {
+        $crate::_parse_prefix!($ctx $msg $tokenizer $args $attachment_index => [ $error $($preamble)* token ] $($rest)* );
+        match $crate::pop_prefix_argument!($type, &$args, $attachment_index, $ctx, $msg, $tokenizer).await {
             Ok(($args, $attachment_index, token)) => {
                 let token: Option<$type> = Some(token);
-                $crate::_parse_prefix!($ctx $msg $args $attachment_index => [ $error $($preamble)* token ] $($rest)* );
+                $crate::_parse_prefix!($ctx $msg $tokenizer $args $attachment_index => [ $error $($preamble)* token ] $($rest)* );
             },
-            Err(e) => $error = e,
+            Err(e) => $error.record(e, Some($crate::_count_prefix_args!($($preamble)*)), $args, false),
         }
     };
 
     // Consume #[rest] Option<T> until the end of the input
-    ( $ctx:ident $msg:ident $args:ident $attachment_index:ident => [ $error:ident $($preamble:tt)* ]
+    ( $ctx:ident $msg:ident $tokenizer:ident $args:ident $attachment_index:ident => [ $error:ident $($preamble:tt)* ]
         (#[rest] Option<$type:ty $(,)?>)
         $( $rest:tt )*
     ) => {
         if $args.trim_start().is_empty() {
             let token: Option<$type> = None;
-            $crate::_parse_prefix!($ctx $msg $args $attachment_index => [ $error $($preamble)* token ]);
+            $crate::_parse_prefix!($ctx $msg $tokenizer $args $attachment_index => [ $error $($preamble)* token ]);
         } else {
             let input = $args.trim_start();
             match <$type as $crate::serenity_prelude::ArgumentConvert>::convert(
@@ -66,15 +262,20 @@ macro_rules! _parse_prefix {
                 Ok(token) => {
                     let $args = "";
                     let token = Some(token);
-                    $crate::_parse_prefix!($ctx $msg $args $attachment_index => [ $error $($preamble)* token ]);
+                    $crate::_parse_prefix!($ctx $msg $tokenizer $args $attachment_index => [ $error $($preamble)* token ]);
                 },
-                Err(e) => $error = (e.into(), Some(input.to_owned())),
+                Err(e) => $error.record(
+                    (e.into(), Some(input.to_owned())),
+                    Some($crate::_count_prefix_args!($($preamble)*)),
+                    $args,
+                    true,
+                ),
             }
         }
     };
 
     // Consume Vec<T> greedy-first
-    ( $ctx:ident $msg:ident $args:ident $attachment_index:ident => [ $error:ident $($preamble:tt)* ]
+    ( $ctx:ident $msg:ident $tokenizer:ident $args:ident $attachment_index:ident => [ $error:ident $($preamble:tt)* ]
         (Vec<$type:ty $(,)?>)
         $( $rest:tt )*
     ) => {
@@ -85,7 +286,7 @@ macro_rules! _parse_prefix {
         let mut attachment = $attachment_index;
 
         loop {
-            match $crate::pop_prefix_argument!($type, &running_args, attachment, $ctx, $msg).await {
+            match $crate::pop_prefix_argument!($type, &running_args, attachment, $ctx, $msg, $tokenizer).await {
                 Ok((popped_args, new_attachment, token)) => {
                     tokens.push(token);
                     token_rest_args.push(popped_args.clone());
@@ -93,7 +294,7 @@ macro_rules! _parse_prefix {
                     attachment = new_attachment;
                 },
                 Err(e) => {
-                    // No `$error = e`, because e.g. parsing into a Vec<Attachment> parameter with
+                    // No `$error.record(e)`, because e.g. parsing into a Vec<Attachment> parameter with
                     // spare arguments would cause the error from the spare arguments to be the
                     // Attachment parse error ("missing attachment"), which is confusing
                     break;
@@ -104,7 +305,7 @@ macro_rules! _parse_prefix {
 
         // This will run at least once
         while let Some(token_rest_args) = token_rest_args.pop() {
-            $crate::_parse_prefix!($ctx $msg token_rest_args attachment => [ $error $($preamble)* tokens ] $($rest)* );
+            $crate::_parse_prefix!($ctx $msg $tokenizer token_rest_args attachment => [ $error $($preamble)* tokens ] $($rest)* );
             tokens.pop();
         }
     };
@@ -113,54 +314,86 @@ macro_rules! _parse_prefix {
     // inconsistency and also the further implementation work makes it not worth it.
 
     // Consume #[rest] T as the last argument
-    ( $ctx:ident $msg:ident $args:ident $attachment_index:ident => [ $error:ident $($preamble:tt)* ]
+    ( $ctx:ident $msg:ident $tokenizer:ident $args:ident $attachment_index:ident => [ $error:ident $($preamble:tt)* ]
         // question to my former self: why the $(poise::)* ?
         (#[rest] $(poise::)* $type:ty)
     ) => {
         let input = $args.trim_start();
         if input.is_empty() {
-            $error = ($crate::TooFewArguments.into(), None);
+            $error.record(
+                ($crate::TooFewArguments.into(), None),
+                Some($crate::_count_prefix_args!($($preamble)*)),
+                $args,
+                true,
+            );
         } else {
             match <$type as $crate::serenity_prelude::ArgumentConvert>::convert(
                 $ctx, $msg.guild_id, Some($msg.channel_id), input
             ).await {
                 Ok(token) => {
                     let $args = "";
-                    $crate::_parse_prefix!($ctx $msg $args $attachment_index => [ $error $($preamble)* token ]);
+                    $crate::_parse_prefix!($ctx $msg $tokenizer $args $attachment_index => [ $error $($preamble)* token ]);
                 },
-                Err(e) => $error = (e.into(), Some(input.to_owned())),
+                Err(e) => $error.record(
+                    (e.into(), Some(input.to_owned())),
+                    Some($crate::_count_prefix_args!($($preamble)*)),
+                    $args,
+                    true,
+                ),
             }
         }
     };
 
     // Consume #[flag] FLAGNAME
-    ( $ctx:ident $msg:ident $args:ident $attachment_index:ident => [ $error:ident $($preamble:tt)* ]
+    ( $ctx:ident $msg:ident $tokenizer:ident $args:ident $attachment_index:ident => [ $error:ident $($preamble:tt)* ]
         (#[flag] $name:literal)
         $( $rest:tt )*
     ) => {
-        match $crate::pop_prefix_argument!(String, &$args, $attachment_index, $ctx, $msg).await {
+        match $crate::pop_prefix_argument!(String, &$args, $attachment_index, $ctx, $msg, $tokenizer).await {
             Ok(($args, $attachment_index, token)) if token.eq_ignore_ascii_case($name) => {
-                $crate::_parse_prefix!($ctx $msg $args $attachment_index => [ $error $($preamble)* true ] $($rest)* );
+                $crate::_parse_prefix!($ctx $msg $tokenizer $args $attachment_index => [ $error $($preamble)* true ] $($rest)* );
             },
             // only allow backtracking if the flag didn't match: it's confusing for the user if they
             // precisely set the flag but it's ignored
             _ => {
-                $error = (concat!("Must use either `", $name, "` or nothing as a modifier").into(), None);
-                $crate::_parse_prefix!($ctx $msg $args $attachment_index => [ $error $($preamble)* false ] $($rest)* );
+                // A missing flag is fine, only complain about a word in its place
+                if !$args.trim_start().is_empty() {
+                    $error.record(
+                        (concat!("Must use either `", $name, "` or nothing as a modifier").into(), None),
+                        Some($crate::_count_prefix_args!($($preamble)*)),
+                        $args,
+                        false,
+                    );
+                }
+                $crate::_parse_prefix!($ctx $msg $tokenizer $args $attachment_index => [ $error $($preamble)* false ] $($rest)* );
             }
         }
     };
 
+    // Consume a #[flatten] parameter group. Its fields are parsed in one go, so backtracking into
+    // the group isn't possible
+    ( $ctx:ident $msg:ident $tokenizer:ident $args:ident $attachment_index:ident => [ $error:ident $($preamble:tt)* ]
+        (#[flatten] $type:ty as $group:path)
+        $( $rest:tt )*
+    ) => {
+        match <$type as $group>::pop_from(&$args, $attachment_index, $tokenizer, $ctx, $msg).await {
+            Ok(($args, $attachment_index, token)) => {
+                $crate::_parse_prefix!($ctx $msg $tokenizer $args $attachment_index => [ $error $($preamble)* token ] $($rest)* );
+            },
+            Err(e) => $error.record_group(e, $crate::_count_prefix_args!($($preamble)*), $args),
+        }
+    };
+
     // Consume T
-    ( $ctx:ident $msg:ident $args:ident $attachment_index:ident => [ $error:ident $($preamble:tt)* ]
+    ( $ctx:ident $msg:ident $tokenizer:ident $args:ident $attachment_index:ident => [ $error:ident $($preamble:tt)* ]
         ($type:ty)
         $( $rest:tt )*
     ) => {
-        match $crate::pop_prefix_argument!($type, &$args, $attachment_index, $ctx, $msg).await {
+        match $crate::pop_prefix_argument!($type, &$args, $attachment_index, $ctx, $msg, $tokenizer).await {
             Ok(($args, $attachment_index, token)) => {
-                $crate::_parse_prefix!($ctx $msg $args $attachment_index => [ $error $($preamble)* token ] $($rest)* );
+                $crate::_parse_prefix!($ctx $msg $tokenizer $args $attachment_index => [ $error $($preamble)* token ] $($rest)* );
             },
-            Err(e) => $error = e,
+            Err(e) => $error.record(e, Some($crate::_count_prefix_args!($($preamble)*)), $args, false),
         }
     };
 
@@ -175,6 +408,9 @@ Macro for parsing an argument string into multiple parameter types.
 An invocation of this macro is generated by the [`crate::command`] macro, so you usually don't need
 to use this macro directly.
 
+Words are split with the [`crate::Tokenizer`] optionally passed after the attachment index, like
+`parse_prefix_args!(ctx, msg, args, 0, &tokenizer => ...)`, or the default one.
+
 ```rust
 # // TODO: figure out some way to mock Context again to have this doctest actually execute
 # use poise::serenity_prelude as serenity;
@@ -210,7 +446,7 @@ assert_eq!(
 */
 #[macro_export]
 macro_rules! parse_prefix_args {
-    ($ctx:expr, $msg:expr, $args:expr, $attachment_index:expr => $(
+    ($ctx:expr, $msg:expr, $args:expr, $attachment_index:expr, $tokenizer:expr => $(
         $( #[$attr:ident] )?
         ( $($type:tt)* )
     ),* $(,)? ) => {
@@ -221,19 +457,24 @@ macro_rules! parse_prefix_args {
             let msg = $msg;
             let args = $args;
             let attachment_index = $attachment_index;
+            let tokenizer: &$crate::Tokenizer = $tokenizer;
 
-            let mut error: (Box<dyn std::error::Error + Send + Sync>, Option<String>)
-                = (Box::new($crate::TooManyArguments) as _, None);
+            let mut error = $crate::DeepestArgumentError::new(args, tokenizer);
 
             $crate::_parse_prefix!(
-                ctx msg args attachment_index => [error]
+                ctx msg tokenizer args attachment_index => [error]
                 $(
                     ($( #[$attr] )? $($type)*)
                 )*
             );
-            Err(error)
+            Err(error.into_error())
         }
     };
+    ($ctx:expr, $msg:expr, $args:expr, $attachment_index:expr => $( $rest:tt )* ) => {
+        $crate::parse_prefix_args!(
+            $ctx, $msg, $args, $attachment_index, &$crate::Tokenizer::default() => $( $rest )*
+        )
+    };
 }
 
 #[cfg(test)]
@@ -335,9 +576,19 @@ mod test {
             parse_prefix_args!(&ctx, &msg, "hello", 0 => #[flag] ("hello"), #[rest] (String))
                 .await
                 .unwrap_err()
-                .0
+                .error
                 .is::<crate::TooFewArguments>(),
         );
+        // The failure that got furthest into the input is reported
+        let error = parse_prefix_args!(&ctx, &msg, "1 x", 0 => (Option<u32>), (u32))
+            .await
+            .unwrap_err();
+        assert_eq!((error.parameter_index, error.span), (Some(1), 2..3));
+        let error = parse_prefix_args!(&ctx, &msg, "1 2 3 ", 0 => (u32), (u32))
+            .await
+            .unwrap_err();
+        assert!(error.error.is::<crate::TooManyArguments>());
+        assert_eq!((error.parameter_index, error.span), (None, 4..5));
         assert_eq!(
             parse_prefix_args!(&ctx, &msg, "helloo", 0 => #[flag] ("hello"), #[rest] (String))
                 .await
@@ -11,53 +11,16 @@ pub use key_value_args::*;
 mod macros;
 pub use macros::*;
 
+mod named_args;
+pub use named_args::*;
+
 mod argument_trait;
 pub use argument_trait::*;
 
-use crate::serenity_prelude as serenity;
-
-/// Pop a whitespace-separated word from the front of the arguments. Supports quotes and quote
-/// escaping.
-///
-/// Leading whitespace will be trimmed; trailing whitespace is not consumed.
-fn pop_string(args: &str) -> Result<(&str, String), crate::TooFewArguments> {
-    // TODO: consider changing the behavior to parse quotes literally if they're in the middle
-    // of the string:
-    // - `"hello world"` => `hello world`
-    // - `"hello "world"` => `"hello "world`
-    // - `"hello" world"` => `hello`
-
-    let args = args.trim_start();
-    if args.is_empty() {
-        return Err(crate::TooFewArguments);
-    }
-
-    let mut output = String::new();
-    let mut inside_string = false;
-    let mut escaping = false;
-
-    let mut chars = args.chars();
-    // .clone().next() is poor man's .peek(), but we can't do peekable because then we can't
-    // call as_str on the Chars iterator
-    while let Some(c) = chars.clone().next() {
-        if escaping {
-            output.push(c);
-            escaping = false;
-        } else if !inside_string && c.is_whitespace() {
-            break;
-        } else if c == '"' {
-            inside_string = !inside_string;
-        } else if c == '\\' {
-            escaping = true;
-        } else {
-            output.push(c);
-        }
-
-        chars.next();
-    }
+mod tokenizer;
+pub use tokenizer::*;
 
-    Ok((chars.as_str(), output))
-}
+use crate::serenity_prelude as serenity;
 
 /// Error thrown if user passes too many arguments to a command
 #[derive(Debug)]
@@ -113,6 +76,10 @@ impl std::error::Error for InvalidBool {}
 #[cfg(test)]
 #[test]
 fn test_pop_string() {
+    fn pop_string(args: &str) -> Result<(&str, String), crate::TooFewArguments> {
+        Tokenizer::default().pop_word(args)
+    }
+
     // Test that trailing whitespace is not consumed
     assert_eq!(pop_string("AA BB").unwrap().0, " BB");
 
@@ -0,0 +1,406 @@
+//! Parsing of POSIX-style named options like `--days 7`, `--reason="spam"` or `-s` in prefix
+//! commands
+
+use super::{PrefixArgumentError, Tokenizer, TooManyArguments};
+
+/// Error thrown when named options are used incorrectly
+#[derive(Debug)]
+pub enum NamedOptionError {
+    /// A required option wasn't passed
+    Missing(
+        /// The option, e.g. `--days`
+        String,
+    ),
+    /// An option which takes a value was passed without one
+    MissingValue(
+        /// The option as written by the user, e.g. `--days`
+        String,
+    ),
+    /// A flag, which doesn't take a value, was passed with one, like `--silent=yes`
+    UnexpectedValue(
+        /// The option as written by the user, e.g. `--silent`
+        String,
+    ),
+}
+impl std::fmt::Display for NamedOptionError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Missing(option) => write!(f, "Missing required option `{}`", option),
+            Self::MissingValue(option) => write!(f, "Option `{}` requires a value", option),
+            Self::UnexpectedValue(option) => write!(f, "Option `{}` doesn't take a value", option),
+        }
+    }
+}
+impl std::error::Error for NamedOptionError {}
+
+/// A named option of a prefix command. Generated by the [`crate::command`] macro for parameters
+/// marked `#[named]`, `#[long = "..."]` or `#[short = '.']`
+#[doc(hidden)]
+#[derive(Clone, Copy, Debug)]
+pub struct NamedOption {
+    /// Long name, used like `--name`
+    pub long: &'static str,
+    /// Short name, used like `-n`
+    pub short: Option<char>,
+    /// Whether the option takes a value. If not, it's a flag
+    pub takes_value: bool,
+    /// Position of the parameter in [`crate::Command::parameters`]
+    pub parameter_index: usize,
+}
+
+/// Named options extracted from a prefix command invocation by [`NamedArgs::extract`]
+#[doc(hidden)]
+#[derive(Debug)]
+pub struct NamedArgs {
+    /// The argument string with all named options removed. Use [`Self::restore_span`] to map the
+    /// span of a positional argument error back into the original argument string
+    pub positional: String,
+    /// For every removed part of the argument string, its offset in [`Self::positional`] and its
+    /// length, ordered by offset
+    removed: Vec<(usize, usize)>,
+    /// The options that were looked for
+    options: Vec<NamedOption>,
+    /// For every option, the values it was passed with as written by the user, i.e. still quoted
+    /// (empty for flags), and their spans
+    occurrences: Vec<Vec<(String, std::ops::Range<usize>)>>,
+}
+
+impl NamedArgs {
+    /// Extracts the given options from anywhere in the argument string
+    ///
+    /// Options are recognized in the forms `--long value`, `--long=value`, `-s value` and
+    /// `-svalue`; flags can be grouped like `-abc`. Unknown options are left in place as
+    /// positional arguments, so that e.g. negative numbers or `---` in free text keep working.
+    /// Everything after a lone `--` is positional. Quoted words are never interpreted as options.
+    pub fn extract(
+        args: &str,
+        tokenizer: &Tokenizer,
+        options: &[NamedOption],
+    ) -> Result<Self, PrefixArgumentError> {
+        let mut extracted = Vec::new();
+        let mut occurrences = vec![Vec::new(); options.len()];
+
+        let offset_of = |suffix: &str| args.len() - suffix.len();
+        let error =
+            |error: NamedOptionError, span: std::ops::Range<usize>, index| PrefixArgumentError {
+                error: error.into(),
+                input: None,
+                parameter_index: index,
+                group_field_index: None,
+                span,
+            };
+
+        let mut remaining = args;
+        while let Ok((rest, token)) = tokenizer.pop_word(remaining) {
+            let start = offset_of(remaining.trim_start());
+            let end = offset_of(rest);
+            let raw = &args[start..end];
+            remaining = rest;
+
+            if raw == "--" {
+                extracted.push(start..end);
+                break;
+            }
+
+            // Finds the value of an option that takes one: either attached to the option or the
+            // next word
+            let mut take_value = |attached: Option<&str>, option: &str| match attached {
+                Some(value) => Ok((value.to_owned(), start..end)),
+                None => match tokenizer.pop_word(remaining) {
+                    Ok((rest, _)) => {
+                        let value_span = offset_of(remaining.trim_start())..offset_of(rest);
+                        extracted.push(value_span.clone());
+                        remaining = rest;
+                        Ok((args[value_span.clone()].to_owned(), value_span))
+                    }
+                    Err(_) => Err(error(
+                        NamedOptionError::MissingValue(option.to_owned()),
+                        start..end,
+                        None,
+                    )),
+                },
+            };
+
+            if let Some(long) = raw.strip_prefix("--").and(token.strip_prefix("--")) {
+                let name = long.split('=').next().unwrap_or(long);
+                // The value is taken from the raw word, so that it's parsed with its quotes
+                let attached_value = raw.split_once('=').map(|(_, value)| value);
+                let option_index = match options.iter().position(|o| o.long == name) {
+                    Some(i) => i,
+                    None => continue,
+                };
+                let option = &options[option_index];
+
+                let occurrence = if option.takes_value {
+                    take_value(attached_value, &format!("--{}", name))?
+                } else if attached_value.is_some() {
+                    return Err(error(
+                        NamedOptionError::UnexpectedValue(format!("--{}", name)),
+                        start..end,
+                        Some(option.parameter_index),
+                    ));
+                } else {
+                    (String::new(), start..end)
+                };
+                occurrences[option_index].push(occurrence);
+            } else if let Some(shorts) = raw.strip_prefix('-').and(token.strip_prefix('-')) {
+                let first = match shorts.chars().next() {
+                    Some(c) => c,
+                    None => continue,
+                };
+                let find_short = |c| options.iter().position(|o| o.short == Some(c));
+                let option_index = match find_short(first) {
+                    Some(i) => i,
+                    None => continue,
+                };
+                let option = &options[option_index];
+
+                if option.takes_value {
+                    let attached_value = match raw[1..].strip_prefix(first) {
+                        Some(value) => value.strip_prefix('=').unwrap_or(value),
+                        // The option letter itself is quoted or escaped
+                        None => continue,
+                    };
+                    let attached_value = Some(attached_value).filter(|v| !v.is_empty());
+                    let occurrence = take_value(attached_value, &format!("-{}", first))?;
+                    occurrences[option_index].push(occurrence);
+                } else {
+                    // Grouped flags like `-sv`. If any of them isn't a flag, the whole word is
+                    // treated as a positional argument
+                    let flag_indices = shorts
+                        .chars()
+                        .map(|c| find_short(c).filter(|&i| !options[i].takes_value))
+                        .collect::<Option<Vec<_>>>();
+                    match flag_indices {
+                        Some(flag_indices) => {
+                            for i in flag_indices {
+                                occurrences[i].push((String::new(), start..end));
+                            }
+                        }
+                        None => continue,
+                    }
+                }
+            } else {
+                continue;
+            }
+            extracted.push(start..end);
+        }
+
+        let (positional, removed) = remove_ranges(args, extracted);
+        Ok(Self {
+            positional,
+            removed,
+            options: options.to_vec(),
+            occurrences,
+        })
+    }
+
+    /// Maps the span of an error from parsing [`Self::positional`] to the original argument string
+    pub fn restore_span(&self, mut error: PrefixArgumentError) -> PrefixArgumentError {
+        let restore = |offset: usize, include_removed_at_offset: bool| {
+            offset
+                + self
+                    .removed
+                    .iter()
+                    .take_while(|&&(at, _)| {
+                        at < offset || (include_removed_at_offset && at == offset)
+                    })
+                    .map(|&(_, len)| len)
+                    .sum::<usize>()
+        };
+        let start = restore(error.span.start, true);
+        let end = restore(error.span.end, false);
+        error.span = start..end.max(start);
+        error
+    }
+
+    /// Whether the flag at the given index was passed
+    pub fn is_present(&self, option_index: usize) -> bool {
+        !self.occurrences[option_index].is_empty()
+    }
+
+    /// Returns the values passed to the option at the given index, as written by the user, and
+    /// their spans. Parse them with [`crate::parse_named_argument!`]
+    pub fn values(
+        &self,
+        option_index: usize,
+    ) -> impl Iterator<Item = (&str, std::ops::Range<usize>)> {
+        self.occurrences[option_index]
+            .iter()
+            .map(|(value, span)| (value.as_str(), span.clone()))
+    }
+
+    /// Turns the result of parsing a value of the option at the given index with
+    /// [`crate::pop_prefix_argument!`] into the parsed value. Fails if not the whole value was
+    /// consumed
+    pub fn check_value<T>(
+        &self,
+        option_index: usize,
+        span: std::ops::Range<usize>,
+        result: Result<
+            (&str, usize, T),
+            (Box<dyn std::error::Error + Send + Sync>, Option<String>),
+        >,
+    ) -> Result<T, PrefixArgumentError> {
+        let (error, input) = match result {
+            Ok((rest, _, value)) if rest.trim().is_empty() => return Ok(value),
+            Ok((rest, _, _)) => (TooManyArguments.into(), Some(rest.trim().to_owned())),
+            Err(error) => error,
+        };
+        Err(PrefixArgumentError {
+            error,
+            input,
+            parameter_index: Some(self.options[option_index].parameter_index),
+            group_field_index: None,
+            span,
+        })
+    }
+
+    /// Unwraps the value of a required option, failing if the option wasn't passed. `value` is
+    /// the last value parsed with [`crate::parse_named_argument!`]
+    pub fn required<T>(
+        &self,
+        option_index: usize,
+        value: Option<T>,
+    ) -> Result<T, PrefixArgumentError> {
+        let option = &self.options[option_index];
+        let end = self.positional.len();
+        value.ok_or_else(|| {
+            self.restore_span(PrefixArgumentError {
+                error: NamedOptionError::Missing(format!("--{}", option.long)).into(),
+                input: None,
+                parameter_index: Some(option.parameter_index),
+                group_field_index: None,
+                span: end..end,
+            })
+        })
+    }
+}
+
+/// Parses all values passed to a named option with [`crate::pop_prefix_argument!`], so that named
+/// options support the same types as positional ones. Evaluates to a future of
+/// `Result<Vec<T>, PrefixArgumentError>`
+///
+/// Used in the desugared [`crate::command`] macro
+#[doc(hidden)]
+#[macro_export]
+macro_rules! parse_named_argument {
+    ($target:ty, $named_args:expr, $option_index:expr, $ctx:expr, $msg:expr, $tokenizer:expr) => {
+        async {
+            let named_args: &$crate::NamedArgs = $named_args;
+            let mut values = Vec::<$target>::new();
+            for (value, span) in named_args.values($option_index) {
+                let result =
+                    $crate::pop_prefix_argument!($target, value, 0, $ctx, $msg, $tokenizer).await;
+                values.push(named_args.check_value($option_index, span, result)?);
+            }
+            Ok::<_, $crate::PrefixArgumentError>(values)
+        }
+    };
+}
+
+/// Removes the given ranges from the argument string, together with the whitespace after each
+/// range (or before it, at the end of the string), so that no gaps remain. Returns the remaining
+/// string and where and how much was removed, see [`NamedArgs::removed`]
+fn remove_ranges(
+    args: &str,
+    mut ranges: Vec<std::ops::Range<usize>>,
+) -> (String, Vec<(usize, usize)>) {
+    // Values taken from the next word are found before their option is recorded
+    ranges.sort_by_key(|range| range.start);
+    let mut merged: Vec<std::ops::Range<usize>> = Vec::new();
+    for mut range in ranges {
+        range.end = args.len() - args[range.end..].trim_start().len();
+        match merged.last_mut() {
+            Some(last) if last.end >= range.start => last.end = last.end.max(range.end),
+            _ => merged.push(range),
+        }
+    }
+    if let Some(last) = merged.last_mut().filter(|last| last.end == args.len()) {
+        last.start = args[..last.start].trim_end().len();
+    }
+
+    let mut positional = String::with_capacity(args.len());
+    let mut removed = Vec::new();
+    let mut kept_from = 0;
+    for range in merged {
+        positional.push_str(&args[kept_from..range.start]);
+        removed.push((positional.len(), range.len()));
+        kept_from = range.end;
+    }
+    positional.push_str(&args[kept_from..]);
+    (positional, removed)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_extract() {
+        let option = |long, short, takes_value| NamedOption {
+            long,
+            short,
+            takes_value,
+            parameter_index: 0,
+        };
+        let options = [
+            option("days", Some('d'), true),
+            option("silent", Some('s'), false),
+            option("verbose", Some('v'), false),
+            option("reason", None, true),
+        ];
+        let tokenizer = Tokenizer::default();
+        fn values(named: &NamedArgs, i: usize) -> Vec<&str> {
+            named.occurrences[i]
+                .iter()
+                .map(|(value, _)| value.as_str())
+                .collect()
+        }
+
+        let args = r#"@user --reason "spam bot" -sv -5 --days=7 "-d" -d3"#;
+        let named = NamedArgs::extract(args, &tokenizer, &options).unwrap();
+        assert_eq!(named.positional, r#"@user -5 "-d""#);
+        assert_eq!(values(&named, 0), ["7", "3"]);
+        assert!(named.is_present(1) && named.is_present(2));
+        assert_eq!(values(&named, 3), [r#""spam bot""#]);
+        assert_eq!(named.occurrences[3][0].1, 15..25);
+
+        // Spans in the positional arguments are mapped back to the original arguments
+        let span_of = |span| {
+            let error = PrefixArgumentError {
+                error: TooManyArguments.into(),
+                input: None,
+                parameter_index: None,
+                group_field_index: None,
+                span,
+            };
+            named.restore_span(error).span
+        };
+        assert_eq!(&args[span_of(6..8)], "-5");
+        assert_eq!(&args[span_of(9..13)], r#""-d""#);
+        assert_eq!(span_of(13..13), args.len()..args.len());
+
+        // No gaps are left in the positional arguments, e.g. for #[rest] parameters
+        let named = NamedArgs::extract("@u being --silent rude -s", &tokenizer, &options).unwrap();
+        assert_eq!(named.positional, "@u being rude");
+
+        let named = NamedArgs::extract("a -- --days 7", &tokenizer, &options).unwrap();
+        assert_eq!(named.positional, "a --days 7");
+        assert!(!named.is_present(0));
+
+        // Options which take their value from the next word are removed along with it
+        let named = NamedArgs::extract("a --reason spam b", &tokenizer, &options).unwrap();
+        assert_eq!(named.positional, "a b");
+        assert_eq!(values(&named, 3), ["spam"]);
+        let named = NamedArgs::extract("a -d 7 b -d 8", &tokenizer, &options).unwrap();
+        assert_eq!(named.positional, "a b");
+        assert_eq!(values(&named, 0), ["7", "8"]);
+
+        // Unknown long options are positional, like unknown short options
+        let named = NamedArgs::extract("a --dyas 7 ---", &tokenizer, &options).unwrap();
+        assert_eq!(named.positional, "a --dyas 7 ---");
+        assert!(NamedArgs::extract("--days", &tokenizer, &options).is_err());
+        assert!(NamedArgs::extract("--silent=yes", &tokenizer, &options).is_err());
+    }
+}
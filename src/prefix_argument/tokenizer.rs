@@ -0,0 +1,219 @@
+//! Splitting of prefix command arguments into words, with configurable quoting and escaping
+//! rules
+
+/// How [`Tokenizer`] treats quotes and backslashes
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum TokenizerMode {
+    /// Quotes can start and end anywhere in a word and a backslash escapes any character, also
+    /// inside quotes. For example, `"hello "world` is read as `hello world`
+    Lenient,
+    /// Like a POSIX shell: single-quoted text is taken literally, and inside other quotes a
+    /// backslash only escapes the closing quote and itself. Outside quotes, a backslash escapes
+    /// any character
+    Shell,
+    /// Words are separated by whitespace. Quotes and backslashes have no special meaning
+    Raw,
+}
+
+/// Rules for splitting prefix command arguments into words, see
+/// [`crate::PrefixFrameworkOptions::tokenizer`]
+///
+/// The default is [`TokenizerMode::Lenient`] with only `"` as quote character.
+///
+/// ```rust
+/// let tokenizer = poise::Tokenizer {
+///     quotes: poise::Tokenizer::SMART_QUOTES,
+///     single_quotes: true,
+///     ..Default::default()
+/// };
+/// assert_eq!(
+///     tokenizer.pop_word("“hello world” 'foo bar'").unwrap(),
+///     (" 'foo bar'", "hello world".to_owned())
+/// );
+/// ```
+#[derive(Clone, Copy, Debug)]
+pub struct Tokenizer {
+    /// How quotes and backslashes are treated
+    pub mode: TokenizerMode,
+    /// Pairs of opening and closing characters which group multiple words into one
+    pub quotes: &'static [(char, char)],
+    /// Whether `'...'` and `‘...’` group words too. To keep apostrophes in words like `don't`
+    /// intact, single quotes only start a quote at the beginning of a word
+    pub single_quotes: bool,
+    #[doc(hidden)]
+    pub __non_exhaustive: (),
+}
+
+impl Default for Tokenizer {
+    fn default() -> Self {
+        Self {
+            mode: TokenizerMode::Lenient,
+            quotes: Self::DOUBLE_QUOTES,
+            single_quotes: false,
+            __non_exhaustive: (),
+        }
+    }
+}
+
+impl Tokenizer {
+    /// Only ASCII double quotes: `"..."`
+    pub const DOUBLE_QUOTES: &'static [(char, char)] = &[('"', '"')];
+    /// ASCII double quotes plus the typographic quotes inserted by mobile keyboards and used in
+    /// other languages: `“...”`, `„...“` and `«...»`
+    pub const SMART_QUOTES: &'static [(char, char)] =
+        &[('"', '"'), ('“', '”'), ('„', '“'), ('«', '»')];
+    /// Quote pairs enabled by [`Self::single_quotes`]
+    const SINGLE_QUOTES: &'static [(char, char)] = &[('\'', '\''), ('‘', '’')];
+
+    /// Words are only separated by whitespace, see [`TokenizerMode::Raw`]
+    pub fn raw() -> Self {
+        Self {
+            mode: TokenizerMode::Raw,
+            ..Default::default()
+        }
+    }
+
+    /// Shell-like quoting with single and double quotes, see [`TokenizerMode::Shell`]
+    pub fn shell() -> Self {
+        Self {
+            mode: TokenizerMode::Shell,
+            single_quotes: true,
+            ..Default::default()
+        }
+    }
+
+    /// Pops a word from the front of the arguments, resolving quotes and escapes.
+    ///
+    /// Leading whitespace will be trimmed; trailing whitespace is not consumed. Returns the
+    /// remaining arguments and the word.
+    pub fn pop_word<'a>(&self, args: &'a str) -> Result<(&'a str, String), crate::TooFewArguments> {
+        let args = args.trim_start();
+        if args.is_empty() {
+            return Err(crate::TooFewArguments);
+        }
+
+        Ok(self.pop_until(args, char::is_whitespace))
+    }
+
+    /// Reads from the front of `args` until the first character outside of quotes and escapes for
+    /// which `is_end` returns true. Returns the rest of the string, starting at that character, and
+    /// the text read with quotes and escapes resolved
+    pub(super) fn pop_until<'a>(
+        &self,
+        args: &'a str,
+        is_end: impl Fn(char) -> bool,
+    ) -> (&'a str, String) {
+        // TODO: consider changing the lenient behavior to parse quotes literally if they're in the
+        // middle of the string:
+        // - `"hello world"` => `hello world`
+        // - `"hello "world"` => `"hello "world`
+        // - `"hello" world"` => `hello`
+
+        let mut output = String::new();
+        // Closing character of the current quote and whether the quote is taken literally
+        let mut quote: Option<(char, bool)> = None;
+        let mut escaping = false;
+
+        let mut chars = args.chars();
+        // .clone().next() is poor man's .peek(), but we can't do peekable because then we can't
+        // call as_str on the Chars iterator
+        while let Some(c) = chars.clone().next() {
+            let at_word_start = chars.as_str().len() == args.len();
+            if escaping {
+                let escapable = match quote {
+                    Some((closing, _)) if self.mode == TokenizerMode::Shell => {
+                        c == closing || c == '\\'
+                    }
+                    _ => true,
+                };
+                if !escapable {
+                    output.push('\\');
+                }
+                output.push(c);
+                escaping = false;
+            } else if let Some((closing, literal)) = quote {
+                if c == closing {
+                    quote = None;
+                } else if c == '\\' && !literal {
+                    escaping = true;
+                } else {
+                    output.push(c);
+                }
+            } else if self.mode != TokenizerMode::Raw && c == '\\' {
+                escaping = true;
+            } else if let Some(opened) = self.open_quote(c, at_word_start) {
+                quote = Some(opened);
+            } else if is_end(c) {
+                break;
+            } else {
+                output.push(c);
+            }
+
+            chars.next();
+        }
+
+        (chars.as_str(), output)
+    }
+
+    /// If `c` opens a quote, returns the closing character and whether the quote is taken
+    /// literally
+    fn open_quote(&self, c: char, at_word_start: bool) -> Option<(char, bool)> {
+        if self.mode == TokenizerMode::Raw {
+            return None;
+        }
+        if let Some(&(_, closing)) = self.quotes.iter().find(|&&(opening, _)| opening == c) {
+            return Some((closing, false));
+        }
+        if self.single_quotes && at_word_start {
+            if let Some(&(_, closing)) = Self::SINGLE_QUOTES.iter().find(|&&(o, _)| o == c) {
+                return Some((closing, self.mode == TokenizerMode::Shell));
+            }
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_tokenizer_modes() {
+        let words = |tokenizer: Tokenizer, mut args: &str| {
+            let mut words = Vec::new();
+            while let Ok((rest, word)) = tokenizer.pop_word(args) {
+                words.push(word);
+                args = rest;
+            }
+            words
+        };
+
+        let smart = Tokenizer {
+            quotes: Tokenizer::SMART_QUOTES,
+            ..Default::default()
+        };
+        assert_eq!(
+            words(smart, "“a b” „c d“ «e f» 'g h'"),
+            ["a b", "c d", "e f", "'g", "h'"]
+        );
+
+        let single = Tokenizer {
+            single_quotes: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            words(single, r#"'a b' don't ‘c d’ 'e\'f'"#),
+            ["a b", "don't", "c d", "e'f"]
+        );
+
+        assert_eq!(
+            words(Tokenizer::raw(), r#""a b" c\ d"#),
+            [r#""a"#, r#"b""#, r#"c\"#, "d"]
+        );
+
+        assert_eq!(
+            words(Tokenizer::shell(), r#"'a\b' "c\d\"\\" e\ f"#),
+            [r#"a\b"#, r#"c\d"\"#, "e f"]
+        );
+    }
+}
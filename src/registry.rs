@@ -0,0 +1,360 @@
+//! Runtime changes to the command list: disabling, enabling, adding and removing commands while
+//! the bot runs
+
+use crate::serenity_prelude as serenity;
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, RwLock};
+
+/// Where a command was disabled via [`CommandRegistry::disable`]
+#[derive(Default, Debug)]
+struct DisabledIn {
+    /// Disabled everywhere
+    globally: bool,
+    /// Disabled only in these guilds
+    guilds: HashSet<serenity::GuildId>,
+}
+
+/// A command added at runtime via [`CommandRegistry::insert`]. Node of an append-only linked list
+#[derive(derivative::Derivative)]
+#[derivative(Debug(bound = ""))]
+struct RuntimeCommand<U, E> {
+    /// The added command
+    command: crate::Command<U, E>,
+    /// Set when this command is removed or replaced
+    removed: AtomicBool,
+    /// The command that was added after this one
+    next: once_cell::sync::OnceCell<Box<RuntimeCommand<U, E>>>,
+}
+
+/// Runtime view onto the command list, stored in [`crate::FrameworkOptions::command_registry`]
+///
+/// [`crate::FrameworkOptions::commands`] is fixed once the framework has started. This registry
+/// allows you to:
+/// - disable and re-enable commands by their qualified name, either globally or per guild.
+///   Invoking a disabled command yields [`crate::FrameworkError::CommandDisabled`]
+/// - add commands (or replace existing ones) and remove commands while the bot runs
+///
+/// ```rust,no_run
+/// # async fn _test(ctx: poise::Context<'_, (), ()>) {
+/// let registry = &ctx.framework().options().command_registry;
+/// // The `config set` subcommand is broken, turn it off until the fix is deployed
+/// registry.disable("config set", None);
+/// // Nobody in this guild may use `ban` anymore
+/// registry.disable("ban", ctx.guild_id());
+/// # }
+/// ```
+///
+/// Added and removed commands also change which application commands should be registered on
+/// Discord. Call [`Self::resync`] to bring the registered application commands up to date.
+///
+/// Removed commands are kept in memory until the framework is dropped, because invocations that
+/// are still running may reference them.
+#[derive(derivative::Derivative)]
+#[derivative(Debug(bound = ""))]
+pub struct CommandRegistry<U, E> {
+    /// Qualified command names mapped to where they are disabled
+    disabled: RwLock<HashMap<String, DisabledIn>>,
+    /// Names of top-level commands in [`crate::FrameworkOptions::commands`] that were removed
+    removed: RwLock<HashSet<String>>,
+    /// Names of startup commands which are removed or replaced by a command added at runtime.
+    /// Recomputed on every change, so that [`Self::commands`] only needs a single lookup per
+    /// command
+    hidden_startup_commands: RwLock<Arc<HashSet<String>>>,
+    /// Head of the list of commands added at runtime
+    runtime_commands: once_cell::sync::OnceCell<Box<RuntimeCommand<U, E>>>,
+    /// Whether commands were added or removed since the last [`Self::resync`]
+    needs_resync: AtomicBool,
+}
+
+impl<U, E> Default for CommandRegistry<U, E> {
+    fn default() -> Self {
+        Self {
+            disabled: RwLock::default(),
+            removed: RwLock::default(),
+            hidden_startup_commands: RwLock::default(),
+            runtime_commands: once_cell::sync::OnceCell::new(),
+            needs_resync: AtomicBool::new(false),
+        }
+    }
+}
+
+impl<U, E> CommandRegistry<U, E> {
+    /// Disables the command with the given qualified name (e.g. `config set`), including all its
+    /// subcommands. With `guild_id` set to `None`, the command is disabled everywhere
+    pub fn disable(&self, qualified_name: &str, guild_id: Option<serenity::GuildId>) {
+        let mut disabled = self.disabled.write().unwrap();
+        let entry = disabled.entry(qualified_name.to_owned()).or_default();
+        match guild_id {
+            Some(guild_id) => {
+                entry.guilds.insert(guild_id);
+            }
+            None => entry.globally = true,
+        }
+    }
+
+    /// Reverts [`Self::disable`] for the given qualified command name. With `guild_id` set to
+    /// `None`, reverts disabling the command everywhere; guild-specific disables are kept
+    pub fn enable(&self, qualified_name: &str, guild_id: Option<serenity::GuildId>) {
+        let mut disabled = self.disabled.write().unwrap();
+        if let Some(entry) = disabled.get_mut(qualified_name) {
+            match guild_id {
+                Some(guild_id) => {
+                    entry.guilds.remove(&guild_id);
+                }
+                None => entry.globally = false,
+            }
+            if !entry.globally && entry.guilds.is_empty() {
+                disabled.remove(qualified_name);
+            }
+        }
+    }
+
+    /// Whether the command with the given qualified name, or any of its parent commands, is
+    /// disabled in the given guild (or in DMs, if `guild_id` is `None`)
+    pub fn is_disabled(&self, qualified_name: &str, guild_id: Option<serenity::GuildId>) -> bool {
+        let disabled = self.disabled.read().unwrap();
+        if disabled.is_empty() {
+            return false;
+        }
+
+        // `config set value` is also disabled by disabling `config` or `config set`
+        crate::util::qualified_name_prefixes(qualified_name)
+            .filter_map(|name| disabled.get(name))
+            .any(|entry| entry.globally || guild_id.map_or(false, |id| entry.guilds.contains(&id)))
+    }
+
+    /// Adds a top-level command, replacing any command of the same name
+    ///
+    /// The command's qualified names are filled in like [`crate::Framework`] does on startup.
+    pub fn insert(&self, mut command: crate::Command<U, E>) {
+        crate::set_qualified_names(std::slice::from_mut(&mut command));
+        // Held until the end to serialize changes, so concurrent ones can't leave a stale
+        // snapshot behind
+        let removed = self.removed.write().unwrap();
+        self.remove_runtime_command(&command.name);
+
+        let mut node = Some(Box::new(RuntimeCommand {
+            command,
+            removed: AtomicBool::new(false),
+            next: once_cell::sync::OnceCell::new(),
+        }));
+        // Append to the end of the list. If the slot is already taken (possibly by a concurrent
+        // insert), move on to the next one
+        let mut slot = &self.runtime_commands;
+        loop {
+            let occupant = slot.get_or_init(|| node.take().expect("only taken once"));
+            if node.is_none() {
+                break;
+            }
+            slot = &occupant.next;
+        }
+
+        self.update_hidden_startup_commands(&removed);
+        self.needs_resync.store(true, Ordering::SeqCst);
+    }
+
+    /// Removes the top-level command with the given name, whether it was supplied in
+    /// [`crate::FrameworkOptions::commands`] or added via [`Self::insert`]
+    pub fn remove(&self, name: &str) {
+        let mut removed = self.removed.write().unwrap();
+        self.remove_runtime_command(name);
+        removed.insert(name.to_owned());
+        self.update_hidden_startup_commands(&removed);
+        self.needs_resync.store(true, Ordering::SeqCst);
+    }
+
+    /// Recomputes [`Self::hidden_startup_commands`]. Must be called while holding the write lock
+    /// of [`Self::removed`]
+    fn update_hidden_startup_commands(&self, removed: &HashSet<String>) {
+        let mut hidden = removed.clone();
+        hidden.extend(
+            self.runtime_nodes()
+                .filter(|node| !node.removed.load(Ordering::SeqCst))
+                .map(|node| node.command.name.clone()),
+        );
+        *self.hidden_startup_commands.write().unwrap() = Arc::new(hidden);
+    }
+
+    /// Marks all commands added at runtime with the given name as removed
+    fn remove_runtime_command(&self, name: &str) {
+        for node in self.runtime_nodes() {
+            if node.command.name == name {
+                node.removed.store(true, Ordering::SeqCst);
+            }
+        }
+    }
+
+    /// Iterates the list of commands added at runtime, including removed ones
+    fn runtime_nodes(&self) -> impl Iterator<Item = &RuntimeCommand<U, E>> {
+        std::iter::successors(self.runtime_commands.get(), |node| node.next.get())
+            .map(|node| &**node)
+    }
+
+    /// Returns all top-level commands that are currently active: the commands added at runtime,
+    /// followed by the given startup commands (usually [`crate::FrameworkOptions::commands`])
+    /// minus the removed ones
+    ///
+    /// Disabled commands are included, since they still exist and are merely refused on
+    /// invocation.
+    pub fn commands<'a>(
+        &'a self,
+        startup_commands: &'a [crate::Command<U, E>],
+    ) -> impl Iterator<Item = &'a crate::Command<U, E>> + 'a {
+        let runtime_commands = self
+            .runtime_nodes()
+            .filter(|node| !node.removed.load(Ordering::SeqCst))
+            .map(|node| &node.command);
+        let hidden = self.hidden_startup_commands.read().unwrap().clone();
+        let startup_commands = startup_commands
+            .iter()
+            .filter(move |command| !hidden.contains(&command.name));
+        runtime_commands.chain(startup_commands)
+    }
+
+    /// Whether commands were added or removed since the last [`Self::resync`]
+    pub fn needs_resync(&self) -> bool {
+        self.needs_resync.load(Ordering::SeqCst)
+    }
+
+    /// Syncs the currently active application commands with Discord, either globally or in the
+    /// given guild, using [`crate::builtins::sync_globally`] or [`crate::builtins::sync_in_guild`]
+    /// semantics
+    ///
+    /// If syncing fails, [`Self::needs_resync`] stays set.
+    pub async fn resync(
+        &self,
+        http: impl AsRef<serenity::Http>,
+        startup_commands: &[crate::Command<U, E>],
+        guild_id: Option<serenity::GuildId>,
+    ) -> Result<crate::builtins::CommandSyncReport, crate::builtins::CommandSyncError> {
+        // Reset before syncing, so that changes made in the meantime aren't lost
+        self.needs_resync.store(false, Ordering::SeqCst);
+        let builders =
+            crate::builtins::create_application_commands(self.commands(startup_commands));
+        let result =
+            crate::builtins::sync_application_commands(http.as_ref(), builders, guild_id, false)
+                .await;
+        if result.is_err() {
+            self.needs_resync.store(true, Ordering::SeqCst);
+        }
+        result
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn command(name: &str) -> crate::Command<(), ()> {
+        crate::Command {
+            name: name.into(),
+            qualified_name: name.into(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_disable() {
+        let registry = CommandRegistry::<(), ()>::default();
+        let guild = serenity::GuildId::new(1);
+
+        registry.disable("config", Some(guild));
+        assert!(registry.is_disabled("config set", Some(guild)));
+        assert!(!registry.is_disabled("config set", None));
+        assert!(!registry.is_disabled("configure", Some(guild)));
+
+        registry.disable("config set", None);
+        registry.enable("config", Some(guild));
+        assert!(registry.is_disabled("config set", Some(guild)));
+        assert!(!registry.is_disabled("config get", Some(guild)));
+    }
+
+    #[test]
+    fn test_insert_remove() {
+        let registry = CommandRegistry::<(), ()>::default();
+        let startup = vec![command("ping"), command("help")];
+        let names = || {
+            registry
+                .commands(&startup)
+                .map(|c| c.name.as_str())
+                .collect::<Vec<_>>()
+        };
+
+        registry.insert(command("echo"));
+        registry.insert(command("ping"));
+        assert_eq!(names(), ["echo", "ping", "help"]);
+        assert!(registry.needs_resync());
+
+        registry.remove("ping");
+        registry.remove("echo");
+        assert_eq!(names(), ["help"]);
+
+        registry.insert(command("echo"));
+        assert_eq!(names(), ["echo", "help"]);
+    }
+
+    #[tokio::test]
+    async fn test_dispatch_disabled_and_inserted() {
+        let ping = crate::Command {
+            prefix_action: Some(crate::testing::pong),
+            ..command("ping")
+        };
+        let harness =
+            crate::testing::TestHarness::with_prefix_commands(vec![ping], Default::default(), ())
+                .await;
+        let registry = &harness.framework().options.command_registry;
+        let replies = || {
+            let replies = harness.replies();
+            harness.clear();
+            replies
+                .into_iter()
+                .map(|reply| reply.content.unwrap_or_default())
+                .collect::<Vec<_>>()
+        };
+
+        registry.disable("ping", None);
+        harness.dispatch_message(harness.message("~ping")).await;
+        assert_eq!(replies(), ["This command is currently disabled."]);
+
+        registry.enable("ping", None);
+        registry.insert(crate::Command {
+            prefix_action: Some(crate::testing::pong),
+            ..command("echo")
+        });
+        harness.dispatch_message(harness.message("~ping")).await;
+        harness.dispatch_message(harness.message("~echo")).await;
+        assert_eq!(replies(), ["Pong!", "Pong!"]);
+
+        registry.remove("ping");
+        harness.dispatch_message(harness.message("~ping")).await;
+        assert!(replies().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_disabled_skipped_for_owners() {
+        let harness = crate::testing::TestHarness::with_prefix_commands(
+            vec![crate::Command {
+                prefix_action: Some(crate::testing::pong),
+                ..command("ping")
+            }],
+            crate::FrameworkOptions {
+                owners: std::iter::once(crate::testing::AUTHOR_ID).collect(),
+                skip_checks_for_owners: true,
+                ..Default::default()
+            },
+            (),
+        )
+        .await;
+
+        harness
+            .framework()
+            .options
+            .command_registry
+            .disable("ping", None);
+        harness.dispatch_message(harness.message("~ping")).await;
+        let replies = harness.replies();
+        assert_eq!(replies.len(), 1);
+        assert_eq!(replies[0].content.as_deref(), Some("Pong!"));
+    }
+}
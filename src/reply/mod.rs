@@ -3,6 +3,9 @@
 mod builder;
 pub use builder::*;
 
+mod response_registry;
+pub use response_registry::*;
+
 mod send_reply;
 pub use send_reply::*;
 
@@ -29,6 +32,14 @@ enum ReplyHandleInner<'a> {
     /// Reply was attempted to be sent in autocomplete context, resulting in a no-op. Calling
     /// methods on this variant will panic
     Autocomplete,
+    /// Reply was captured by a [`crate::testing::Recorder`] instead of being sent
+    #[cfg(any(test, feature = "testing"))]
+    Recorded {
+        /// Recorder that captured the reply. Edits and deletions are recorded here too
+        recorder: std::sync::Arc<crate::testing::Recorder>,
+        /// Index of the reply in the recorder
+        index: usize,
+    },
 }
 
 /// Returned from [`send_reply()`] to operate on the sent message
@@ -60,6 +71,11 @@ impl ReplyHandle<'_> {
                 interaction,
                 followup: None,
             } => interaction.get_response(http).await,
+            #[cfg(any(test, feature = "testing"))]
+            Recorded { recorder, index } => Ok(crate::testing::recorded_message(
+                index,
+                recorder.reply(index),
+            )),
             Autocomplete => panic!("reply is a no-op in autocomplete context"),
         }
     }
@@ -85,6 +101,11 @@ impl ReplyHandle<'_> {
                 interaction,
                 followup: None,
             } => Ok(Cow::Owned(interaction.get_response(http).await?)),
+            #[cfg(any(test, feature = "testing"))]
+            Recorded { recorder, index } => Ok(Cow::Owned(crate::testing::recorded_message(
+                *index,
+                recorder.reply(*index),
+            ))),
             Autocomplete => panic!("reply is a no-op in autocomplete context"),
         }
     }
@@ -124,6 +145,13 @@ impl ReplyHandle<'_> {
                     .edit_followup(http, msg.id, reply.to_slash_followup_response())
                     .await?;
             }
+            #[cfg(any(test, feature = "testing"))]
+            ReplyHandleInner::Recorded { recorder, index } => {
+                recorder.record(crate::testing::RecordedAction::Edit {
+                    index: *index,
+                    reply,
+                });
+            }
             ReplyHandleInner::Autocomplete => panic!("reply is a no-op in autocomplete context"),
         }
         Ok(())
@@ -147,6 +175,10 @@ impl ReplyHandle<'_> {
                     interaction.delete_response(ctx.discord()).await?;
                 }
             },
+            #[cfg(any(test, feature = "testing"))]
+            ReplyHandleInner::Recorded { recorder, index } => {
+                recorder.record(crate::testing::RecordedAction::Delete { index: *index });
+            }
             ReplyHandleInner::Autocomplete => panic!("delete is a no-op in autocomplete context"),
         }
         Ok(())
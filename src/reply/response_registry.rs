@@ -0,0 +1,386 @@
+//! Remembers the responses of command invocations so they can be edited later, see
+//! [`ResponseRegistry`]
+
+use crate::serenity_prelude as serenity;
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+/// How long Discord accepts interaction tokens, counted from the creation of the interaction
+const INTERACTION_TOKEN_LIFETIME_SECS: i64 = 15 * 60;
+
+/// Private enum so we can extend, split apart, or merge variants without breaking changes
+#[derive(Clone, derivative::Derivative)]
+#[derivative(Debug)]
+enum TrackedResponseInner {
+    /// A reply sent to a prefix command, i.e. a normal standalone message
+    Message {
+        /// Channel of the message
+        channel_id: serenity::ChannelId,
+        /// ID of the message
+        message_id: serenity::MessageId,
+    },
+    /// The initial response to an application command. Discord doesn't return its message ID
+    InteractionResponse {
+        /// Token of the interaction
+        #[derivative(Debug = "ignore")]
+        token: String,
+    },
+    /// A followup response to an application command
+    Followup {
+        /// Token of the interaction
+        #[derivative(Debug = "ignore")]
+        token: String,
+        /// ID of the followup message
+        message_id: serenity::MessageId,
+    },
+}
+
+/// An owned reference to a reply sent by a command invocation, retrieved from a
+/// [`ResponseRegistry`]
+///
+/// Unlike [`crate::ReplyHandle`], this is not bound to the lifetime of the invocation, so it can be
+/// used to edit a response from a background task. Responses to application commands can only be
+/// edited until the interaction token expires, 15 minutes after the invocation.
+#[derive(Clone, Debug)]
+pub struct TrackedResponse {
+    /// Where the response is and how to access it
+    inner: TrackedResponseInner,
+    /// Unix timestamp after which the registry forgets this response
+    expires_at: i64,
+}
+
+impl TrackedResponse {
+    /// Returns the ID of the response message, or `None` for the initial response to an
+    /// application command, whose ID Discord doesn't return. Use [`Self::message`] in that case
+    pub fn message_id(&self) -> Option<serenity::MessageId> {
+        match &self.inner {
+            TrackedResponseInner::Message { message_id, .. }
+            | TrackedResponseInner::Followup { message_id, .. } => Some(*message_id),
+            TrackedResponseInner::InteractionResponse { .. } => None,
+        }
+    }
+
+    /// Whether the registry forgets this response, and, for application commands, whether the
+    /// interaction token expired
+    pub fn is_expired(&self) -> bool {
+        self.expires_at <= serenity::Timestamp::now().unix_timestamp()
+    }
+
+    /// Fetches the message object of the response
+    pub async fn message(
+        &self,
+        http: &serenity::Http,
+    ) -> Result<serenity::Message, serenity::Error> {
+        match &self.inner {
+            TrackedResponseInner::Message {
+                channel_id,
+                message_id,
+            } => channel_id.message(http, *message_id).await,
+            TrackedResponseInner::InteractionResponse { token } => {
+                http.get_original_interaction_response(token).await
+            }
+            TrackedResponseInner::Followup { token, message_id } => {
+                http.get_followup_message(token, *message_id).await
+            }
+        }
+    }
+
+    /// Edits the response and returns the edited message
+    ///
+    /// Since there's no command context, the reply isn't completed with the defaults from
+    /// [`crate::FrameworkOptions`], like [`crate::FrameworkOptions::allowed_mentions`].
+    pub async fn edit(
+        &self,
+        http: &serenity::Http,
+        reply: crate::CreateReply,
+    ) -> Result<serenity::Message, serenity::Error> {
+        use crate::serenity_prelude::Builder as _;
+
+        match &self.inner {
+            TrackedResponseInner::Message {
+                channel_id,
+                message_id,
+            } => {
+                channel_id
+                    .edit_message(http, *message_id, reply.to_prefix_edit())
+                    .await
+            }
+            TrackedResponseInner::InteractionResponse { token } => {
+                reply
+                    .to_slash_initial_response_edit()
+                    .execute(http, token)
+                    .await
+            }
+            TrackedResponseInner::Followup { token, message_id } => {
+                reply
+                    .to_slash_followup_response()
+                    .execute(http, (Some(*message_id), token))
+                    .await
+            }
+        }
+    }
+
+    /// Deletes the response
+    pub async fn delete(&self, http: &serenity::Http) -> Result<(), serenity::Error> {
+        match &self.inner {
+            TrackedResponseInner::Message {
+                channel_id,
+                message_id,
+            } => channel_id.delete_message(http, *message_id).await,
+            TrackedResponseInner::InteractionResponse { token } => {
+                http.delete_original_interaction_response(token).await
+            }
+            TrackedResponseInner::Followup { token, message_id } => {
+                http.delete_followup_message(token, *message_id).await
+            }
+        }
+    }
+}
+
+/// Records the replies sent by each command invocation, so they can be edited later by
+/// invocation ID (see [`Self::invocation_id`]), for example to update a status message from a
+/// background task. Stored in [`crate::FrameworkOptions::response_registry`]
+///
+/// Works for prefix and application commands alike:
+/// ```rust,no_run
+/// # use poise::serenity_prelude as serenity;
+/// # async fn _test(ctx: poise::Context<'_, (), ()>) -> Result<(), serenity::Error> {
+/// let registry = ctx.framework().options().response_registry.clone().unwrap();
+/// let invocation_id = poise::ResponseRegistry::invocation_id(ctx);
+/// let http = ctx.serenity_context().http.clone();
+///
+/// ctx.say("Working on it...").await?;
+/// tokio::spawn(async move {
+///     // ... long running work ...
+///     if let Some(response) = registry.last_response(invocation_id) {
+///         let reply = poise::CreateReply::default().content("Done!");
+///         let _ = response.edit(&http, reply).await;
+///     }
+/// });
+/// # Ok(()) }
+/// ```
+///
+/// Responses to prefix commands are forgotten after the duration given to [`Self::for_timespan`].
+/// Responses to application commands are remembered until their interaction token expires.
+/// Forgotten responses are purged periodically by [`crate::Framework`].
+#[derive(Debug)]
+pub struct ResponseRegistry {
+    /// How long responses to prefix commands are remembered
+    max_duration: std::time::Duration,
+    /// Invocation IDs mapped to the responses of the invocation, oldest first
+    responses: Mutex<HashMap<u64, Vec<TrackedResponse>>>,
+}
+
+impl ResponseRegistry {
+    /// Creates a registry which remembers responses to prefix commands for the specified duration
+    pub fn for_timespan(duration: std::time::Duration) -> std::sync::Arc<Self> {
+        std::sync::Arc::new(Self {
+            max_duration: duration,
+            responses: Mutex::new(HashMap::new()),
+        })
+    }
+
+    /// Returns the ID under which the responses of the given invocation are recorded: the ID of
+    /// the invocation message for prefix commands, and the interaction ID for application commands
+    ///
+    /// Unlike [`crate::Context::id`], this stays the same when a prefix command is re-run because
+    /// its invocation message was edited. So responses to the edited invocation are recorded
+    /// alongside the previous ones.
+    pub fn invocation_id<U, E>(ctx: crate::Context<'_, U, E>) -> u64 {
+        match ctx {
+            crate::Context::Prefix(ctx) => ctx.msg.id.get(),
+            crate::Context::Application(ctx) => ctx.interaction.id().get(),
+        }
+    }
+
+    /// Records a prefix command response. A response which was already recorded for the
+    /// invocation, e.g. because it's reused on edits (see [`crate::Command::reuse_response`]), is
+    /// kept only once, as the most recent response
+    pub(crate) fn record_message(&self, invocation_id: u64, message: &serenity::Message) {
+        let expires_at =
+            serenity::Timestamp::now().unix_timestamp() + self.max_duration.as_secs() as i64;
+        self.record(
+            invocation_id,
+            TrackedResponse {
+                inner: TrackedResponseInner::Message {
+                    channel_id: message.channel_id,
+                    message_id: message.id,
+                },
+                expires_at,
+            },
+        );
+    }
+
+    /// Records an application command response. `followup` is the followup message, or `None`
+    /// for the initial response
+    pub(crate) fn record_interaction_response(
+        &self,
+        interaction: &serenity::CommandInteraction,
+        followup: Option<&serenity::Message>,
+    ) {
+        let expires_at =
+            interaction.id.created_at().unix_timestamp() + INTERACTION_TOKEN_LIFETIME_SECS;
+        let token = interaction.token.clone();
+        let inner = match followup {
+            Some(followup) => TrackedResponseInner::Followup {
+                token,
+                message_id: followup.id,
+            },
+            None => TrackedResponseInner::InteractionResponse { token },
+        };
+        self.record(interaction.id.get(), TrackedResponse { inner, expires_at });
+    }
+
+    /// Adds a response to the responses of an invocation
+    fn record(&self, invocation_id: u64, response: TrackedResponse) {
+        let mut responses = self.responses.lock().unwrap();
+        let responses = responses.entry(invocation_id).or_default();
+        if let Some(message_id) = response.message_id() {
+            responses.retain(|r| r.message_id() != Some(message_id));
+        }
+        responses.push(response);
+    }
+
+    /// Returns the responses of the given invocation that haven't expired yet, oldest first
+    pub fn responses(&self, invocation_id: u64) -> Vec<TrackedResponse> {
+        let responses = self.responses.lock().unwrap();
+        responses
+            .get(&invocation_id)
+            .into_iter()
+            .flatten()
+            .filter(|response| !response.is_expired())
+            .cloned()
+            .collect()
+    }
+
+    /// Returns the most recent response of the given invocation, if it hasn't expired yet
+    pub fn last_response(&self, invocation_id: u64) -> Option<TrackedResponse> {
+        let responses = self.responses.lock().unwrap();
+        let response = responses.get(&invocation_id)?.last()?;
+        if response.is_expired() {
+            return None;
+        }
+        Some(response.clone())
+    }
+
+    /// Forgets the responses of the given invocation
+    pub fn forget(&self, invocation_id: u64) {
+        self.responses.lock().unwrap().remove(&invocation_id);
+    }
+
+    /// Forgets all expired responses
+    pub fn purge(&self) {
+        let mut responses = self.responses.lock().unwrap();
+        responses.retain(|_, responses| {
+            responses.retain(|response| !response.is_expired());
+            !responses.is_empty()
+        });
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn message(id: u64) -> serenity::Message {
+        let mut msg = serenity::CustomMessage::new();
+        msg.id(serenity::MessageId::new(id))
+            .channel_id(serenity::ChannelId::new(1));
+        msg.build()
+    }
+
+    #[test]
+    fn test_record_message() {
+        let registry = ResponseRegistry::for_timespan(std::time::Duration::from_secs(3600));
+        registry.record_message(5, &message(10));
+        registry.record_message(5, &message(11));
+        // Reused responses are only stored once, as the most recent response
+        registry.record_message(5, &message(10));
+
+        let message_ids = registry
+            .responses(5)
+            .iter()
+            .map(|r| r.message_id().unwrap().get())
+            .collect::<Vec<_>>();
+        assert_eq!(message_ids, [11, 10]);
+        assert!(registry.responses(6).is_empty());
+
+        let expired = ResponseRegistry::for_timespan(std::time::Duration::ZERO);
+        expired.record_message(5, &message(10));
+        assert!(expired.last_response(5).is_none());
+        expired.purge();
+        assert!(expired.responses.lock().unwrap().is_empty());
+    }
+
+    fn working(
+        ctx: crate::ApplicationContext<'_, (), ()>,
+    ) -> crate::BoxFuture<'_, Result<(), crate::FrameworkError<'_, (), ()>>> {
+        Box::pin(async move {
+            let _ = crate::say_reply(ctx.into(), "Working on it...").await;
+            let _ = crate::say_reply(ctx.into(), "Done!").await;
+            Ok(())
+        })
+    }
+
+    async fn harness(
+        registry: std::sync::Arc<ResponseRegistry>,
+    ) -> crate::testing::TestHarness<(), ()> {
+        crate::testing::TestHarness::with_prefix_commands(
+            vec![crate::Command {
+                name: "ping".into(),
+                prefix_action: Some(crate::testing::pong),
+                slash_action: Some(working),
+                ..Default::default()
+            }],
+            crate::FrameworkOptions {
+                response_registry: Some(registry),
+                ..Default::default()
+            },
+            (),
+        )
+        .await
+    }
+
+    #[tokio::test]
+    async fn test_prefix_edit() {
+        let registry = ResponseRegistry::for_timespan(std::time::Duration::from_secs(3600));
+        let harness = harness(registry.clone()).await;
+
+        harness.dispatch_message(harness.message("~ping")).await;
+        // Re-running the edited invocation records under the same invocation ID
+        let mut edited = harness.message("~ping");
+        edited.edited_timestamp = Some(serenity::Timestamp::now());
+        harness.dispatch_message(edited).await;
+
+        let message_ids = registry
+            .responses(1)
+            .iter()
+            .map(|r| r.message_id().unwrap().get())
+            .collect::<Vec<_>>();
+        assert_eq!(message_ids, [1, 2]);
+        assert_eq!(
+            registry.last_response(1).unwrap().message_id(),
+            Some(serenity::MessageId::new(2))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_application_responses() {
+        let registry = ResponseRegistry::for_timespan(std::time::Duration::from_secs(3600));
+        let harness = harness(registry.clone()).await;
+
+        // Interaction tokens expire 15 minutes after the interaction was created, so the
+        // interaction needs a recent ID
+        let mut interaction = harness.interaction("ping", vec![]);
+        let discord_epoch_millis = 1_420_070_400_000;
+        let millis = serenity::Timestamp::now().unix_timestamp() * 1000 - discord_epoch_millis;
+        interaction.id = serenity::InteractionId::new((millis as u64) << 22);
+        harness.dispatch_interaction(interaction.clone()).await;
+
+        let responses = registry.responses(interaction.id.get());
+        let message_ids = responses.iter().map(|r| r.message_id()).collect::<Vec<_>>();
+        // The initial response has no message ID, the followup does
+        assert_eq!(message_ids, [None, Some(serenity::MessageId::new(2))]);
+        assert!(!responses[0].is_expired());
+    }
+}
@@ -26,6 +26,22 @@ pub async fn send_reply<U, E>(
     ctx: crate::Context<'_, U, E>,
     builder: crate::CreateReply,
 ) -> Result<crate::ReplyHandle<'_>, serenity::Error> {
+    // Prefix replies are usually represented by the sent message, which can't be used while testing
+    #[cfg(any(test, feature = "testing"))]
+    if let crate::Context::Prefix(prefix_ctx) = ctx {
+        if let Some(recorder) = crate::testing::recorder(prefix_ctx.serenity_context).await {
+            let index = recorder.record_reply(builder.complete_from_ctx(ctx));
+            if let Some(registry) = &prefix_ctx.framework.options().response_registry {
+                let response = crate::testing::recorded_message(index, None);
+                registry.record_message(crate::ResponseRegistry::invocation_id(ctx), &response);
+            }
+            return Ok(super::ReplyHandle(super::ReplyHandleInner::Recorded {
+                recorder,
+                index,
+            }));
+        }
+    }
+
     Ok(match ctx {
         crate::Context::Prefix(ctx) => super::ReplyHandle(super::ReplyHandleInner::Prefix(
             crate::send_prefix_reply(ctx, builder).await?,
@@ -69,6 +85,23 @@ async fn _send_application_reply<U, E>(
         }
     };
 
+    #[cfg(any(test, feature = "testing"))]
+    if let Some(recorder) = crate::testing::recorder(ctx.serenity_context).await {
+        let has_sent_initial_response = ctx
+            .has_sent_initial_response
+            .swap(true, std::sync::atomic::Ordering::SeqCst);
+        let index = recorder.record_reply(data);
+        if let Some(registry) = &ctx.framework.options().response_registry {
+            let followup =
+                has_sent_initial_response.then(|| crate::testing::recorded_message(index, None));
+            registry.record_interaction_response(interaction, followup.as_ref());
+        }
+        return Ok(super::ReplyHandle(super::ReplyHandleInner::Recorded {
+            recorder,
+            index,
+        }));
+    }
+
     let has_sent_initial_response = ctx
         .has_sent_initial_response
         .load(std::sync::atomic::Ordering::SeqCst);
@@ -92,6 +125,10 @@ async fn _send_application_reply<U, E>(
         None
     };
 
+    if let Some(registry) = &ctx.framework.options().response_registry {
+        registry.record_interaction_response(interaction, followup.as_deref());
+    }
+
     Ok(super::ReplyHandle(super::ReplyHandleInner::Application {
         http: &ctx.serenity_context.http,
         interaction,
@@ -112,6 +149,15 @@ async fn _send_prefix_reply<'a, U, E>(
     ctx: crate::PrefixContext<'_, U, E>,
     reply: crate::CreateReply,
 ) -> Result<Box<serenity::Message>, serenity::Error> {
+    #[cfg(any(test, feature = "testing"))]
+    if let Some(recorder) = crate::testing::recorder(ctx.serenity_context).await {
+        let index = recorder.record_reply(reply.clone());
+        return Ok(Box::new(crate::testing::recorded_message(
+            index,
+            Some(reply),
+        )));
+    }
+
     // This must only return None when we _actually_ want to reuse the existing response! There are
     // no checks later
     let lock_edit_tracker = || {
@@ -122,24 +168,16 @@ async fn _send_prefix_reply<'a, U, E>(
     };
 
     let existing_response = if ctx.command.reuse_response {
-        lock_edit_tracker()
-            .as_mut()
-            .and_then(|t| t.find_bot_response(ctx.msg.id))
-            .cloned()
+        lock_edit_tracker().and_then(|t| t.find_bot_response(ctx.msg.id))
     } else {
         None
     };
 
-    Ok(Box::new(if let Some(mut response) = existing_response {
-        response.edit(ctx.discord, reply.to_prefix_edit()).await?;
-
-        // If the entry still exists after the await, update it to the new contents
-        // We don't check ctx.command.reuse_response because it's true anyways in this branch
-        if let Some(mut edit_tracker) = lock_edit_tracker() {
-            edit_tracker.set_bot_response(ctx.msg, response.clone(), ctx.command.track_deletion);
-        }
-
-        response
+    let response = if let Some(response_id) = existing_response {
+        ctx.msg
+            .channel_id
+            .edit_message(ctx.discord, response_id, reply.to_prefix_edit())
+            .await?
     } else {
         let new_response = ctx
             .msg
@@ -149,9 +187,18 @@ async fn _send_prefix_reply<'a, U, E>(
         // We don't check ctx.command.reuse_response because we need to store bot responses for
         // track_deletion too
         if let Some(track_edits) = &mut lock_edit_tracker() {
-            track_edits.set_bot_response(ctx.msg, new_response.clone(), ctx.command.track_deletion);
+            track_edits.set_bot_response(ctx.msg, new_response.id, ctx.command.track_deletion);
         }
 
         new_response
-    }))
+    };
+
+    if let Some(registry) = &ctx.framework.options().response_registry {
+        registry.record_message(
+            crate::ResponseRegistry::invocation_id(ctx.into()),
+            &response,
+        );
+    }
+
+    Ok(Box::new(response))
 }
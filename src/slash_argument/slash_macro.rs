@@ -45,16 +45,25 @@ impl SlashArgError {
                 ctx: ctx.into(),
                 error,
                 input: Some(input),
+                parameter: None,
+                expected_type: None,
+                span: None,
             },
             crate::SlashArgError::Invalid(description) => crate::FrameworkError::ArgumentParse {
                 ctx: ctx.into(),
                 error: description.into(),
                 input: None,
+                parameter: None,
+                expected_type: None,
+                span: None,
             },
             crate::SlashArgError::Http(error) => crate::FrameworkError::ArgumentParse {
                 ctx: ctx.into(),
                 error: error.into(),
                 input: None,
+                parameter: None,
+                expected_type: None,
+                span: None,
             },
         }
     }
@@ -114,13 +123,27 @@ macro_rules! _parse_slash {
         }
     };
 
-    // Extract Vec<T> (delegating to Option<T> because slash commands don't support variadic
-    // arguments right now)
-    ($ctx:ident, $interaction:ident, $args:ident => $name:ident: Vec<$type:ty $(,)*>) => {
-        match $crate::_parse_slash!($ctx, $interaction, $args => $name: Option<$type>) {
-            Some(value) => vec![value],
-            None => vec![],
+    // Extract Vec<T> from the option named like the parameter and, because slash commands don't
+    // support variadic arguments, the numbered options `name_2`, `name_3`... that some parameters
+    // are registered with (see `CommandParameter::create_as_slash_command_options`)
+    ($ctx:ident, $interaction:ident, $args:ident => $name:ident: Vec<$type:ty $(,)*>) => {{
+        let mut args = $args
+            .iter()
+            .filter_map(|arg| Some(($crate::slash_option_index(&arg.name, stringify!($name))?, arg)))
+            .collect::<Vec<_>>();
+        args.sort_by_key(|&(index, _)| index);
+
+        let mut values = Vec::new();
+        for (_, arg) in args {
+            values.push($crate::extract_slash_argument!($type, $ctx, $interaction, &arg.value).await?);
         }
+        values
+    }};
+
+    // Extract Option<Vec<T>>, which is None instead of an empty Vec
+    ($ctx:ident, $interaction:ident, $args:ident => $name:ident: OPTIONAL_VEC<$type:ty $(,)*>) => {
+        Some($crate::_parse_slash!($ctx, $interaction, $args => $name: Vec<$type>))
+            .filter(|values| !values.is_empty())
     };
 
     // Extract #[flag]
@@ -129,6 +152,11 @@ macro_rules! _parse_slash {
             .unwrap_or(false)
     };
 
+    // Extract a #[flatten] parameter group from the options of its fields
+    ($ctx:ident, $interaction:ident, $args:ident => $name:ident: #[flatten] $type:ty as $group:path) => {
+        <$type as $group>::extract($ctx, $interaction, $args).await?
+    };
+
     // Extract T
     ($ctx:ident, $interaction:ident, $args:ident => $name:ident: $($type:tt)*) => {
         $crate::_parse_slash!($ctx, $interaction, $args => $name: Option<$($type)*>)
@@ -136,6 +164,20 @@ macro_rules! _parse_slash {
     };
 }
 
+/// If the slash command option with the given name belongs to the given parameter, returns its
+/// position among the options of the parameter: 0 for `name`, 1 for `name_2` and so on
+#[doc(hidden)]
+pub fn slash_option_index(option_name: &str, parameter_name: &str) -> Option<usize> {
+    let suffix = option_name.strip_prefix(parameter_name)?;
+    if suffix.is_empty() {
+        return Some(0);
+    }
+    match suffix.strip_prefix('_')?.parse::<usize>() {
+        Ok(number) if number >= 2 => Some(number - 1),
+        _ => None,
+    }
+}
+
 /**
 Macro for extracting and parsing slash command arguments out of an array of
 [`serenity::ResolvedOption`].
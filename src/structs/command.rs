@@ -161,7 +161,9 @@ impl<U, E> Command<U, E> {
             for param in &self.parameters {
                 // Using `?` because if this command has slash-incompatible parameters, we cannot
                 // just ignore them but have to abort the creation process entirely
-                b = b.add_sub_option(param.create_as_slash_command_option()?);
+                for option in param.create_as_slash_command_options()? {
+                    b = b.add_sub_option(option);
+                }
             }
         } else {
             b = b.kind(serenity::CommandOptionType::SubCommandGroup);
@@ -205,7 +207,9 @@ impl<U, E> Command<U, E> {
             for param in &self.parameters {
                 // Using `?` because if this command has slash-incompatible parameters, we cannot
                 // just ignore them but have to abort the creation process entirely
-                b = b.add_option(param.create_as_slash_command_option()?);
+                for option in param.create_as_slash_command_options()? {
+                    b = b.add_option(option);
+                }
             }
         } else {
             for subcommand in &self.subcommands {
@@ -236,6 +240,88 @@ impl<U, E> Command<U, E> {
         )
     }
 
+    /// Generates a usage string for this command and all of its subcommands, one line per
+    /// invocable command, e.g. `~ban|b <user> [reason...]`
+    ///
+    /// Required parameters are rendered as `<name>`, optional ones as `[name]`, rest and variadic
+    /// parameters get a trailing `...` and flags are shown as the keyword that sets them. Named
+    /// options follow the positional parameters, like `[-d|--days <days>]`. Aliases
+    /// are listed next to the command name, separated with `|`. Hidden subcommands are skipped.
+    ///
+    /// The `prefix` is prepended to every line; pass `"/"` to render slash command usage.
+    pub fn usage(&self, prefix: &str) -> String {
+        let mut lines = Vec::new();
+        self.push_usage_lines(prefix, &mut lines);
+        lines.join("\n")
+    }
+
+    /// Appends the usage lines of this command and its subcommands to `lines`
+    fn push_usage_lines(&self, prefix: &str, lines: &mut Vec<String>) {
+        if self.subcommands.is_empty() || !self.subcommand_required {
+            // `qualified_name` ends with `name`; only the last segment can be replaced by an alias
+            let parents = self
+                .qualified_name
+                .strip_suffix(self.name.as_str())
+                .unwrap_or("");
+            let mut line = format!("{}{}{}", prefix, parents, self.name);
+            for alias in self.aliases {
+                line += "|";
+                line += alias;
+            }
+
+            // Named options can appear anywhere, so list them after the positional parameters
+            let (named, positional) = self
+                .parameters
+                .iter()
+                .partition::<Vec<_>, _>(|param| param.details.long.is_some());
+            for param in positional {
+                let name = &param.name;
+                let token = if param.details.flag {
+                    format!("[{}]", name)
+                } else if param.details.rest || param.details.variadic {
+                    match param.required {
+                        true => format!("<{}...>", name),
+                        false => format!("[{}...]", name),
+                    }
+                } else {
+                    match param.required {
+                        true => format!("<{}>", name),
+                        false => format!("[{}]", name),
+                    }
+                };
+                line += " ";
+                line += &token;
+            }
+            for param in named {
+                let long = param.details.long.as_deref().unwrap_or_default();
+                let mut token = match param.details.short {
+                    Some(short) => format!("-{}|--{}", short, long),
+                    None => format!("--{}", long),
+                };
+                if !param.details.flag {
+                    token += &format!(" <{}>", param.name);
+                }
+                let token = if param.details.variadic {
+                    format!("[{}]...", token)
+                } else if param.required {
+                    token
+                } else {
+                    format!("[{}]", token)
+                };
+                line += " ";
+                line += &token;
+            }
+
+            lines.push(line);
+        }
+
+        for subcommand in &self.subcommands {
+            if !subcommand.hide_in_help {
+                subcommand.push_usage_lines(prefix, lines);
+            }
+        }
+    }
+
     /// **Deprecated**
     #[deprecated = "Please use `poise::Command { category: \"...\", ..command() }` instead"]
     pub fn category(mut self, category: &'static str) -> Self {
@@ -255,3 +341,96 @@ impl<U, E> Command<U, E> {
         self
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn param(
+        name: &str,
+        required: bool,
+        rest: bool,
+        flag: bool,
+    ) -> crate::CommandParameter<(), ()> {
+        crate::CommandParameter {
+            name: name.into(),
+            name_localizations: Default::default(),
+            description: None,
+            description_localizations: Default::default(),
+            required,
+            details: crate::CommandParameterDetails {
+                rest,
+                flag,
+                type_name: "String",
+                ..Default::default()
+            },
+            channel_types: None,
+            choices: vec![],
+            type_setter: None,
+            autocomplete_callback: None,
+        }
+    }
+
+    fn named(
+        mut param: crate::CommandParameter<(), ()>,
+        long: &str,
+        short: Option<char>,
+    ) -> crate::CommandParameter<(), ()> {
+        param.details.long = Some(long.into());
+        param.details.short = short;
+        param
+    }
+
+    #[test]
+    fn test_usage() {
+        let ban = Command::<(), ()> {
+            name: "ban".into(),
+            qualified_name: "ban".into(),
+            aliases: &["b"],
+            parameters: vec![
+                param("user", true, false, false),
+                param("reason", false, true, false),
+                param("silent", false, false, true),
+            ],
+            ..Default::default()
+        };
+        assert_eq!(ban.usage("~"), "~ban|b <user> [reason...] [silent]");
+
+        let purge = Command::<(), ()> {
+            name: "purge".into(),
+            qualified_name: "purge".into(),
+            parameters: vec![
+                named(param("days", false, false, false), "days", Some('d')),
+                param("channel", true, false, false),
+                named(param("silent", false, false, true), "silent", None),
+                named(param("reason", true, false, false), "reason", None),
+            ],
+            ..Default::default()
+        };
+        assert_eq!(
+            purge.usage("~"),
+            "~purge <channel> [-d|--days <days>] [--silent] --reason <reason>"
+        );
+
+        let config = Command::<(), ()> {
+            name: "config".into(),
+            qualified_name: "config".into(),
+            subcommand_required: true,
+            subcommands: vec![
+                Command {
+                    name: "set".into(),
+                    qualified_name: "config set".into(),
+                    parameters: vec![param("key", true, false, false)],
+                    ..Default::default()
+                },
+                Command {
+                    name: "reset".into(),
+                    qualified_name: "config reset".into(),
+                    ..Default::default()
+                },
+            ],
+            ..Default::default()
+        };
+        assert_eq!(config.usage("/"), "/config set <key>\n/config reset");
+    }
+}
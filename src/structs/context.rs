@@ -405,14 +405,37 @@ context_methods! {
     }
 
     /// If available, returns the locale (selected language) of the invoking user
+    ///
+    /// For prefix commands, the locale is determined by
+    /// [`crate::PrefixFrameworkOptions::locale_resolver`] or, as a fallback, the preferred locale
+    /// of the guild.
     (locale self)
     (pub fn locale(self) -> Option<&'a str>) {
         match self {
             Context::Application(ctx) => Some(ctx.interaction.locale()),
-            Context::Prefix(_) => None,
+            Context::Prefix(ctx) => ctx.locale,
         }
     }
 
+    /// Retrieves a message from [`crate::FrameworkOptions::translations`] in the locale of the
+    /// invoking user (see [`Self::locale`]), falling back to the main locale. For an easier way
+    /// to pass arguments, see [`crate::tr!`]
+    ///
+    /// If the message doesn't exist in any locale, logs a warning and returns the message ID.
+    #[cfg(feature = "fluent")]
+    (tr self id args)
+    (pub fn tr(self, id: &str, args: Option<&crate::localization::FluentArgs<'_>>) -> String) {
+        self.framework()
+            .options()
+            .translations
+            .as_ref()
+            .and_then(|translations| translations.format(self.locale(), id, None, args))
+            .unwrap_or_else(|| {
+                log::warn!("unknown fluent message identifier `{}`", id);
+                id.to_owned()
+            })
+    }
+
     /// Builds a [`crate::CreateReply`] by combining the builder closure with the defaults that were
     /// pre-configured in poise.
     ///
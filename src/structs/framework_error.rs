@@ -67,6 +67,29 @@ pub enum FrameworkError<'a, U, E> {
         error: Box<dyn std::error::Error + Send + Sync>,
         /// If applicable, the input on which parsing failed
         input: Option<String>,
+        /// Name of the parameter which failed to parse
+        ///
+        /// Only set in prefix commands, and only if the error is tied to a single parameter (as
+        /// opposed to, for example, too many arguments being passed)
+        parameter: Option<&'a str>,
+        /// Type of the parameter which failed to parse, see [`crate::CommandParameterDetails::type_name`].
+        /// Set along with [`Self::ArgumentParse::parameter`]
+        expected_type: Option<&'static str>,
+        /// Byte range of the offending input within the message content. Only set in prefix
+        /// commands
+        span: Option<std::ops::Range<usize>>,
+        /// General context
+        ctx: crate::Context<'a, U, E>,
+    },
+    /// A command argument was parsed successfully but rejected by a validation attribute of its
+    /// parameter, like `#[max]` or `#[regex]`. See [`crate::validation`]
+    ArgumentValidation {
+        /// Why the argument was rejected
+        error: crate::ArgumentValidationError,
+        /// The rejected input, if available
+        input: Option<String>,
+        /// Name of the parameter which was rejected
+        parameter: &'a str,
         /// General context
         ctx: crate::Context<'a, U, E>,
     },
@@ -85,6 +108,12 @@ pub enum FrameworkError<'a, U, E> {
     CooldownHit {
         /// Time until the command may be invoked for the next time in the given context
         remaining_cooldown: std::time::Duration,
+        /// The cooldown bucket that tripped
+        bucket: crate::CooldownBucket,
+        /// How many uses are left in the tripped bucket
+        remaining_uses: u32,
+        /// Time until the tripped bucket is completely refilled
+        window_reset: std::time::Duration,
         /// General context
         ctx: crate::Context<'a, U, E>,
     },
@@ -125,6 +154,58 @@ pub enum FrameworkError<'a, U, E> {
         /// General context
         ctx: crate::Context<'a, U, E>,
     },
+    /// Command was invoked but it, or one of its parent commands, is disabled in
+    /// [`crate::CommandRegistry`]
+    CommandDisabled {
+        /// General context
+        ctx: crate::Context<'a, U, E>,
+    },
+    /// Command was invoked but it, or one of its parent commands, is disabled in the guild's
+    /// [`crate::GuildSettings::disabled_commands`]
+    GuildCommandDisabled {
+        /// General context
+        ctx: crate::Context<'a, U, E>,
+    },
+    /// Command was invoked but its category, or the category of one of its parent commands, is
+    /// disabled in the guild's [`crate::GuildSettings::disabled_categories`]
+    GuildCategoryDisabled {
+        /// The disabled category
+        category: &'static str,
+        /// General context
+        ctx: crate::Context<'a, U, E>,
+    },
+    /// Command was invoked in a channel where the guild's [`crate::GuildSettings`] don't allow
+    /// commands
+    ChannelNotAllowed {
+        /// General context
+        ctx: crate::Context<'a, U, E>,
+    },
+    /// An attachment parameter received attachments which violate its
+    /// [`crate::CommandParameterDetails::attachment_constraints`]
+    AttachmentConstraint {
+        /// Which constraint was violated
+        violation: crate::AttachmentConstraintViolation,
+        /// Name of the attachment parameter
+        parameter: &'a str,
+        /// General context
+        ctx: crate::Context<'a, U, E>,
+    },
+    /// Command was invoked but the user has none of the roles that the guild's
+    /// [`crate::GuildSettings::required_roles`] require for this command or a parent command
+    MissingRequiredRole {
+        /// Roles of which the user needs at least one
+        required_roles: Vec<serenity::RoleId>,
+        /// General context
+        ctx: crate::Context<'a, U, E>,
+    },
+    /// [`crate::FrameworkOptions::guild_settings`] returned an error
+    GuildSettings {
+        /// Error which was thrown by the guild settings provider
+        error: Box<dyn std::error::Error + Send + Sync>,
+        /// General context
+        #[derivative(Debug = "ignore")]
+        ctx: crate::PartialContext<'a, U, E>,
+    },
     /// Provided pre-command check either errored, or returned false, so command execution aborted
     CommandCheckFailed {
         /// If execution wasn't aborted because of an error but because it successfully returned
@@ -144,6 +225,16 @@ pub enum FrameworkError<'a, U, E> {
         /// Message which the dynamic prefix callback was evaluated upon
         msg: &'a serenity::Message,
     },
+    /// [`crate::PrefixFrameworkOptions::locale_resolver`] returned an error
+    LocaleResolver {
+        /// Error which was thrown in the locale resolver
+        error: E,
+        /// General context
+        #[derivative(Debug = "ignore")]
+        ctx: crate::PartialContext<'a, U, E>,
+        /// Message whose locale was to be resolved
+        msg: &'a serenity::Message,
+    },
     /// A message had the correct prefix but the following string was not a recognized command
     UnknownCommand {
         /// Serenity's Context
@@ -165,6 +256,17 @@ pub enum FrameworkError<'a, U, E> {
         invocation_data: &'a tokio::sync::Mutex<Box<dyn std::any::Any + Send + Sync>>,
         /// Which event triggered the message parsing routine
         trigger: crate::MessageDispatchTrigger,
+        /// Qualified names of the commands the user may have meant, best match first. See
+        /// [`crate::builtins::suggest_commands`]
+        ///
+        /// Only computed if [`crate::PrefixFrameworkOptions::suggest_unknown_commands`] is
+        /// enabled, empty otherwise.
+        suggestions: Vec<String>,
+        /// Locale of the user, see [`crate::PrefixFrameworkOptions::locale_resolver`]
+        ///
+        /// Only resolved if [`crate::PrefixFrameworkOptions::suggest_unknown_commands`] is
+        /// enabled, `None` otherwise.
+        locale: Option<&'a str>,
     },
     /// The command name from the interaction is unrecognized
     UnknownInteraction {
@@ -191,6 +293,7 @@ impl<'a, U, E> FrameworkError<'a, U, E> {
             Self::Listener { .. } => return None,
             Self::Command { ctx, .. } => ctx.discord(),
             Self::ArgumentParse { ctx, .. } => ctx.discord(),
+            Self::ArgumentValidation { ctx, .. } => ctx.discord(),
             Self::CommandStructureMismatch { ctx, .. } => ctx.discord,
             Self::CooldownHit { ctx, .. } => ctx.discord(),
             Self::MissingBotPermissions { ctx, .. } => ctx.discord(),
@@ -199,8 +302,16 @@ impl<'a, U, E> FrameworkError<'a, U, E> {
             Self::GuildOnly { ctx, .. } => ctx.discord(),
             Self::DmOnly { ctx, .. } => ctx.discord(),
             Self::NsfwOnly { ctx, .. } => ctx.discord(),
+            Self::CommandDisabled { ctx, .. } => ctx.discord(),
+            Self::GuildCommandDisabled { ctx, .. } => ctx.discord(),
+            Self::GuildCategoryDisabled { ctx, .. } => ctx.discord(),
+            Self::ChannelNotAllowed { ctx, .. } => ctx.discord(),
+            Self::MissingRequiredRole { ctx, .. } => ctx.discord(),
+            Self::AttachmentConstraint { ctx, .. } => ctx.discord(),
+            Self::GuildSettings { ctx, .. } => ctx.serenity_context,
             Self::CommandCheckFailed { ctx, .. } => ctx.discord(),
-            Self::DynamicPrefix { ctx, .. } => ctx.discord,
+            Self::DynamicPrefix { ctx, .. } => ctx.serenity_context,
+            Self::LocaleResolver { ctx, .. } => ctx.serenity_context,
             Self::UnknownCommand { ctx, .. } => ctx,
             Self::UnknownInteraction { ctx, .. } => ctx,
             Self::__NonExhaustive => unreachable!(),
@@ -214,6 +325,7 @@ impl<'a, U, E> FrameworkError<'a, U, E> {
             Self::SubcommandRequired { ctx } => ctx,
             Self::CommandPanic { ctx, .. } => ctx,
             Self::ArgumentParse { ctx, .. } => ctx,
+            Self::ArgumentValidation { ctx, .. } => ctx,
             Self::CommandStructureMismatch { ctx, .. } => crate::Context::Application(ctx),
             Self::CooldownHit { ctx, .. } => ctx,
             Self::MissingBotPermissions { ctx, .. } => ctx,
@@ -222,12 +334,20 @@ impl<'a, U, E> FrameworkError<'a, U, E> {
             Self::GuildOnly { ctx, .. } => ctx,
             Self::DmOnly { ctx, .. } => ctx,
             Self::NsfwOnly { ctx, .. } => ctx,
+            Self::CommandDisabled { ctx } => ctx,
+            Self::GuildCommandDisabled { ctx } => ctx,
+            Self::GuildCategoryDisabled { ctx, .. } => ctx,
+            Self::ChannelNotAllowed { ctx } => ctx,
+            Self::MissingRequiredRole { ctx, .. } => ctx,
+            Self::AttachmentConstraint { ctx, .. } => ctx,
             Self::CommandCheckFailed { ctx, .. } => ctx,
             Self::Setup { .. }
             | Self::EventHandler { .. }
             | Self::UnknownCommand { .. }
             | Self::UnknownInteraction { .. }
-            | Self::DynamicPrefix { .. } => return None,
+            | Self::DynamicPrefix { .. }
+            | Self::LocaleResolver { .. }
+            | Self::GuildSettings { .. } => return None,
             Self::__NonExhaustive(unreachable) => match unreachable {},
         })
     }
@@ -277,14 +397,33 @@ impl<U, E: std::fmt::Display> std::fmt::Display for FrameworkError<'_, U, E> {
                 write!(f, "panic in command `{}`", full_command_name!(ctx))
             }
             Self::ArgumentParse {
-                error: _,
                 input,
+                parameter,
                 ctx,
+                ..
+            } => {
+                write!(f, "failed to parse argument")?;
+                if let Some(parameter) = parameter {
+                    write!(f, " `{}`", parameter)?;
+                }
+                write!(
+                    f,
+                    " in command `{}` on input {:?}",
+                    full_command_name!(ctx),
+                    input
+                )
+            }
+            Self::ArgumentValidation {
+                error,
+                parameter,
+                ctx,
+                ..
             } => write!(
                 f,
-                "failed to parse argument in command `{}` on input {:?}",
+                "argument `{}` in command `{}` failed validation: {}",
+                parameter,
                 full_command_name!(ctx),
-                input
+                error
             ),
             Self::CommandStructureMismatch { description, ctx } => write!(
                 f,
@@ -294,11 +433,14 @@ impl<U, E: std::fmt::Display> std::fmt::Display for FrameworkError<'_, U, E> {
             ),
             Self::CooldownHit {
                 remaining_cooldown,
+                bucket,
                 ctx,
+                ..
             } => write!(
                 f,
-                "cooldown hit in command `{}` ({:?} remaining)",
+                "cooldown hit in command `{}` in bucket {} ({:?} remaining)",
                 full_command_name!(ctx),
+                bucket,
                 remaining_cooldown
             ),
             Self::MissingBotPermissions {
@@ -339,6 +481,50 @@ impl<U, E: std::fmt::Display> std::fmt::Display for FrameworkError<'_, U, E> {
                 "nsfw-only command `{}` cannot run in non-nsfw channels",
                 full_command_name!(ctx)
             ),
+            Self::CommandDisabled { ctx } => {
+                write!(f, "command `{}` is disabled", full_command_name!(ctx))
+            }
+            Self::GuildCommandDisabled { ctx } => write!(
+                f,
+                "command `{}` is disabled in this guild",
+                full_command_name!(ctx)
+            ),
+            Self::GuildCategoryDisabled { category, ctx } => write!(
+                f,
+                "command `{}` is in category `{}` which is disabled in this guild",
+                full_command_name!(ctx),
+                category
+            ),
+            Self::ChannelNotAllowed { ctx } => write!(
+                f,
+                "command `{}` cannot run in this channel",
+                full_command_name!(ctx)
+            ),
+            Self::MissingRequiredRole {
+                required_roles,
+                ctx,
+            } => write!(
+                f,
+                "user is missing one of the roles {:?} to execute command `{}`",
+                required_roles,
+                full_command_name!(ctx)
+            ),
+            Self::AttachmentConstraint {
+                violation,
+                parameter,
+                ctx,
+            } => write!(
+                f,
+                "attachments for parameter `{}` of command `{}` violate its constraints: {}",
+                parameter,
+                full_command_name!(ctx),
+                violation
+            ),
+            Self::GuildSettings { error: _, ctx } => write!(
+                f,
+                "guild settings provider errored for guild {:?}",
+                ctx.guild_id
+            ),
             Self::CommandCheckFailed { error: _, ctx } => write!(
                 f,
                 "pre-command check for command `{}` either denied access or errored",
@@ -355,6 +541,13 @@ impl<U, E: std::fmt::Display> std::fmt::Display for FrameworkError<'_, U, E> {
                     msg.content
                 )
             }
+            Self::LocaleResolver {
+                error: _,
+                ctx: _,
+                msg,
+            } => {
+                write!(f, "locale resolver errored on message {:?}", msg.content)
+            }
             Self::UnknownCommand { msg_content, .. } => {
                 write!(f, "unknown command `{}`", msg_content)
             }
@@ -377,6 +570,7 @@ impl<'a, U: std::fmt::Debug, E: std::error::Error + 'static> std::error::Error
             Self::SubcommandRequired { .. } => None,
             Self::CommandPanic { .. } => None,
             Self::ArgumentParse { error, .. } => Some(&**error),
+            Self::ArgumentValidation { error, .. } => Some(error),
             Self::CommandStructureMismatch { .. } => None,
             Self::CooldownHit { .. } => None,
             Self::MissingBotPermissions { .. } => None,
@@ -385,8 +579,16 @@ impl<'a, U: std::fmt::Debug, E: std::error::Error + 'static> std::error::Error
             Self::GuildOnly { .. } => None,
             Self::DmOnly { .. } => None,
             Self::NsfwOnly { .. } => None,
+            Self::CommandDisabled { .. } => None,
+            Self::GuildCommandDisabled { .. } => None,
+            Self::GuildCategoryDisabled { .. } => None,
+            Self::ChannelNotAllowed { .. } => None,
+            Self::MissingRequiredRole { .. } => None,
+            Self::AttachmentConstraint { violation, .. } => Some(violation),
+            Self::GuildSettings { error, .. } => Some(&**error),
             Self::CommandCheckFailed { error, .. } => error.as_ref().map(|x| x as _),
             Self::DynamicPrefix { error, .. } => Some(error),
+            Self::LocaleResolver { error, .. } => Some(error),
             Self::UnknownCommand { .. } => None,
             Self::UnknownInteraction { .. } => None,
             Self::__NonExhaustive(unreachable) => match *unreachable {},
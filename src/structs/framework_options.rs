@@ -8,6 +8,8 @@ use crate::{serenity_prelude as serenity, BoxFuture};
 pub struct FrameworkOptions<U, E> {
     /// List of commands in the framework
     pub commands: Vec<crate::Command<U, E>>,
+    /// Allows disabling, enabling, adding and removing commands while the bot runs
+    pub command_registry: crate::CommandRegistry<U, E>,
     /// Provide a callback to be invoked when any user code yields an error.
     #[derivative(Debug = "ignore")]
     pub on_error: fn(crate::FrameworkError<'_, U, E>) -> BoxFuture<'_, ()>,
@@ -40,6 +42,55 @@ pub struct FrameworkOptions<U, E> {
     /// Useful for implementing custom cooldown behavior. See [`crate::Command::cooldowns`] and
     /// the methods on [`crate::Cooldowns`] for how to do that.
     pub manual_cooldowns: bool,
+    /// Where cooldown state is stored.
+    ///
+    /// Defaults to [`crate::InMemoryCooldownStore`], which forgets all cooldowns on restart. Use
+    /// [`crate::FileCooldownStore`] or your own [`crate::CooldownStore`] implementation to persist
+    /// cooldowns.
+    #[derivative(Debug = "ignore")]
+    pub cooldown_store: std::sync::Arc<dyn crate::CooldownStore>,
+    /// Called whenever cooldowns are checked or started, to adjust them for a single invocation.
+    ///
+    /// Receives the invocation context, the command whose cooldowns are processed (the invoked
+    /// command or one of its parents), and that command's configured cooldowns. Can be used to
+    /// let users with a certain role bypass cooldowns, or to give certain guilds shorter
+    /// cooldowns.
+    ///
+    /// Applied by the framework before checking and starting cooldowns, see
+    /// [`crate::Cooldowns::effective_for`]. It's async, so it can look up the invoking user in a
+    /// database.
+    #[derivative(Debug = "ignore")]
+    pub cooldown_override: Option<
+        for<'a> fn(
+            crate::Context<'a, U, E>,
+            &'a crate::Command<U, E>,
+            &'a crate::CooldownConfig,
+        ) -> BoxFuture<'a, crate::CooldownOverride>,
+    >,
+    /// Per-guild prefix, disabled commands and categories, channel restrictions and role
+    /// requirements, queried for every guild message and command invocation.
+    ///
+    /// Denied invocations yield one of [`crate::FrameworkError::GuildCommandDisabled`],
+    /// [`crate::FrameworkError::GuildCategoryDisabled`],
+    /// [`crate::FrameworkError::ChannelNotAllowed`] or
+    /// [`crate::FrameworkError::MissingRequiredRole`]. Like other checks, these are skipped for
+    /// owners if [`Self::skip_checks_for_owners`] is set.
+    #[derivative(Debug = "ignore")]
+    pub guild_settings: Option<std::sync::Arc<dyn crate::GuildSettingsProvider>>,
+    /// Fluent translation files, applied to [`Self::commands`] on startup and used by
+    /// [`crate::Context::tr`]. Missing translations are logged as warnings
+    ///
+    /// See [`crate::localization`]
+    #[cfg(feature = "fluent")]
+    #[derivative(Debug = "ignore")]
+    pub translations: Option<crate::localization::Translations>,
+    /// Texts of the messages which poise sends to users, for example in
+    /// [`crate::builtins::on_error`]. Override to translate them, see [`crate::MessageCatalog`]
+    #[derivative(Debug = "ignore")]
+    pub messages: std::sync::Arc<dyn crate::MessageCatalog>,
+    /// If set, every reply sent by a command invocation is recorded, so it can be edited later by
+    /// invocation ID. See [`crate::ResponseRegistry`]
+    pub response_registry: Option<std::sync::Arc<crate::ResponseRegistry>>,
     /// If `true`, changes behavior of guild_only command check to abort execution if the guild is
     /// not in cache.
     ///
@@ -93,6 +144,7 @@ where
         #[allow(deprecated)] // we need to set the listener field
         Self {
             commands: Vec::new(),
+            command_registry: Default::default(),
             on_error: |error| {
                 Box::pin(async move {
                     if let Err(e) = crate::builtins::on_error(error).await {
@@ -111,6 +163,13 @@ where
             ),
             reply_callback: None,
             manual_cooldowns: false,
+            cooldown_store: std::sync::Arc::new(crate::InMemoryCooldownStore::default()),
+            cooldown_override: None,
+            guild_settings: None,
+            #[cfg(feature = "fluent")]
+            translations: None,
+            messages: std::sync::Arc::new(crate::EnglishMessages),
+            response_registry: None,
             require_cache_for_guild_check: false,
             prefix_options: Default::default(),
             owners: Default::default(),
@@ -46,6 +46,8 @@ pub struct PrefixContext<'a, U, E> {
     pub data: &'a U,
     /// Custom user data carried across a single command invocation
     pub invocation_data: &'a tokio::sync::Mutex<Box<dyn std::any::Any + Send + Sync>>,
+    /// Locale of the invoking user, see [`PrefixFrameworkOptions::locale_resolver`]
+    pub locale: Option<&'a str>,
     /// How this command invocation was triggered
     pub trigger: MessageDispatchTrigger,
     /// The function that is called to execute the actual command
@@ -120,6 +122,21 @@ pub struct PrefixFrameworkOptions<U, E> {
             &'a U,
         ) -> BoxFuture<'a, Result<Option<(&'a str, &'a str)>, E>>,
     >,
+    /// Callback invoked on every prefix command invocation to determine the locale of the user,
+    /// for example from per-user or per-guild language preferences in your database. The result
+    /// is returned by [`crate::Context::locale`].
+    ///
+    /// Only invoked once the command was found, or for unknown commands if
+    /// [`Self::suggest_unknown_commands`] is enabled. Return one of the
+    /// [locales supported by Discord](https://discord.com/developers/docs/reference#locales),
+    /// like `de` or `en-US`; other locales are ignored with a warning.
+    ///
+    /// If this field is not set or returns `None`, the
+    /// [preferred locale](serenity::Guild::preferred_locale) of the guild is used, if the guild is
+    /// in cache. Errors are passed to [`crate::FrameworkError::LocaleResolver`]
+    #[derivative(Debug = "ignore")]
+    pub locale_resolver:
+        Option<fn(crate::PartialContext<'_, U, E>) -> BoxFuture<'_, Result<Option<String>, E>>>,
     /// Treat a bot mention (a ping) like a prefix
     pub mention_as_prefix: bool,
     /// If Some, the framework will react to message edits by editing the corresponding bot response
@@ -146,6 +163,17 @@ pub struct PrefixFrameworkOptions<U, E> {
     pub ignore_bots: bool,
     /// Whether command names should be compared case-insensitively.
     pub case_insensitive_commands: bool,
+    /// Whether [`crate::builtins::on_error`] should reply to unknown commands with suggestions
+    /// for what the user may have meant, like "Unknown command `~hlep`, did you mean `~help`?"
+    ///
+    /// Only commands the user could find in the help menu are suggested. Unknown commands
+    /// without any similar command are ignored.
+    pub suggest_unknown_commands: bool,
+    /// How command arguments are split into words, e.g. which quote characters are recognized.
+    /// Also used by [`crate::KeyValueArgs`] and named options
+    ///
+    /// See [`crate::Tokenizer`] for the available rules.
+    pub tokenizer: crate::Tokenizer,
     /* // TODO: implement
     /// Whether to invoke help command when someone sends a message with just a bot mention
     pub help_when_mentioned: bool,
@@ -167,6 +195,7 @@ impl<U, E> Default for PrefixFrameworkOptions<U, E> {
             additional_prefixes: Vec::new(),
             dynamic_prefix: None,
             stripped_dynamic_prefix: None,
+            locale_resolver: None,
             mention_as_prefix: true,
             edit_tracker: None,
             execute_untracked_edits: true,
@@ -174,6 +203,8 @@ impl<U, E> Default for PrefixFrameworkOptions<U, E> {
             execute_self_messages: false,
             ignore_bots: true,
             case_insensitive_commands: true,
+            suggest_unknown_commands: false,
+            tokenizer: crate::Tokenizer::default(),
             // help_when_mentioned: true,
             // help_commmand: None,
             // command_specific_help_commmand: None,
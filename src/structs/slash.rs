@@ -146,6 +146,14 @@ impl<U, E> ApplicationContext<'_, U, E> {
             .has_sent_initial_response
             .load(std::sync::atomic::Ordering::SeqCst)
         {
+            #[cfg(any(test, feature = "testing"))]
+            if let Some(recorder) = crate::testing::recorder(self.serenity_context).await {
+                recorder.record(crate::testing::RecordedAction::Defer { ephemeral });
+                self.has_sent_initial_response
+                    .store(true, std::sync::atomic::Ordering::SeqCst);
+                return Ok(());
+            }
+
             interaction
                 .create_response(
                     self.discord,
@@ -199,6 +207,38 @@ pub struct CommandParameterChoice {
     pub localizations: std::collections::HashMap<String, String>,
 }
 
+/// Additional information about a [`CommandParameter`], as generated by the [`crate::command`]
+/// macro from the parameter attributes
+///
+/// Kept in a separate struct so that new fields can be added without breaking code that creates
+/// [`CommandParameter`]s manually. Construct with `Default::default()`.
+#[derive(Clone, Debug, Default)]
+pub struct CommandParameterDetails {
+    /// Whether this parameter consumes the rest of the input, i.e. is marked `#[rest]`
+    /// (prefix-only)
+    pub rest: bool,
+    /// Whether this parameter is a bool that is only set when given, i.e. is marked `#[flag]` or
+    /// is a named option without value (prefix-only)
+    pub flag: bool,
+    /// If this parameter is a named option, its long name, used like `--name` (prefix-only)
+    pub long: Option<String>,
+    /// If this parameter is a named option, its optional short name, used like `-n` (prefix-only)
+    pub short: Option<char>,
+    /// Whether this parameter accepts any number of values, i.e. is a `Vec` (prefix-only)
+    pub variadic: bool,
+    /// Human-readable name of this parameter's type, e.g. `User` or `String`. Displayed in help
+    /// menus
+    pub type_name: &'static str,
+    /// If this parameter takes attachments, restrictions on the passed attachments
+    ///
+    /// In slash commands, `Vec` attachment parameters are registered as multiple attachment
+    /// options, see [`CommandParameter::create_as_slash_command_options`].
+    pub attachment_constraints: Option<crate::AttachmentConstraints>,
+    // Like #[non_exhaustive], but #[poise::command] still needs to be able to create an instance
+    #[doc(hidden)]
+    pub __non_exhaustive: (),
+}
+
 /// A single parameter of a [`crate::Command`]
 #[derive(Clone, derivative::Derivative)]
 #[derivative(Debug(bound = ""))]
@@ -213,6 +253,8 @@ pub struct CommandParameter<U, E> {
     pub description_localizations: std::collections::HashMap<String, String>,
     /// `true` is this parameter is required, `false` if it's optional or variadic
     pub required: bool,
+    /// Additional information about this parameter, mostly used by prefix commands and help menus
+    pub details: CommandParameterDetails,
     /// If this parameter is a channel, users can only enter these channel types in a slash command
     ///
     /// Prefix commands are currently unaffected by this
@@ -249,19 +291,49 @@ impl<U, E> CommandParameter<U, E> {
     /// Generates a slash command parameter builder from this [`CommandParameter`] instance. This
     /// can be used to register the command on Discord's servers
     pub fn create_as_slash_command_option(&self) -> Option<serenity::CreateCommandOption> {
+        self.create_numbered_slash_command_option(0)
+    }
+
+    /// Generates the builders of all slash command options this parameter is registered as
+    ///
+    /// Usually that's a single option like [`Self::create_as_slash_command_option`]. But since
+    /// Discord has no variadic options, a `Vec` attachment parameter (see
+    /// [`CommandParameterDetails::attachment_constraints`]) is registered as
+    /// [`crate::AttachmentConstraints::max_count`] attachment options, named `files`, `files_2`,
+    /// `files_3` and so on.
+    pub fn create_as_slash_command_options(&self) -> Option<Vec<serenity::CreateCommandOption>> {
+        let count = match &self.details.attachment_constraints {
+            Some(constraints) if self.details.variadic => constraints.slash_option_count(),
+            _ => 1,
+        };
+        (0..count)
+            .map(|i| self.create_numbered_slash_command_option(i))
+            .collect()
+    }
+
+    /// Generates the builder of the slash command option with the given index, see
+    /// [`Self::create_as_slash_command_options`]
+    fn create_numbered_slash_command_option(
+        &self,
+        index: usize,
+    ) -> Option<serenity::CreateCommandOption> {
+        let suffix = match index {
+            0 => String::new(),
+            _ => format!("_{}", index + 1),
+        };
         let mut b = serenity::CreateCommandOption::new(
             serenity::CommandOptionType::Unknown(0), // Will be overwritten by type_setter below
-            &self.name,
+            format!("{}{}", self.name, suffix),
             self.description
                 .as_deref()
                 .unwrap_or("A slash command parameter"),
         );
 
         b = b
-            .required(self.required)
+            .required(self.required && index == 0)
             .set_autocomplete(self.autocomplete_callback.is_some());
         for (locale, name) in &self.name_localizations {
-            b = b.name_localized(locale, name);
+            b = b.name_localized(locale, format!("{}{}", name, suffix));
         }
         for (locale, description) in &self.description_localizations {
             b = b.description_localized(locale, description);
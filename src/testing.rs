@@ -0,0 +1,484 @@
+//! Utilities to drive commands in tests, without connecting to the Discord gateway
+//!
+//! The centerpiece is [`TestHarness`]: it owns a set of [`crate::FrameworkOptions`] and a dummy
+//! [`serenity::Context`] that isn't connected to Discord. Synthetic messages and interactions can
+//! be injected with [`TestHarness::dispatch_message`] and [`TestHarness::dispatch_interaction`]
+//! and go through the normal dispatch code, including checks, cooldowns and error handling.
+//!
+//! Instead of being sent to Discord, every reply, defer, edit and deletion done by the framework
+//! is recorded and can be inspected with [`TestHarness::actions`] and [`TestHarness::replies`].
+//!
+//! ```rust,no_run
+//! # type Error = Box<dyn std::error::Error + Send + Sync>;
+//! #[poise::command(prefix_command, slash_command)]
+//! async fn ping(ctx: poise::Context<'_, (), Error>) -> Result<(), Error> {
+//!     ctx.say("Pong!").await?;
+//!     Ok(())
+//! }
+//!
+//! # async fn _test() {
+//! let harness = poise::testing::TestHarness::new(
+//!     poise::FrameworkOptions {
+//!         commands: vec![ping()],
+//!         prefix_options: poise::PrefixFrameworkOptions {
+//!             prefix: Some("~".into()),
+//!             ..Default::default()
+//!         },
+//!         ..Default::default()
+//!     },
+//!     (),
+//! )
+//! .await;
+//!
+//! harness.dispatch_message(harness.message("~ping")).await;
+//! harness.dispatch_interaction(harness.interaction("ping", vec![])).await;
+//!
+//! let replies = harness.replies();
+//! assert_eq!(replies.len(), 2);
+//! assert!(replies.iter().all(|r| r.content.as_deref() == Some("Pong!")));
+//! # }
+//! ```
+//!
+//! Only replies are intercepted. Other HTTP requests a command makes (e.g. fetching a member) are
+//! sent to an [`serenity::Http`] instance with an invalid token and will fail.
+
+use crate::serenity_prelude as serenity;
+use std::sync::Arc;
+
+/// Something the framework would have sent to Discord, captured by [`Recorder`]
+#[derive(Clone)]
+pub enum RecordedAction {
+    /// A reply was sent (a message for prefix commands, an interaction response or followup for
+    /// application commands)
+    Reply {
+        /// Index of this reply, as referenced by [`RecordedAction::Edit`] and
+        /// [`RecordedAction::Delete`]
+        index: usize,
+        /// The reply, after [`crate::CreateReply::complete_from_ctx`] has been applied
+        reply: crate::CreateReply,
+    },
+    /// An interaction response was deferred
+    Defer {
+        /// Whether the deferred response was marked ephemeral
+        ephemeral: bool,
+    },
+    /// A previously sent reply was edited via [`crate::ReplyHandle::edit`]
+    Edit {
+        /// Index of the edited reply
+        index: usize,
+        /// The new reply contents
+        reply: crate::CreateReply,
+    },
+    /// A previously sent reply was deleted via [`crate::ReplyHandle::delete`]
+    Delete {
+        /// Index of the deleted reply
+        index: usize,
+    },
+}
+
+/// Collects all replies, defers, edits and deletions sent through a [`serenity::Context`]
+///
+/// When a recorder is stored in the context's data map (which [`TestHarness`] does for you), the
+/// framework's reply functions record into it instead of sending HTTP requests.
+#[derive(Default)]
+pub struct Recorder {
+    /// All recorded actions in chronological order
+    actions: std::sync::Mutex<Vec<RecordedAction>>,
+    /// Number of replies recorded so far, used to assign reply indices
+    num_replies: std::sync::atomic::AtomicUsize,
+}
+
+/// Key under which [`Recorder`] is stored in [`serenity::Context::data`]
+struct RecorderKey;
+
+/// Set once any recorder was installed. Until then, [`recorder`] can skip locking the data map,
+/// which keeps the reply functions free of test-only overhead in production
+static ANY_RECORDER_INSTALLED: std::sync::atomic::AtomicBool =
+    std::sync::atomic::AtomicBool::new(false);
+
+impl serenity::TypeMapKey for RecorderKey {
+    type Value = Arc<Recorder>;
+}
+
+impl Recorder {
+    /// Stores the given recorder in the context's data map, which makes all framework replies
+    /// sent through this context be recorded
+    pub async fn install(self: Arc<Self>, ctx: &serenity::Context) {
+        ANY_RECORDER_INSTALLED.store(true, std::sync::atomic::Ordering::SeqCst);
+        ctx.data.write().await.insert::<RecorderKey>(self);
+    }
+
+    /// Returns all recorded actions in chronological order
+    pub fn actions(&self) -> Vec<RecordedAction> {
+        self.actions.lock().unwrap().clone()
+    }
+
+    /// Returns the contents of all recorded replies, in the order they were sent
+    ///
+    /// Edits are applied, i.e. if a reply was edited, its latest version is returned. Deleted
+    /// replies are still included.
+    pub fn replies(&self) -> Vec<crate::CreateReply> {
+        self.replies_by_index().into_values().collect()
+    }
+
+    /// Returns the latest version of the reply with the given index, if it was recorded since the
+    /// last [`Self::clear`]
+    pub fn reply(&self, index: usize) -> Option<crate::CreateReply> {
+        self.replies_by_index().remove(&index)
+    }
+
+    /// Replays the recorded replies and edits, keyed by reply index
+    fn replies_by_index(&self) -> std::collections::BTreeMap<usize, crate::CreateReply> {
+        let mut replies = std::collections::BTreeMap::new();
+        for action in &*self.actions.lock().unwrap() {
+            match action {
+                RecordedAction::Reply { index, reply } | RecordedAction::Edit { index, reply } => {
+                    replies.insert(*index, reply.clone());
+                }
+                _ => {}
+            }
+        }
+        replies
+    }
+
+    /// Forgets all recorded actions
+    ///
+    /// Reply indices keep counting up, so handles from before remain distinguishable. Edits of
+    /// replies from before are still recorded, but don't show up in [`Self::replies`].
+    pub fn clear(&self) {
+        self.actions.lock().unwrap().clear();
+    }
+
+    /// Records a new reply and returns its index
+    pub(crate) fn record_reply(&self, reply: crate::CreateReply) -> usize {
+        let index = self
+            .num_replies
+            .fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+        self.record(RecordedAction::Reply { index, reply });
+        index
+    }
+
+    /// Appends an action to the history
+    pub(crate) fn record(&self, action: RecordedAction) {
+        self.actions.lock().unwrap().push(action);
+    }
+}
+
+/// Returns the recorder installed in this context, if any
+pub(crate) async fn recorder(ctx: &serenity::Context) -> Option<Arc<Recorder>> {
+    if !ANY_RECORDER_INSTALLED.load(std::sync::atomic::Ordering::SeqCst) {
+        return None;
+    }
+    ctx.data.read().await.get::<RecorderKey>().cloned()
+}
+
+/// Builds a message object representing a recorded reply
+///
+/// Only the content is filled in; there's no way to convert the embed builders back into embeds.
+pub(crate) fn recorded_message(
+    index: usize,
+    reply: Option<crate::CreateReply>,
+) -> serenity::Message {
+    let mut msg = serenity::CustomMessage::new();
+    msg.id(serenity::MessageId::new(index as u64 + 1))
+        .channel_id(CHANNEL_ID)
+        .content(reply.and_then(|r| r.content).unwrap_or_default());
+    msg.build()
+}
+
+/// User ID of the simulated bot
+pub const BOT_ID: serenity::UserId = serenity::UserId::new(1000);
+/// User ID of the author of messages and interactions created by [`TestHarness`]
+pub const AUTHOR_ID: serenity::UserId = serenity::UserId::new(2000);
+/// Channel ID of messages and interactions created by [`TestHarness`]
+pub const CHANNEL_ID: serenity::ChannelId = serenity::ChannelId::new(3000);
+/// Prefix of the commands set up by [`TestHarness::with_prefix_commands`]
+pub const PREFIX: &str = "~";
+
+/// Owns everything needed to dispatch synthetic events onto framework commands, without a
+/// connection to Discord
+///
+/// See the [module docs](self) for an example.
+pub struct TestHarness<U, E> {
+    /// Framework configuration, including the commands under test
+    options: crate::FrameworkOptions<U, E>,
+    /// User data made available to commands
+    user_data: U,
+    /// Serenity context which isn't connected to Discord
+    serenity_context: serenity::Context,
+    /// Shard manager of a client that is never started
+    shard_manager: Arc<tokio::sync::Mutex<serenity::ShardManager>>,
+    /// Records everything that would have been sent to Discord
+    recorder: Arc<Recorder>,
+}
+
+impl<U: Send + Sync, E> TestHarness<U, E> {
+    /// Sets up the harness with the given framework options and user data
+    ///
+    /// Like [`crate::Framework`] does on startup, this fills in the qualified names of all
+    /// commands.
+    pub async fn new(mut options: crate::FrameworkOptions<U, E>, user_data: U) -> Self {
+        crate::set_qualified_names(&mut options.commands);
+
+        // create a one-off client to get the shard manager. The token is never validated because
+        // the client is never started
+        let client = serenity::Client::builder("testing", serenity::GatewayIntents::empty())
+            .await
+            .expect("failed to create dummy client");
+        let shard_manager = client.shard_manager.clone();
+        drop(client);
+
+        let shard_runner =
+            ::serenity::gateway::ShardRunner::new(::serenity::gateway::ShardRunnerOptions {
+                data: Arc::default(),
+                event_handlers: vec![],
+                raw_event_handlers: vec![],
+                framework: None,
+                manager: shard_manager.clone(),
+                #[cfg(feature = "cache")]
+                cache: Default::default(),
+                http: Arc::new(serenity::Http::new("testing")),
+            });
+        let serenity_context = serenity::Context {
+            data: Arc::new(tokio::sync::RwLock::new(serenity::TypeMap::new())),
+            shard: ::serenity::gateway::ShardMessenger::new(&shard_runner),
+            shard_id: Default::default(),
+            http: Arc::new(serenity::Http::new("testing")),
+            #[cfg(feature = "cache")]
+            cache: Default::default(),
+        };
+
+        let recorder = Arc::new(Recorder::default());
+        recorder.clone().install(&serenity_context).await;
+
+        Self {
+            options,
+            user_data,
+            serenity_context,
+            shard_manager,
+            recorder,
+        }
+    }
+
+    /// Sets up the harness with the given commands, which can be invoked with [`PREFIX`]
+    ///
+    /// All other framework configuration is taken from `options`, for example to test cooldowns
+    /// or owner checks.
+    pub async fn with_prefix_commands(
+        commands: Vec<crate::Command<U, E>>,
+        mut options: crate::FrameworkOptions<U, E>,
+        user_data: U,
+    ) -> Self {
+        options.commands = commands;
+        options.prefix_options.prefix = Some(PREFIX.into());
+        Self::new(options, user_data).await
+    }
+
+    /// Returns a view into the framework data, like [`crate::Framework`] would pass it to
+    /// [`crate::dispatch_event`]
+    pub fn framework(&self) -> crate::FrameworkContext<'_, U, E> {
+        crate::FrameworkContext {
+            bot_id: BOT_ID,
+            options: &self.options,
+            user_data: &self.user_data,
+            shard_manager: &self.shard_manager,
+        }
+    }
+
+    /// Returns the dummy serenity context that is passed to commands
+    pub fn serenity_context(&self) -> &serenity::Context {
+        &self.serenity_context
+    }
+
+    /// Returns the recorder that captures everything that would have been sent to Discord
+    pub fn recorder(&self) -> &Arc<Recorder> {
+        &self.recorder
+    }
+
+    /// Shorthand for [`Recorder::actions`]
+    pub fn actions(&self) -> Vec<RecordedAction> {
+        self.recorder.actions()
+    }
+
+    /// Shorthand for [`Recorder::replies`]
+    pub fn replies(&self) -> Vec<crate::CreateReply> {
+        self.recorder.replies()
+    }
+
+    /// Shorthand for [`Recorder::clear`]
+    pub fn clear(&self) {
+        self.recorder.clear();
+    }
+
+    /// Creates a guild-less message with the given content, sent by [`AUTHOR_ID`] in
+    /// [`CHANNEL_ID`]
+    ///
+    /// Use [`serenity::CustomMessage`] directly if you need more control.
+    pub fn message(&self, content: &str) -> serenity::Message {
+        let mut msg = serenity::CustomMessage::new();
+        msg.id(serenity::MessageId::new(1))
+            .author(test_user(AUTHOR_ID))
+            .channel_id(CHANNEL_ID)
+            .content(content)
+            .timestamp(serenity::Timestamp::now());
+        msg.build()
+    }
+
+    /// Creates a slash command interaction invoking the given command, sent by
+    /// [`AUTHOR_ID`] in [`CHANNEL_ID`]
+    ///
+    /// Subcommands are specified by separating names with spaces, e.g. `"config set"`. Option
+    /// types are inferred from the JSON value: strings, integers, floating point numbers and
+    /// booleans are supported.
+    pub fn interaction(
+        &self,
+        command: &str,
+        options: Vec<(&str, serenity::json::Value)>,
+    ) -> serenity::CommandInteraction {
+        let mut names = command.split_whitespace().collect::<Vec<_>>();
+        let name = names.remove(0);
+
+        let mut options = options
+            .into_iter()
+            .map(|(name, value)| {
+                let kind = match &value {
+                    serenity::json::Value::String(_) => 3,
+                    serenity::json::Value::Bool(_) => 5,
+                    serenity::json::Value::Number(n) if n.is_f64() => 10,
+                    serenity::json::Value::Number(_) => 4,
+                    other => panic!("unsupported option value in test interaction: {}", other),
+                };
+                serenity::json::json!({ "name": name, "type": kind, "value": value })
+            })
+            .collect::<Vec<_>>();
+        // Wrap options into subcommand (groups), innermost first
+        for (i, subcommand_name) in names.iter().enumerate().rev() {
+            let is_group = i + 1 < names.len();
+            options = vec![serenity::json::json!({
+                "name": subcommand_name,
+                "type": if is_group { 2 } else { 1 },
+                "options": options,
+            })];
+        }
+
+        let interaction = serenity::json::json!({
+            "id": "1",
+            "application_id": BOT_ID.get().to_string(),
+            "type": 2,
+            "data": {
+                "id": "1",
+                "name": name,
+                "type": 1,
+                "options": options,
+            },
+            "channel_id": CHANNEL_ID.get().to_string(),
+            "user": serenity::json::to_value(test_user(AUTHOR_ID))
+                .expect("failed to serialize test user"),
+            "token": "testing",
+            "version": 1,
+            "locale": "en-US",
+        });
+        serenity::json::from_value(interaction).expect("failed to build test interaction")
+    }
+
+    /// Dispatches a message onto the framework, as if it was received from the gateway
+    pub async fn dispatch_message(&self, msg: serenity::Message) {
+        let event = serenity::FullEvent::Message {
+            ctx: self.serenity_context.clone(),
+            new_message: msg,
+        };
+        crate::dispatch_event(self.framework(), &event).await;
+    }
+
+    /// Dispatches a slash command or context menu interaction onto the framework, as if it was
+    /// received from the gateway
+    pub async fn dispatch_interaction(&self, interaction: serenity::CommandInteraction) {
+        let event = serenity::FullEvent::InteractionCreate {
+            ctx: self.serenity_context.clone(),
+            interaction: serenity::Interaction::Command(interaction),
+        };
+        crate::dispatch_event(self.framework(), &event).await;
+    }
+}
+
+/// Prefix command action that replies with "Pong!", shared by the tests of this crate
+#[cfg(test)]
+pub(crate) fn pong(
+    ctx: crate::PrefixContext<'_, (), ()>,
+) -> crate::BoxFuture<'_, Result<(), crate::FrameworkError<'_, (), ()>>> {
+    Box::pin(async move {
+        let _ = crate::say_reply(ctx.into(), "Pong!").await;
+        Ok(())
+    })
+}
+
+/// Creates a non-bot user with the given ID
+fn test_user(id: serenity::UserId) -> serenity::User {
+    serenity::json::from_value(serenity::json::json!({
+        "id": id.get().to_string(),
+        "username": format!("user{}", id),
+        "discriminator": "0000",
+        "avatar": null,
+    }))
+    .expect("failed to build test user")
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn ping(
+        ctx: crate::PrefixContext<'_, (), ()>,
+    ) -> crate::BoxFuture<'_, Result<(), crate::FrameworkError<'_, (), ()>>> {
+        Box::pin(async move {
+            let handle = crate::say_reply(ctx.into(), "Pong!").await.map_err(|_| {
+                crate::FrameworkError::Command {
+                    error: (),
+                    ctx: ctx.into(),
+                }
+            })?;
+            let _ = handle
+                .edit(
+                    ctx.into(),
+                    crate::CreateReply::default().content("Pong again!"),
+                )
+                .await;
+            Ok(())
+        })
+    }
+
+    #[tokio::test]
+    async fn test_harness_records_replies() {
+        let command = crate::Command {
+            name: "ping".into(),
+            prefix_action: Some(ping),
+            ..Default::default()
+        };
+        let harness =
+            TestHarness::with_prefix_commands(vec![command], Default::default(), ()).await;
+
+        harness.dispatch_message(harness.message("~ping")).await;
+        harness
+            .dispatch_message(harness.message("not a command"))
+            .await;
+
+        let actions = harness.actions();
+        assert_eq!(actions.len(), 2);
+        assert!(matches!(actions[0], RecordedAction::Reply { index: 0, .. }));
+        assert!(matches!(actions[1], RecordedAction::Edit { index: 0, .. }));
+        assert_eq!(harness.replies()[0].content.as_deref(), Some("Pong again!"));
+
+        harness.clear();
+        assert!(harness.actions().is_empty());
+
+        // Indices keep counting after clearing, but the new reply is the only one left
+        harness.dispatch_message(harness.message("~ping")).await;
+        let actions = harness.actions();
+        assert!(matches!(actions[0], RecordedAction::Reply { index: 1, .. }));
+        assert!(matches!(actions[1], RecordedAction::Edit { index: 1, .. }));
+        let replies = harness.replies();
+        assert_eq!(replies.len(), 1);
+        assert_eq!(replies[0].content.as_deref(), Some("Pong again!"));
+        assert!(harness.recorder().reply(0).is_none());
+    }
+}
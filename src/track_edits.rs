@@ -3,30 +3,135 @@
 
 use crate::serenity_prelude as serenity;
 
+/// A tracked command invocation. Stores only what's needed to re-dispatch an edited invocation
+/// message and to find the bot response, instead of the whole message
+#[derive(Debug)]
+struct CachedInvocation {
+    /// Channel of the invocation message and the bot response
+    channel_id: serenity::ChannelId,
+    /// Guild of the invocation message, if not sent in DMs
+    guild_id: Option<serenity::GuildId>,
+    /// Author of the invocation message. Message update events don't necessarily include it
+    author: serenity::User,
+    /// When the invocation message was sent
+    timestamp: serenity::Timestamp,
+    /// When the invocation message was last edited, or sent if it wasn't edited yet
+    last_update: serenity::Timestamp,
+    /// The bot response, if one was sent yet
+    bot_response: Option<serenity::MessageId>,
+    /// See [`crate::Command::track_deletion`]
+    track_deletion: bool,
+    /// Key of this entry in [`EditTracker::recency`]
+    last_use: u64,
+}
+
+/// Numbers describing the state of an [`EditTracker`], see [`EditTracker::metrics`]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct EditTrackerMetrics {
+    /// Number of currently tracked invocations
+    pub entries: usize,
+    /// Number of invocations that were forgotten because the tracker was full
+    pub evictions: u64,
+}
+
 /// Stores messages and the associated bot responses in order to implement poise's edit tracking
 /// feature.
+///
+/// Invocations are forgotten when they're older than the configured duration, or when the maximum
+/// number of entries is reached, starting with the least recently used one.
 #[derive(Debug)]
 pub struct EditTracker {
     /// Duration after which cached messages can be purged
     max_duration: std::time::Duration,
+    /// Maximum number of tracked invocations
+    max_entries: usize,
     /// Cache, which stores invocation messages, and the corresponding bot response message if any
-    // TODO: change to `OrderedMap<MessageId, (Message, Option<serenity::Message>)>`?
-    cache: Vec<CachedInvocation>,
+    cache: std::collections::HashMap<serenity::MessageId, CachedInvocation>,
+    /// Keys of [`Self::cache`] ordered from least to most recently used
+    recency: std::collections::BTreeMap<u64, serenity::MessageId>,
+    /// Incremented on every use of an entry, to order [`Self::recency`]
+    use_counter: u64,
+    /// See [`EditTrackerMetrics::evictions`]
+    evictions: u64,
 }
 
 impl EditTracker {
-    /// Create an edit tracker which tracks messages for the specified duration.
+    /// Maximum number of tracked invocations of an edit tracker created with
+    /// [`Self::for_timespan`]
+    pub const DEFAULT_MAX_ENTRIES: usize = 10_000;
+
+    /// Create an edit tracker which tracks messages for the specified duration, and at most
+    /// [`Self::DEFAULT_MAX_ENTRIES`] invocations at once.
     ///
     /// Note: [`EditTracker`] will only purge messages outside the duration when [`Self::purge`]
     /// is called. If you supply the created [`EditTracker`] to [`crate::Framework`], the framework
     /// will take care of that by calling [`Self::purge`] periodically.
     pub fn for_timespan(duration: std::time::Duration) -> std::sync::Arc<std::sync::RwLock<Self>> {
+        Self::for_timespan_and_capacity(duration, Self::DEFAULT_MAX_ENTRIES)
+    }
+
+    /// Create an edit tracker which tracks messages for the specified duration, and at most
+    /// `max_entries` invocations at once. When full, the least recently used invocation is
+    /// forgotten. See [`Self::for_timespan`]
+    pub fn for_timespan_and_capacity(
+        duration: std::time::Duration,
+        max_entries: usize,
+    ) -> std::sync::Arc<std::sync::RwLock<Self>> {
         std::sync::Arc::new(std::sync::RwLock::new(Self {
             max_duration: duration,
-            cache: Vec::new(),
+            max_entries,
+            cache: std::collections::HashMap::new(),
+            recency: std::collections::BTreeMap::new(),
+            use_counter: 0,
+            evictions: 0,
         }))
     }
 
+    /// Returns the number of tracked invocations and how many were evicted so far
+    pub fn metrics(&self) -> EditTrackerMetrics {
+        EditTrackerMetrics {
+            entries: self.cache.len(),
+            evictions: self.evictions,
+        }
+    }
+
+    /// Marks the given invocation as most recently used
+    fn touch(&mut self, user_msg_id: serenity::MessageId) {
+        if let Some(invocation) = self.cache.get_mut(&user_msg_id) {
+            self.recency.remove(&invocation.last_use);
+            invocation.last_use = self.use_counter;
+            self.recency.insert(self.use_counter, user_msg_id);
+            self.use_counter += 1;
+        }
+    }
+
+    /// Starts tracking an invocation, evicting the least recently used ones if the tracker is full
+    fn insert(&mut self, user_msg_id: serenity::MessageId, mut invocation: CachedInvocation) {
+        invocation.last_use = self.use_counter;
+        self.recency.insert(self.use_counter, user_msg_id);
+        self.use_counter += 1;
+        if let Some(previous) = self.cache.insert(user_msg_id, invocation) {
+            self.recency.remove(&previous.last_use);
+        }
+
+        while self.cache.len() > self.max_entries {
+            let (&last_use, &evicted) = match self.recency.iter().next() {
+                Some(entry) => entry,
+                None => break,
+            };
+            self.recency.remove(&last_use);
+            self.cache.remove(&evicted);
+            self.evictions += 1;
+        }
+    }
+
+    /// Stops tracking an invocation
+    fn remove(&mut self, user_msg_id: serenity::MessageId) -> Option<CachedInvocation> {
+        let invocation = self.cache.remove(&user_msg_id)?;
+        self.recency.remove(&invocation.last_use);
+        Some(invocation)
+    }
+
     /// Returns a copy of a newly up-to-date cached message, or a brand new generated message when
     /// not in cache. Also returns a bool with `true` if this message was previously tracked
     ///
@@ -36,11 +141,7 @@ impl EditTracker {
         user_msg_update: &serenity::MessageUpdateEvent,
         ignore_edits_if_not_yet_responded: bool,
     ) -> Option<(serenity::Message, bool)> {
-        match self
-            .cache
-            .iter_mut()
-            .find(|invocation| invocation.user_msg.id == user_msg_update.id)
-        {
+        match self.cache.get_mut(&user_msg_update.id) {
             Some(invocation) => {
                 if ignore_edits_if_not_yet_responded && invocation.bot_response.is_none() {
                     return None;
@@ -55,8 +156,25 @@ impl EditTracker {
                     return None;
                 }
 
-                user_msg_update.apply_to_message(user_msg);
-                Some((user_msg.clone(), true))
+                let mut user_msg = serenity::CustomMessage::new();
+                user_msg
+                    .id(user_msg_update.id)
+                    .channel_id(invocation.channel_id)
+                    .author(invocation.author.clone())
+                    .timestamp(invocation.timestamp);
+                if let Some(guild_id) = invocation.guild_id {
+                    user_msg.guild_id(guild_id);
+                }
+                let mut user_msg = user_msg.build();
+                user_msg_update.apply_to_message(&mut user_msg);
+
+                invocation.last_update = user_msg.edited_timestamp.unwrap_or(user_msg.timestamp);
+                if let Some(author) = &user_msg_update.author {
+                    invocation.author = author.clone();
+                }
+                self.touch(user_msg_update.id);
+
+                Some((user_msg, true))
             }
             None => {
                 if ignore_edits_if_not_yet_responded {
@@ -72,15 +190,13 @@ impl EditTracker {
     /// Removes this command invocation from the cache and returns the associated bot response,
     /// if the command invocation is cached, and it has an associated bot response, and the command
     /// is marked track_deletion
+    ///
+    /// The bot response is in the same channel as the deleted message.
     pub fn process_message_delete(
         &mut self,
         deleted_message_id: serenity::MessageId,
-    ) -> Option<serenity::Message> {
-        let invocation = self.cache.remove(
-            self.cache
-                .iter()
-                .position(|invocation| invocation.user_msg.id == deleted_message_id)?,
-        );
+    ) -> Option<serenity::MessageId> {
+        let invocation = self.remove(deleted_message_id)?;
         if invocation.track_deletion {
             invocation.bot_response
         } else {
@@ -91,26 +207,26 @@ impl EditTracker {
     /// Forget all of the messages that are older than the specified duration.
     pub fn purge(&mut self) {
         let max_duration = self.max_duration;
-        self.cache.retain(|invocation| {
-            let last_update = invocation
-                .user_msg
-                .edited_timestamp
-                .unwrap_or(invocation.user_msg.timestamp);
-            let age = serenity::Timestamp::now().unix_timestamp() - last_update.unix_timestamp();
-            age < max_duration.as_secs() as i64
+        let now = serenity::Timestamp::now().unix_timestamp();
+        let recency = &mut self.recency;
+        self.cache.retain(|_, invocation| {
+            let age = now - invocation.last_update.unix_timestamp();
+            let keep = age < max_duration.as_secs() as i64;
+            if !keep {
+                recency.remove(&invocation.last_use);
+            }
+            keep
         });
     }
 
     /// Given a message by a user, find the corresponding bot response, if one exists and is cached.
+    ///
+    /// The bot response is in the same channel as the user message.
     pub fn find_bot_response(
         &self,
         user_msg_id: serenity::MessageId,
-    ) -> Option<&serenity::Message> {
-        let invocation = self
-            .cache
-            .iter()
-            .find(|invocation| invocation.user_msg.id == user_msg_id)?;
-        invocation.bot_response.as_ref()
+    ) -> Option<serenity::MessageId> {
+        self.cache.get(&user_msg_id)?.bot_response
     }
 
     /// Notify the [`EditTracker`] that the given user message should be associated with the given
@@ -118,21 +234,19 @@ impl EditTracker {
     pub fn set_bot_response(
         &mut self,
         user_msg: &serenity::Message,
-        bot_response: serenity::Message,
+        bot_response: serenity::MessageId,
         track_deletion: bool,
     ) {
-        if let Some(invocation) = self
-            .cache
-            .iter_mut()
-            .find(|invocation| invocation.user_msg.id == user_msg.id)
-        {
-            invocation.bot_response = Some(bot_response);
-        } else {
-            self.cache.push(CachedInvocation {
-                user_msg: user_msg.clone(),
-                bot_response: Some(bot_response),
-                track_deletion,
-            });
+        match self.cache.get_mut(&user_msg.id) {
+            Some(invocation) => {
+                invocation.bot_response = Some(bot_response);
+                self.touch(user_msg.id);
+            }
+            None => {
+                let mut invocation = CachedInvocation::new(user_msg, track_deletion);
+                invocation.bot_response = Some(bot_response);
+                self.insert(user_msg.id, invocation);
+            }
         }
     }
 
@@ -140,16 +254,69 @@ impl EditTracker {
     /// invocation message (e.g. removing embeds), we don't accidentally treat it as an
     /// `execute_untracked_edits` situation and start an infinite loop
     pub fn track_command(&mut self, user_msg: &serenity::Message, track_deletion: bool) {
-        if !self
-            .cache
-            .iter()
-            .any(|invocation| invocation.user_msg.id == user_msg.id)
-        {
-            self.cache.push(CachedInvocation {
-                user_msg: user_msg.clone(),
-                bot_response: None,
-                track_deletion,
-            });
+        if !self.cache.contains_key(&user_msg.id) {
+            self.insert(user_msg.id, CachedInvocation::new(user_msg, track_deletion));
+        }
+    }
+}
+
+impl CachedInvocation {
+    /// Creates an entry without bot response for the given invocation message
+    fn new(user_msg: &serenity::Message, track_deletion: bool) -> Self {
+        Self {
+            channel_id: user_msg.channel_id,
+            guild_id: user_msg.guild_id,
+            author: user_msg.author.clone(),
+            timestamp: user_msg.timestamp,
+            last_update: user_msg.edited_timestamp.unwrap_or(user_msg.timestamp),
+            bot_response: None,
+            track_deletion,
+            last_use: 0,
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn message(id: u64) -> serenity::Message {
+        let mut msg = serenity::CustomMessage::new();
+        msg.id(serenity::MessageId::new(id))
+            .timestamp(serenity::Timestamp::now());
+        msg.build()
+    }
+
+    #[test]
+    fn test_lru_eviction() {
+        let tracker =
+            EditTracker::for_timespan_and_capacity(std::time::Duration::from_secs(3600), 2);
+        let mut tracker = tracker.write().unwrap();
+
+        tracker.track_command(&message(1), false);
+        tracker.track_command(&message(2), false);
+        // Using the first invocation makes the second one the least recently used
+        tracker.set_bot_response(&message(1), serenity::MessageId::new(10), true);
+        tracker.track_command(&message(3), false);
+
+        assert_eq!(
+            tracker.metrics(),
+            EditTrackerMetrics {
+                entries: 2,
+                evictions: 1
+            }
+        );
+        assert_eq!(
+            tracker.find_bot_response(serenity::MessageId::new(1)),
+            Some(serenity::MessageId::new(10))
+        );
+        assert!(tracker
+            .process_message_delete(serenity::MessageId::new(2))
+            .is_none());
+        assert_eq!(
+            tracker.process_message_delete(serenity::MessageId::new(1)),
+            Some(serenity::MessageId::new(10))
+        );
+        assert_eq!(tracker.metrics().entries, 1);
+    }
+}
@@ -51,3 +51,28 @@ impl<K, V> IntoIterator for OrderedMap<K, V> {
         self.0.into_iter()
     }
 }
+
+/// Yields the given qualified command name and the qualified names of all its parent commands,
+/// outermost first: `config set value` yields `config`, `config set`, `config set value`
+pub fn qualified_name_prefixes(qualified_name: &str) -> impl Iterator<Item = &str> {
+    qualified_name
+        .match_indices(' ')
+        .map(|(i, _)| i)
+        .chain(std::iter::once(qualified_name.len()))
+        .map(move |end| &qualified_name[..end])
+}
+
+/// Locales supported by Discord, see <https://discord.com/developers/docs/reference#locales>
+const DISCORD_LOCALES: &[&str] = &[
+    "id", "da", "de", "en-GB", "en-US", "es-ES", "es-419", "fr", "hr", "it", "lt", "hu", "nl",
+    "no", "pl", "pt-BR", "ro", "fi", "sv-SE", "vi", "tr", "cs", "el", "bg", "ru", "uk", "hi", "th",
+    "zh-CN", "ja", "zh-TW", "ko",
+];
+
+/// Returns the `'static` version of the given locale, or None if Discord doesn't support it
+pub fn discord_locale(locale: &str) -> Option<&'static str> {
+    DISCORD_LOCALES
+        .iter()
+        .find(|&&known| known == locale)
+        .copied()
+}
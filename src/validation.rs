@@ -0,0 +1,369 @@
+//! Validation of parsed parameter values against the `#[min]`, `#[max]`, `#[min_length]`,
+//! `#[max_length]`, `#[regex]` and `#[validate]` attributes of the [`crate::command`] macro
+//!
+//! Discord enforces some of these attributes itself for slash commands, but not for prefix
+//! commands or for types which are sent as strings, like [`crate::Duration`]. So poise checks
+//! them after parsing, in both prefix and slash commands.
+
+/// Why a parameter value was rejected, see [`crate::FrameworkError::ArgumentValidation`]
+#[derive(Debug)]
+pub enum ArgumentValidationError {
+    /// The value is outside the `#[min]` and `#[max]` bounds of the parameter
+    OutOfRange {
+        /// Minimum value of the parameter, if any
+        min: Option<String>,
+        /// Maximum value of the parameter, if any
+        max: Option<String>,
+    },
+    /// The value is shorter than `#[min_length]` or longer than `#[max_length]`
+    InvalidLength {
+        /// Length of the value in characters
+        length: usize,
+        /// Minimum length of the parameter, if any
+        min_length: Option<usize>,
+        /// Maximum length of the parameter, if any
+        max_length: Option<usize>,
+    },
+    /// The value doesn't match the `#[regex]` pattern of the parameter
+    PatternMismatch {
+        /// The regular expression
+        pattern: &'static str,
+    },
+    /// The `#[validate]` function of the parameter returned an error
+    Custom(Box<dyn std::error::Error + Send + Sync>),
+}
+
+impl std::fmt::Display for ArgumentValidationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::OutOfRange {
+                min: Some(min),
+                max: Some(max),
+            } => write!(f, "Must be between {} and {}", min, max),
+            Self::OutOfRange {
+                min: Some(min),
+                max: None,
+            } => write!(f, "Must be at least {}", min),
+            Self::OutOfRange {
+                min: None,
+                max: Some(max),
+            } => write!(f, "Must be at most {}", max),
+            Self::OutOfRange {
+                min: None,
+                max: None,
+            } => f.write_str("Out of range"),
+            Self::InvalidLength {
+                length,
+                min_length,
+                max_length,
+            } => {
+                match (min_length, max_length) {
+                    (Some(min), Some(max)) => {
+                        write!(f, "Must be between {} and {} characters", min, max)?
+                    }
+                    (Some(min), None) => write!(f, "Must be at least {} characters", min)?,
+                    (None, Some(max)) => write!(f, "Must be at most {} characters", max)?,
+                    (None, None) => f.write_str("Invalid length")?,
+                }
+                write!(f, " (is {})", length)
+            }
+            Self::PatternMismatch { pattern } => write!(f, "Must match `{}`", pattern),
+            Self::Custom(error) => write!(f, "{}", error),
+        }
+    }
+}
+
+impl std::error::Error for ArgumentValidationError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Custom(error) => Some(&**error),
+            _ => None,
+        }
+    }
+}
+
+/// Implemented for the parameter types which support `#[min]` and `#[max]` bounds
+#[doc(hidden)]
+pub trait BoundedArgument {
+    /// Type of the bounds which the [`crate::command`] macro passes: `f64` for numbers, whose
+    /// bounds are parsed at compile time, and the literal string for other types
+    type Bound: Copy;
+
+    /// Checks that the value is within the given bounds. On violation, returns the offending
+    /// value as string
+    fn check_bounds(
+        &self,
+        min: Option<Self::Bound>,
+        max: Option<Self::Bound>,
+    ) -> Result<(), (String, ArgumentValidationError)>;
+}
+
+impl<T: BoundedArgument> BoundedArgument for Option<T> {
+    type Bound = T::Bound;
+
+    fn check_bounds(
+        &self,
+        min: Option<Self::Bound>,
+        max: Option<Self::Bound>,
+    ) -> Result<(), (String, ArgumentValidationError)> {
+        match self {
+            Some(value) => value.check_bounds(min, max),
+            None => Ok(()),
+        }
+    }
+}
+
+impl<T: BoundedArgument> BoundedArgument for Vec<T> {
+    type Bound = T::Bound;
+
+    fn check_bounds(
+        &self,
+        min: Option<Self::Bound>,
+        max: Option<Self::Bound>,
+    ) -> Result<(), (String, ArgumentValidationError)> {
+        self.iter()
+            .try_for_each(|value| value.check_bounds(min, max))
+    }
+}
+
+/// Checks that `value` is within the bounds. On violation, returns `input` as string
+fn check_range<T: PartialOrd + std::fmt::Display>(
+    value: T,
+    min: Option<T>,
+    max: Option<T>,
+    input: &impl std::fmt::Display,
+) -> Result<(), (String, ArgumentValidationError)> {
+    let too_small = min.as_ref().map_or(false, |min| value < *min);
+    let too_large = max.as_ref().map_or(false, |max| value > *max);
+    if too_small || too_large {
+        return Err((
+            input.to_string(),
+            ArgumentValidationError::OutOfRange {
+                min: min.map(|x| x.to_string()),
+                max: max.map(|x| x.to_string()),
+            },
+        ));
+    }
+    Ok(())
+}
+
+/// Implements [`BoundedArgument`] for number types
+macro_rules! impl_bounded_number {
+    ($($t:ty)*) => { $(
+        impl BoundedArgument for $t {
+            type Bound = f64;
+
+            fn check_bounds(
+                &self,
+                min: Option<f64>,
+                max: Option<f64>,
+            ) -> Result<(), (String, ArgumentValidationError)> {
+                check_range(*self as f64, min, max, self)
+            }
+        }
+    )* };
+}
+// Like Discord, compare numbers with f64 precision so that any number literal can be used as bound
+impl_bounded_number!(i8 i16 i32 i64 isize u8 u16 u32 u64 usize f32 f64);
+
+/// Implements [`BoundedArgument`] by parsing the bound strings with `FromStr`
+macro_rules! impl_bounded_parse {
+    ($($t:ty)*) => { $(
+        impl BoundedArgument for $t {
+            type Bound = &'static str;
+
+            fn check_bounds(
+                &self,
+                min: Option<&'static str>,
+                max: Option<&'static str>,
+            ) -> Result<(), (String, ArgumentValidationError)> {
+                // Parsed on every check, so that relative bounds like `#[max = "in 1d"]` move
+                // along with the current time
+                let parse_bound = |bound: Option<&str>, attribute: &str| {
+                    bound.map(|bound| {
+                        bound.parse::<$t>().unwrap_or_else(|_| {
+                            let type_name = stringify!($t);
+                            panic!("invalid #[{}] bound `{}` for {}", attribute, bound, type_name)
+                        })
+                    })
+                };
+                check_range(*self, parse_bound(min, "min"), parse_bound(max, "max"), self)
+            }
+        }
+    )* };
+}
+impl_bounded_parse!(crate::Duration crate::Timestamp);
+
+/// Implemented for the parameter types which support `#[min_length]`, `#[max_length]` and
+/// `#[regex]`
+#[doc(hidden)]
+pub trait TextArgument {
+    /// Returns the strings that were passed to the parameter
+    fn texts(&self) -> Vec<&str>;
+}
+
+impl TextArgument for String {
+    fn texts(&self) -> Vec<&str> {
+        vec![self]
+    }
+}
+
+impl TextArgument for crate::CodeBlock {
+    fn texts(&self) -> Vec<&str> {
+        vec![&self.code]
+    }
+}
+
+impl<T: TextArgument> TextArgument for Option<T> {
+    fn texts(&self) -> Vec<&str> {
+        self.as_ref().map_or_else(Vec::new, |x| x.texts())
+    }
+}
+
+impl<T: TextArgument> TextArgument for Vec<T> {
+    fn texts(&self) -> Vec<&str> {
+        self.iter().flat_map(|x| x.texts()).collect()
+    }
+}
+
+/// Checks the value of a parameter against its `#[min]` and `#[max]` bounds
+///
+/// Mainly used in the desugared [`crate::command`] macro
+#[doc(hidden)]
+pub fn check_argument_bounds<'a, U, E, T: BoundedArgument>(
+    ctx: crate::Context<'a, U, E>,
+    parameter_index: usize,
+    value: &T,
+    min: Option<T::Bound>,
+    max: Option<T::Bound>,
+) -> Result<(), crate::FrameworkError<'a, U, E>> {
+    value
+        .check_bounds(min, max)
+        .map_err(|(input, error)| validation_error(ctx, parameter_index, Some(input), error))
+}
+
+/// Checks the value of a parameter against its `#[min_length]` and `#[max_length]`
+///
+/// Mainly used in the desugared [`crate::command`] macro
+#[doc(hidden)]
+pub fn check_argument_length<'a, U, E>(
+    ctx: crate::Context<'a, U, E>,
+    parameter_index: usize,
+    value: &impl TextArgument,
+    min_length: Option<usize>,
+    max_length: Option<usize>,
+) -> Result<(), crate::FrameworkError<'a, U, E>> {
+    for text in value.texts() {
+        let length = text.chars().count();
+        if min_length.map_or(false, |min| length < min)
+            || max_length.map_or(false, |max| length > max)
+        {
+            let error = ArgumentValidationError::InvalidLength {
+                length,
+                min_length,
+                max_length,
+            };
+            return Err(validation_error(
+                ctx,
+                parameter_index,
+                Some(text.to_owned()),
+                error,
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Checks the value of a parameter against its `#[regex]` pattern, which must match the entire
+/// value. Compiled patterns are cached. The [`crate::command`] macro rejects invalid patterns at
+/// compile time
+///
+/// Mainly used in the desugared [`crate::command`] macro
+#[doc(hidden)]
+pub fn check_argument_regex<'a, U, E>(
+    ctx: crate::Context<'a, U, E>,
+    parameter_index: usize,
+    value: &impl TextArgument,
+    pattern: &'static str,
+) -> Result<(), crate::FrameworkError<'a, U, E>> {
+    static CACHE: once_cell::sync::Lazy<
+        std::sync::Mutex<std::collections::HashMap<&'static str, regex::Regex>>,
+    > = once_cell::sync::Lazy::new(Default::default);
+
+    let regex = CACHE
+        .lock()
+        .unwrap()
+        .entry(pattern)
+        .or_insert_with(|| {
+            regex::Regex::new(&format!("^(?:{})$", pattern))
+                .unwrap_or_else(|e| panic!("invalid #[regex] pattern `{}`: {}", pattern, e))
+        })
+        .clone();
+
+    for text in value.texts() {
+        if !regex.is_match(text) {
+            let error = ArgumentValidationError::PatternMismatch { pattern };
+            return Err(validation_error(
+                ctx,
+                parameter_index,
+                Some(text.to_owned()),
+                error,
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Converts the result of a parameter's `#[validate]` function into a framework error
+///
+/// Mainly used in the desugared [`crate::command`] macro
+#[doc(hidden)]
+pub fn check_argument_validator<'a, U, E>(
+    ctx: crate::Context<'a, U, E>,
+    parameter_index: usize,
+    result: Result<(), impl Into<Box<dyn std::error::Error + Send + Sync>>>,
+) -> Result<(), crate::FrameworkError<'a, U, E>> {
+    result.map_err(|error| {
+        let error = ArgumentValidationError::Custom(error.into());
+        validation_error(ctx, parameter_index, None, error)
+    })
+}
+
+/// Creates a [`crate::FrameworkError::ArgumentValidation`] for the parameter at the given index
+fn validation_error<'a, U, E>(
+    ctx: crate::Context<'a, U, E>,
+    parameter_index: usize,
+    input: Option<String>,
+    error: ArgumentValidationError,
+) -> crate::FrameworkError<'a, U, E> {
+    crate::FrameworkError::ArgumentValidation {
+        error,
+        input,
+        parameter: &ctx.command().parameters[parameter_index].name,
+        ctx,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_check_bounds() {
+        assert!(5u32.check_bounds(Some(1.0), Some(10.0)).is_ok());
+        assert!(0.5f32.check_bounds(Some(1.0), None).is_err());
+        assert!(None::<u8>.check_bounds(Some(1.0), None).is_ok());
+
+        let (input, error) = vec![5000u32].check_bounds(None, Some(100.0)).unwrap_err();
+        assert_eq!(input, "5000");
+        assert_eq!(error.to_string(), "Must be at most 100");
+
+        let duration = |s: &str| s.parse::<crate::Duration>().unwrap();
+        assert!(duration("10m").check_bounds(Some("1m"), Some("1h")).is_ok());
+        let (input, error) = Some(duration("2d"))
+            .check_bounds(Some("1m"), Some("28h"))
+            .unwrap_err();
+        assert_eq!(input, "2d");
+        assert_eq!(error.to_string(), "Must be between 1m and 1d 4h");
+    }
+}